@@ -1,5 +1,6 @@
 use anyhow::Context;
 use std::{
+    borrow::Cow,
     cmp::Ordering,
     collections::{HashMap, VecDeque},
     io::{self, Read, Write},
@@ -13,20 +14,27 @@ use zip::write::SimpleFileOptions;
 use crate::{
     colorscheme::ColorScheme,
     docx_document::{
-        self, Color, ParagraphProperties, SectrOfProperties, SpacingProperties, TextNode,
-        TextProperties,
+        self,
+        numbering::{ListLabel, NumberingCounters},
+        Bookmark, Border, BreakType, Color, Comment, HeaderFooterReferences, Hyperlink, Image,
+        InlineImage, LevelSuffix, NoteKind, NoteMark, NumberingLevel, Orientation, PageType,
+        ParagraphProperties, Revision, RevisionKind, SectrOfProperties, SpacingProperties,
+        StyleSheet, TabAlignment, TabLeader, TabStop, TableCell, TableProperties, TableRow,
+        TextNode, TextProperties, ThemeFonts, VerticalAlign, VerticalMerge,
     },
     draw::DrawState,
     font, math,
-    primitives::{PlainTextProperties, Primitive, PrimitiveProperties},
-    state::State,
+    primitives::{ImageProperties, PlainTextProperties, Primitive, PrimitiveProperties},
+    state::{self, State},
     traits::AsAnyhow,
 };
 
 #[derive(Debug)]
 pub struct DocumentDraw {
     pub paragraphs: Vec<Paragraph>,
+    pub tables: Vec<Table>,
     pub fonts: HashMap<FontIdx, rusttype::Font<'static>>,
+    pub images: HashMap<String, Arc<Image>>,
     pub scroll: f32,
     pub scale: f32,
     pub pages: Vec<Page>,
@@ -35,6 +43,24 @@ pub struct DocumentDraw {
     pub cursor: Cursor,
     pub cursor_prims: Vec<Primitive>,
     pub sect_properties: SectrOfProperties,
+    pub headers_footers: HashMap<String, Vec<docx_document::DocxNode>>,
+    pub even_and_odd_headers: bool,
+    pub default_tab_stop: Option<f32>,
+    pub do_not_expand_shift_return: bool,
+    /// Header and footer of each page in `pages`.
+    pub page_decorations: Vec<PageDecoration>,
+    /// Footnotes and endnotes in the order they are referred to.
+    pub notes: Vec<Note>,
+    pub note_separators: Vec<Primitive>,
+    /// Cursor positions links were followed from, the last one first to go
+    /// back to.
+    pub link_history: Vec<CursorPos>,
+    /// Comments of the document and the replies to them.
+    pub comments: Vec<Comment>,
+    /// Styles the properties of the paragraphs and runs are resolved from.
+    /// Only what differs from them is saved as direct formatting.
+    pub styles: StyleSheet,
+    pub theme_fonts: ThemeFonts,
 }
 
 #[derive(Debug)]
@@ -61,7 +87,37 @@ pub struct FontIdx {
 #[derive(Debug)]
 pub struct Page {
     pub page_properties: PageProperties,
+    /// Index of the section the page starts in, see `DocumentDraw::sections`.
+    pub section: usize,
     pub primitive: Primitive,
+    /// Lines drawn between the columns of the page.
+    pub separators: Vec<Primitive>,
+}
+
+/// Header and footer drawn on one page. Each page lays them out at its own
+/// position, so every page keeps its own paragraphs.
+#[derive(Debug, Default)]
+pub struct PageDecoration {
+    pub header: Option<HeaderFooter>,
+    pub footer: Option<HeaderFooter>,
+}
+
+#[derive(Debug)]
+pub struct HeaderFooter {
+    /// Relationship id of the `header*.xml`/`footer*.xml` part.
+    pub part: String,
+    pub paragraphs: Vec<Paragraph>,
+}
+
+/// Footnote body drawn at the bottom of the page its reference is on, or
+/// endnote body drawn after the document.
+#[derive(Debug)]
+pub struct Note {
+    pub kind: NoteKind,
+    pub id: i64,
+    pub paragraphs: Vec<Paragraph>,
+    /// False for a footnote whose reference is gone, which isn't drawn.
+    pub laid_out: bool,
 }
 
 #[derive(Debug)]
@@ -69,6 +125,36 @@ pub struct Paragraph {
     pub properties: docx_document::ParagraphProperties,
     pub words: Vec<Word>,
     pub lines: Vec<Line>,
+    pub cell: Option<CellRef>,
+    pub label: Option<ParagraphLabel>,
+}
+
+/// Bullet or number drawn in front of the first line of a list paragraph.
+#[derive(Debug)]
+pub struct ParagraphLabel {
+    pub word: Word,
+    pub level: NumberingLevel,
+}
+
+/// Table layout data. Cell paragraphs live in `DocumentDraw::paragraphs`
+/// and point back here with a `CellRef`, so `rows` carry no content. A
+/// table nested in a cell comes after the table it is in, and its
+/// paragraphs among that cell's paragraphs in reading order.
+#[derive(Debug)]
+pub struct Table {
+    pub properties: TableProperties,
+    pub grid: Vec<f32>,
+    pub rows: Vec<TableRow>,
+    pub primitives: Vec<Primitive>,
+    /// Cell the table is nested in.
+    pub parent: Option<CellRef>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct CellRef {
+    pub table: usize,
+    pub row: usize,
+    pub cell: usize,
 }
 
 #[derive(Debug, Clone)]
@@ -78,6 +164,8 @@ pub struct Line {
     widht_with_spacing: f32,
     last_scale: f32,
     range: Range<usize>,
+    /// Width and leader of every tab of the line.
+    tabs: Vec<(f32, TabLeader)>,
 }
 
 #[derive(Debug, Default)]
@@ -90,8 +178,67 @@ pub struct Word {
 pub struct GlyphsView {
     pub word_range: Range<usize>,
     pub properties: TextProperties,
+    /// Set for a picture, which takes the place of one object replacement
+    /// character in the word.
+    pub image: Option<InlineImage>,
+    /// Set for a note mark, drawn raised and smaller.
+    pub note: Option<NoteMark>,
+    /// Set for a break, which ends the line it is on.
+    pub br: Option<BreakType>,
+    pub link: Option<Hyperlink>,
+    pub revision: Option<Revision>,
+    /// Ids of the comments whose range the view is in.
+    pub comments: Vec<i64>,
     pub glyphs: Vec<rusttype::PositionedGlyph<'static>>,
     pub primitive: Primitive,
+    /// Shading, highlight, underline and strikethrough of the text.
+    pub decorations: Vec<Primitive>,
+}
+
+/// Horizontal indentation and tab stops of a paragraph's lines, relative to
+/// the content rect. `label` is where a list label starts on the first line.
+#[derive(Debug, Clone, Default)]
+struct LineIndent {
+    left: f32,
+    right: f32,
+    first_line: f32,
+    label: f32,
+    tabs: Vec<TabStop>,
+    default_tab: f32,
+}
+
+impl LineIndent {
+    fn start(&self, line_idx: usize) -> f32 {
+        if line_idx == 0 {
+            self.left + self.first_line
+        } else {
+            self.left
+        }
+    }
+
+    fn width(&self, ctx: &DrawStateCtx, line_idx: usize) -> f32 {
+        ctx.page_content_rect.width() - self.start(line_idx) - self.right
+    }
+
+    /// Width of a tab at `x` followed by `segment` wide text up to the next
+    /// tab, of which `decimal` is before the decimal point.
+    fn tab_width(&self, x: f32, (segment, decimal): (f32, f32)) -> (f32, TabLeader) {
+        let stop = self.tabs.iter().find(|stop| {
+            stop.position > x && !matches!(stop.alignment, TabAlignment::Clear | TabAlignment::Bar)
+        });
+        let Some(stop) = stop else {
+            let next = ((x / self.default_tab).floor() + 1.) * self.default_tab;
+            return ((next - x).max(0.), TabLeader::None);
+        };
+
+        let end = match stop.alignment {
+            TabAlignment::Center => stop.position - segment / 2.,
+            TabAlignment::End => stop.position - segment,
+            TabAlignment::Decimal => stop.position - decimal,
+            _ => stop.position,
+        };
+        ((end - x).max(0.), stop.leader)
+    }
 }
 
 struct DrawStateCtx {
@@ -101,6 +248,67 @@ struct DrawStateCtx {
     page_rect: math::Rectangle,
     v_width: f32,
     scale: f32,
+    /// Page, index in `DocumentDraw::notes` and reserved height of every
+    /// footnote laid out so far.
+    footnotes: Vec<(usize, usize, f32)>,
+    section: usize,
+    /// Distance between the default tab stops.
+    default_tab_stop: f32,
+    /// Whether justified lines ended by a text wrapping break stay ragged.
+    do_not_expand_shift_return: bool,
+    /// Column of `page_properties` that `page_content_rect` is in.
+    column: usize,
+    /// Top of the columns on the current page, below the content of the
+    /// section before for a continuous section.
+    column_top: f32,
+    /// Bottom of the content of the columns left on the current page.
+    columns_bottom: f32,
+    /// Page bottom for the cells of a row split over pages, past which their
+    /// lines go on the next page.
+    cell_page_bottom: Option<f32>,
+}
+
+impl DrawStateCtx {
+    /// Height of the content of a whole page.
+    fn page_height(&self) -> f32 {
+        (self.page_rect.height()
+            - (self.page_properties.paddings.top + self.page_properties.paddings.bottom)
+                * self.scale)
+            .max(0.)
+    }
+
+    /// Whether nothing is laid out in the current column yet.
+    fn at_page_top(&self) -> bool {
+        self.page_content_rect.y() <= self.column_top + 0.5
+    }
+
+    /// Rect of `column` on the current page from the top of the columns down
+    /// to `bottom`.
+    fn column_rect(&self, column: usize, bottom: f32) -> math::Rectangle {
+        let content_rect = self
+            .page_rect
+            .add_paddings(self.page_properties.paddings * self.scale);
+        let (start, width) = self
+            .page_properties
+            .columns
+            .get(column)
+            .map_or((0., content_rect.width()), |&(start, width)| {
+                (start * self.scale, width * self.scale)
+            });
+        math::Rectangle::new(
+            (content_rect.x() + start, self.column_top),
+            (width, (bottom - self.column_top).max(0.)),
+        )
+    }
+
+    /// Start the columns of a continuous section below all the columns of the
+    /// one before, in the first of them.
+    fn start_below_columns(&mut self) {
+        self.column_top = self.columns_bottom.max(self.page_content_rect.y());
+        self.columns_bottom = self.column_top;
+        self.column = 0;
+        self.page_content_rect = self.column_rect(0, self.page_content_rect.right_bottom.y);
+    }
 }
 
 pub enum DocumentCommand {
@@ -114,11 +322,45 @@ pub enum DocumentCommand {
     Add(String),
     AddSpace,
     Save(PathBuf),
+    /// Go to the bookmark of the link under the cursor, or show the target of
+    /// an external one.
+    FollowLink,
+    /// Go back to where the last followed link was.
+    JumpBack,
+    GoToBookmark(String),
+    ListBookmarks,
+    /// Go to the next tracked change, or to the previous one when negative.
+    ChangeRevision(i64),
+    /// Accept or reject the tracked change under the cursor, or all of them.
+    ResolveRevisions {
+        accept: bool,
+        all: bool,
+    },
+    /// Comment the word under the cursor, or with `reply` answer the comment
+    /// under it.
+    AddComment {
+        text: String,
+        reply: bool,
+    },
+    /// Replace the text of the comment the thread under the cursor starts
+    /// with.
+    EditComment(String),
+    /// Resolve the comment thread under the cursor or open it again.
+    ResolveComment,
+    /// Remove the comment thread under the cursor with all its replies.
+    DeleteComment,
 }
 
 pub enum VerticalSpacing {
     Relative(f32),
-    Absolute(f32),
+    /// `space` relative spacing with `word` more after the words from word
+    /// `from` on and `letter` more after each of their characters.
+    Justified {
+        space: f32,
+        word: f32,
+        letter: f32,
+        from: usize,
+    },
 }
 
 impl DrawState<'_> {
@@ -128,6 +370,10 @@ impl DrawState<'_> {
     const DEFAULT_LINE_SPACING: f32 = 1.0;
     const PAGE_SPACE_BETWEEN: f32 = 100.;
     const DEFAULT_FONT_SIZE: f32 = 12.;
+    const DEFAULT_TAB_STOP: f32 = 72.;
+    const VERTICAL_ALIGN_SCALE: f32 = 0.65;
+    const SMALL_CAPS_SCALE: f32 = 0.8;
+    const NOTE_SEPARATOR_SPACE: f32 = 10.;
 
     pub fn new_document_draw(
         &self,
@@ -145,6 +391,7 @@ impl DrawState<'_> {
             colorscheme.page_bg_color,
             document_draw.scroll,
             document_draw.scale,
+            0,
         );
 
         let page_rect = first_page.primitive.get_rect();
@@ -157,14 +404,33 @@ impl DrawState<'_> {
             page_rect,
             v_width,
             scale: document_draw.scale,
+            footnotes: Vec::new(),
+            section: 0,
+            default_tab_stop: Self::DEFAULT_TAB_STOP,
+            do_not_expand_shift_return: false,
+            column: 0,
+            column_top: page_content_rect.y(),
+            columns_bottom: page_content_rect.y(),
+            cell_page_bottom: None,
         };
 
         log::info!("page rect {page_rect:?}, page content rect {page_content_rect:?}");
 
+        document_draw.images = document.images.clone();
+        document_draw.headers_footers = document.headers_footers.clone();
+        document_draw.comments = document.comments.clone();
+        document_draw.styles = document.styles.clone();
+        document_draw.theme_fonts = document.theme_fonts.clone();
+        document_draw.even_and_odd_headers = document.settings.even_and_odd_headers;
+        document_draw.default_tab_stop = document.settings.default_tab_stop;
+        document_draw.do_not_expand_shift_return = document.settings.do_not_expand_shift_return;
         document_draw.selection_color = colorscheme.selection_color;
         document_draw.bg_color = colorscheme.page_bg_color;
         document_draw.pages = vec![first_page];
-        document_draw.sect_properties = SectrOfProperties::from(page_properties);
+        document_draw.sect_properties = document
+            .get_properties()
+            .unwrap_or_else(|| SectrOfProperties::from(page_properties));
+
 
 
 
@@ -173,32 +439,209 @@ impl DrawState<'_> {
             return Ok(document_draw);
         };
 
-        for paragraph in nodes.iter() {
-            let docx_document::DocxNode::Paragrapth {
-                properties, texts, ..
-            } = paragraph
-            else {
+        let mut counters = NumberingCounters::default();
+        let mut next_label = |properties: &ParagraphProperties| {
+            properties
+                .numbering
+                .and_then(|reference| counters.next_label(&document.numbering, reference))
+        };
+
+        for node in nodes.iter() {
+            match node {
+                docx_document::DocxNode::Paragrapth {
+                    properties, texts, ..
+                } => {
+                    let label = next_label(properties);
+                    self.push_paragraph(&mut document_draw, properties, texts, None, label, &ctx)?
+                }
+                docx_document::DocxNode::Table {
+                    properties,
+                    grid,
+                    rows,
+                } => self.push_table(
+                    &mut document_draw,
+                    (properties, grid, rows),
+                    None,
+                    &mut next_label,
+                    &ctx,
+                )?,
+                _ => {}
+            }
+        }
+
+        for &(kind, id) in &document.note_references {
+            let Some(content) = document.notes.get(&(kind, id)) else {
                 continue;
             };
-            let paragraph_tp = properties.text_properties.clone().unwrap_or_default();
+            let paragraphs = get_cell_paragraphs(content)
+                .into_iter()
+                .map(|(properties, texts)| {
+                    self.new_paragraph(&mut document_draw, properties, texts, None, None, &ctx)
+                })
+                .collect::<anyhow::Result<_>>()?;
+            document_draw.notes.push(Note {
+                kind,
+                id,
+                paragraphs,
+                laid_out: kind == NoteKind::Endnote,
+            });
+        }
+
+        Ok(document_draw)
+    }
 
-            let mut words = get_words(texts);
+    /// Push a table in `parent`, or in the body for none, and the
+    /// paragraphs of its cells, with the tables nested in them.
+    fn push_table(
+        &self,
+        document_draw: &mut DocumentDraw,
+        (properties, grid, rows): (&TableProperties, &[f32], &[TableRow]),
+        parent: Option<CellRef>,
+        next_label: &mut dyn FnMut(&ParagraphProperties) -> Option<ListLabel>,
+        ctx: &DrawStateCtx,
+    ) -> anyhow::Result<()> {
+        let table = document_draw.tables.len();
+        document_draw.tables.push(Table {
+            properties: properties.clone(),
+            grid: grid.to_vec(),
+            rows: rows
+                .iter()
+                .map(|row| TableRow {
+                    properties: row.properties.clone(),
+                    cells: row
+                        .cells
+                        .iter()
+                        .map(|cell| TableCell {
+                            properties: cell.properties.clone(),
+                            content: Vec::new(),
+                        })
+                        .collect(),
+                })
+                .collect(),
+            primitives: Vec::new(),
+            parent,
+        });
 
-            self.create_words_prims(&mut words, &mut document_draw, paragraph_tp, &ctx)?;
+        for (row_idx, row) in rows.iter().enumerate() {
+            for (cell_idx, cell) in row.cells.iter().enumerate() {
+                let cell_ref = Some(CellRef {
+                    table,
+                    row: row_idx,
+                    cell: cell_idx,
+                });
 
-            document_draw.paragraphs.push(Paragraph {
-                words,
-                lines: Vec::new(),
-                properties: properties.clone(),
-            });
+                let mut has_paragraph = false;
+                for node in &cell.content {
+                    match node {
+                        docx_document::DocxNode::Paragrapth {
+                            properties, texts, ..
+                        } => {
+                            let label = next_label(properties);
+                            self.push_paragraph(
+                                document_draw,
+                                properties,
+                                texts,
+                                cell_ref,
+                                label,
+                                ctx,
+                            )?;
+                            has_paragraph = true;
+                        }
+                        docx_document::DocxNode::Table {
+                            properties,
+                            grid,
+                            rows,
+                        } => self.push_table(
+                            document_draw,
+                            (properties, grid, rows),
+                            cell_ref,
+                            next_label,
+                            ctx,
+                        )?,
+                        _ => {}
+                    }
+                }
+                if !has_paragraph {
+                    let properties = ParagraphProperties::default();
+                    self.push_paragraph(document_draw, &properties, &[], cell_ref, None, ctx)?;
+                }
+            }
         }
+        Ok(())
+    }
 
-        Ok(document_draw)
+    fn push_paragraph(
+        &self,
+        document_draw: &mut DocumentDraw,
+        properties: &ParagraphProperties,
+        texts: &[TextNode],
+        cell: Option<CellRef>,
+        label: Option<ListLabel>,
+        ctx: &DrawStateCtx,
+    ) -> anyhow::Result<()> {
+        let paragraph = self.new_paragraph(document_draw, properties, texts, cell, label, ctx)?;
+        document_draw.paragraphs.push(paragraph);
+        Ok(())
+    }
+
+    fn new_paragraph(
+        &self,
+        document_draw: &mut DocumentDraw,
+        properties: &ParagraphProperties,
+        texts: &[TextNode],
+        cell: Option<CellRef>,
+        label: Option<ListLabel>,
+        ctx: &DrawStateCtx,
+    ) -> anyhow::Result<Paragraph> {
+        let paragraph_tp = properties.text_properties.clone().unwrap_or_default();
+
+        let mut words = get_words(texts);
+
+        self.create_words_prims(
+            &mut words,
+            &mut document_draw.fonts,
+            &document_draw.images,
+            paragraph_tp.clone(),
+            ctx,
+        )?;
+
+        let label = match label {
+            Some(ListLabel { text, level }) if !text.is_empty() => {
+                let mut word = Word {
+                    glyphs_views: vec![GlyphsView {
+                        word_range: 0..text.len(),
+                        properties: texts
+                            .first()
+                            .map(|text| text.properties.clone())
+                            .unwrap_or(paragraph_tp.clone()),
+                        ..Default::default()
+                    }],
+                    word: text,
+                };
+                self.create_word_prim(
+                    &mut word,
+                    &mut document_draw.fonts,
+                    &document_draw.images,
+                    &paragraph_tp,
+                    ctx.scale,
+                )?;
+                Some(ParagraphLabel { word, level })
+            }
+            _ => None,
+        };
+
+        Ok(Paragraph {
+            words,
+            lines: Vec::new(),
+            properties: properties.clone(),
+            cell,
+            label,
+        })
     }
 
     pub fn update_document(&self, document_draw: &mut DocumentDraw) -> anyhow::Result<()> {
-        let first_page = document_draw.pages.first().unwrap();
-        let page_properties = first_page.page_properties.clone();
+        let sections = document_draw.sections();
+        let page_properties = PageProperties::from(sections.first().cloned());
         let (v_width, _v_height) = (self.config.width as f32, self.config.height as f32);
 
         let bg_color = document_draw.bg_color;
@@ -207,7 +650,7 @@ impl DrawState<'_> {
         let scale = document_draw.scale;
 
         let first_page =
-            self.new_page_with_offset(&page_properties, v_width, bg_color, scroll, scale);
+            self.new_page_with_offset(&page_properties, v_width, bg_color, scroll, scale, 0);
 
         let page_rect = first_page.primitive.get_rect();
         let page_content_rect = page_rect.add_paddings(page_properties.paddings * scale);
@@ -222,15 +665,35 @@ impl DrawState<'_> {
             page_rect,
             v_width,
             scale,
+            footnotes: Vec::new(),
+            section: 0,
+            default_tab_stop: document_draw.default_tab_stop.unwrap_or(Self::DEFAULT_TAB_STOP),
+            do_not_expand_shift_return: document_draw.do_not_expand_shift_return,
+            column: 0,
+            column_top: page_content_rect.y(),
+            columns_bottom: page_content_rect.y(),
+            cell_page_bottom: None,
         };
+        ctx.page_content_rect = ctx.column_rect(0, page_content_rect.right_bottom.y);
 
         document_draw.clear_document();
 
         let paragraphs_len = document_draw.paragraphs.len();
-        for (par_idx, paragraph) in document_draw.paragraphs.iter_mut().enumerate() {
-            let properties = paragraph.properties.clone();
+        let mut par_idx = 0;
+        while par_idx < paragraphs_len {
+            let properties = document_draw.paragraphs[par_idx].properties.clone();
+            let table = document_draw.table_in(document_draw.paragraphs[par_idx].cell, None);
+
+            let new_section = par_idx != 0 && document_draw.paragraphs[par_idx - 1].ends_section();
+            if new_section {
+                ctx.section += 1;
+                let section = &sections[ctx.section];
+                self.start_section(&mut ctx, &mut document_draw.pages, section);
+            }
 
-            if par_idx != 0 {
+            if table.is_none() && properties.page_break_before && !ctx.at_page_top() {
+                self.break_page(&mut ctx, &mut document_draw.pages);
+            } else if par_idx != 0 && table.is_none() && !new_section {
                 let delta = properties
                     .spacing
                     .before
@@ -239,50 +702,25 @@ impl DrawState<'_> {
 
                 self.vertical_offset_and_push(&mut ctx, &mut document_draw.pages, delta);
             }
-            paragraph.lines = get_lines(&paragraph.words, &ctx, Self::DEFAULT_VERTICAL_SPACING);
-
-            log::info!("{:?}", paragraph.lines);
-            for (line_idx, line) in paragraph.lines.iter().enumerate() {
-                log::info!("{:?}", ctx.page_content_rect);
-
-                let (vertical_offset, vertical_space) = get_line_vertical_metrics(
-                    properties.justify.clone(),
-                    &ctx,
-                    line,
-                    Self::DEFAULT_VERTICAL_SPACING,
-                );
-
-                update_line(
-                    &mut paragraph.words,
-                    line,
-                    &ctx,
-                    vertical_offset,
-                    vertical_space,
-                );
-
-                self.update_cursor(
-                    &document_draw.selection_color,
-                    &mut document_draw.cursor_prims,
-                    &document_draw.cursor,
-                    par_idx,
-                    line_idx,
-                    paragraph,
-                    line,
-                    &ctx,
-                );
-
-                if line_idx != paragraph.lines.len() - 1 {
-                    let delta = properties
-                        .spacing
-                        .line
-                        .map(|sp| sp * ctx.scale)
-                        .unwrap_or(Self::DEFAULT_LINE_SPACING * line.height);
 
-                    self.vertical_offset_and_push(&mut ctx, &mut document_draw.pages, delta);
+            let next_idx = match table {
+                Some(table) => {
+                    let end = document_draw.table_end(par_idx, table);
+                    self.update_table(document_draw, table, par_idx..end, &mut ctx);
+                    end
                 }
-            }
+                None => {
+                    let keep_tail = if properties.keep_next {
+                        self.get_keep_next_height(document_draw, par_idx + 1, &ctx)
+                    } else {
+                        0.
+                    };
+                    self.update_paragraph(document_draw, par_idx, keep_tail, &mut ctx);
+                    par_idx + 1
+                }
+            };
 
-            if par_idx != paragraphs_len - 1 {
+            if next_idx != paragraphs_len && table.is_none() {
                 let delta = properties
                     .spacing
                     .after
@@ -291,8 +729,14 @@ impl DrawState<'_> {
 
                 self.vertical_offset_and_push(&mut ctx, &mut document_draw.pages, delta);
             }
+            par_idx = next_idx;
         }
 
+        document_draw.note_separators = Vec::new();
+        self.update_endnotes(document_draw, &mut ctx);
+        self.update_footnotes(document_draw, &mut ctx);
+        self.update_page_decorations(document_draw, &ctx)?;
+
         document_draw.for_prims_mut(|prim| {
             let prop = prim.prop.clone();
             self.update_prim(prop, prim);
@@ -300,143 +744,1057 @@ impl DrawState<'_> {
         Ok(())
     }
 
-    pub fn process_document_command(
+    /// Lay out the lines of one paragraph from the top of `ctx` and return
+    /// the bottom of its last line. `keep_tail` is the height of what
+    /// `keepNext` keeps on the page of its last line.
+    fn update_paragraph(
         &self,
         document_draw: &mut DocumentDraw,
-        command: DocumentCommand,
-        state: Arc<Mutex<State>>,
-    ) -> anyhow::Result<()> {
-        match command {
-            DocumentCommand::NewScroll(new_scroll) => {
-                document_draw.scroll = new_scroll;
-            }
-            DocumentCommand::NewScale(scale) => {
-                let scale = scale.clamp(0.1, 2.);
-                let ratio = scale / document_draw.scale;
-                self.scale_by_ratio(document_draw, ratio);
-                document_draw.scale = scale.clamp(0.1, 2.);
+        par_idx: usize,
+        keep_tail: f32,
+        ctx: &mut DrawStateCtx,
+    ) -> f32 {
+        let paragraph = &mut document_draw.paragraphs[par_idx];
+        let properties = paragraph.properties.clone();
+        let indent = self.get_line_indent(paragraph, ctx);
+
+        self.reset_letter_spacing(&mut paragraph.words, ctx.scale);
+        paragraph.lines = get_lines(
+            &paragraph.words,
+            ctx,
+            &indent,
+            Self::DEFAULT_VERTICAL_SPACING,
+        );
+
+        // Cell paragraphs are laid out in their cell, which never breaks.
+        let paginate = paragraph.cell.is_none();
+
+        log::info!("{:?}", paragraph.lines);
+        for (line_idx, line) in paragraph.lines.iter().enumerate() {
+            log::info!("{:?}", ctx.page_content_rect);
+
+            if paginate && keeps_to_next_page(paragraph, line_idx, keep_tail, ctx) {
+                self.break_column(ctx, &mut document_draw.pages);
             }
-            DocumentCommand::DeltaScroll(delta) => document_draw.scroll += delta,
-            DocumentCommand::RatioScale(ratio) => {
-                let prev = document_draw.scale;
-                document_draw.scale = (document_draw.scale * ratio).clamp(0.1, 2.);
-                let ratio = document_draw.scale / prev;
-                self.scale_by_ratio(document_draw, ratio);
+            // A line taller than the whole page stays where it is.
+            if !paginate
+                && let Some(bottom) = ctx.cell_page_bottom
+                && ctx.page_content_rect.y() + line.height > bottom
+                && ctx.page_content_rect.y() > bottom - ctx.page_height() + 0.5
+            {
+                self.next_cell_page(ctx);
             }
-            DocumentCommand::ChangeCharIdx(char_delta) => document_draw.change_char(char_delta),
-            DocumentCommand::ChangeLineIdx(line_delta) => document_draw.change_line(line_delta),
-            DocumentCommand::Remove => {
-                document_draw
-                    .remove()
-                    .iter()
-                    .for_each(|(par_idx, word_idx)| {
-                        let par = &mut document_draw.paragraphs[*par_idx];
-                        let par_tp = par.properties.text_properties.clone().unwrap_or_default();
 
-                        let word = &mut par.words[*word_idx];
-                        let scale = document_draw.scale;
-                        println!("{:?}", word);
+            let (vertical_offset, vertical_space) = get_line_vertical_metrics(
+                properties.justify.clone(),
+                indent.width(ctx, line_idx),
+                &paragraph.words,
+                line,
+                line_idx + 1 == paragraph.lines.len(),
+                ctx.do_not_expand_shift_return,
+                Self::DEFAULT_VERTICAL_SPACING,
+            );
 
-                        let _ =
-                            self.create_word_prim(word, &mut document_draw.fonts, &par_tp, scale);
-                    });
+            self.update_line(
+                &mut paragraph.words,
+                line,
+                ctx,
+                indent.start(line_idx) + vertical_offset,
+                vertical_space,
+            );
 
-                let _ = self.update_document(document_draw);
-                document_draw.change_char(-1);
+            if line_idx == 0
+                && let Some(label) = &mut paragraph.label
+            {
+                let label_line = Line {
+                    range: 0..1,
+                    ..line.clone()
+                };
+                self.update_line(
+                    std::slice::from_mut(&mut label.word),
+                    &label_line,
+                    ctx,
+                    indent.label,
+                    VerticalSpacing::Relative(0.),
+                );
             }
-            DocumentCommand::Add(data) => {
-                document_draw
-                    .insert(data)
-                    .iter()
-                    .for_each(|(par_idx, word_idx)| {
-                        let par = &mut document_draw.paragraphs[*par_idx];
-                        let par_tp = par.properties.text_properties.clone().unwrap_or_default();
 
-                        let word = &mut par.words[*word_idx];
-                        let scale = document_draw.scale;
-                        println!("{:?}", word);
+            self.reserve_footnotes(
+                &mut document_draw.notes,
+                document_draw.pages.len() - 1,
+                &paragraph.words[line.range.clone()],
+                ctx,
+            );
 
-                        let _ =
-                            self.create_word_prim(word, &mut document_draw.fonts, &par_tp, scale);
-                    });
+            self.update_cursor(
+                &document_draw.selection_color,
+                &mut document_draw.cursor_prims,
+                &document_draw.cursor,
+                par_idx,
+                line_idx,
+                paragraph,
+                line,
+                ctx,
+            );
 
-                document_draw.clear_document();
-                let _ = self.update_document(document_draw);
-                document_draw.change_char(1);
+            let break_type = line.break_type(&paragraph.words).filter(|_| paginate);
+            if let Some(BreakType::Page) = break_type {
+                self.break_page(ctx, &mut document_draw.pages);
+            } else if let Some(BreakType::Column) = break_type {
+                self.break_column(ctx, &mut document_draw.pages);
+            } else if line_idx != paragraph.lines.len() - 1 {
+                let delta = get_line_delta(line, properties.spacing.line, ctx.scale);
+                self.vertical_offset_and_push(ctx, &mut document_draw.pages, delta);
             }
-            DocumentCommand::AddSpace => {
-                document_draw
-                    .insert_space()
-                    .iter()
-                    .for_each(|(par_idx, word_idx)| {
-                        let par = &mut document_draw.paragraphs[*par_idx];
-                        let par_tp = par.properties.text_properties.clone().unwrap_or_default();
+        }
 
-                        let word = &mut par.words[*word_idx];
-                        let scale = document_draw.scale;
-                        println!("{:?}", word);
+        ctx.page_content_rect.y() + paragraph.lines.last().map_or(0., |line| line.height)
+    }
 
-                        let _ =
-                            self.create_word_prim(word, &mut document_draw.fonts, &par_tp, scale);
-                    });
-                document_draw.clear_document();
-                let _ = self.update_document(document_draw);
-                document_draw.change_char(1);
+    /// Height of what `keepNext` keeps on the page of the paragraph before
+    /// `par_idx`: the following `keepNext` paragraphs and the first line of
+    /// the one after them, or two with widow control, with the spacing
+    /// between them.
+    fn get_keep_next_height(
+        &self,
+        document_draw: &mut DocumentDraw,
+        par_idx: usize,
+        ctx: &DrawStateCtx,
+    ) -> f32 {
+        let mut height = 0.;
+        for par_idx in par_idx..document_draw.paragraphs.len() {
+            let prev = &document_draw.paragraphs[par_idx - 1];
+            let (after, ends_section) = (prev.properties.spacing.after, prev.ends_section());
+            let paragraph = &mut document_draw.paragraphs[par_idx];
+            let properties = paragraph.properties.clone();
+            if ends_section || paragraph.cell.is_some() || properties.page_break_before {
+                break;
             }
-            DocumentCommand::Save(file) => {
-                let state_clone = Arc::clone(&state);
-                let state_guard = state_clone.lock().to_anyhow()?;
-                let zip_document = &state_guard
-                    .document
-                    .as_ref()
-                    .context("[Document Command] Read document")?
-                    .zip_document;
-
-                let archive_data = Vec::new();
-                let mut new_archive = zip::ZipWriter::new(io::Cursor::new(archive_data));
 
-                let options =
-                    SimpleFileOptions::default().compression_method(zip::CompressionMethod::Stored);
+            let spacing = after.unwrap_or(Self::DEFAULT_SPACING_AFTER)
+                + properties.spacing.before.unwrap_or(Self::DEFAULT_SPACING_BEFORE);
+            height += spacing * ctx.scale;
 
-                for file_name in zip::ZipArchive::new(io::Cursor::new(zip_document))?.file_names() {
-                    new_archive.start_file(file_name, options)?;
-                    let mut file = Vec::new();
+            let lines = self.measure_lines(paragraph, ctx);
+            let kept = if properties.keep_next || properties.keep_lines {
+                lines.len()
+            } else if properties.widow_control {
+                2
+            } else {
+                1
+            };
+            let lines = &lines[..kept.min(lines.len())];
+            height += get_lines_height(lines, properties.spacing.line, ctx.scale);
+            if !properties.keep_next {
+                break;
+            }
+        }
+        height
+    }
 
-                    if "word/document.xml" == file_name {
-                        let element = document_draw.get_word_xml_document()?;
-                        println!("document: {:?}", element);
-                        element.write_to(&mut file)?;
-                        println!("result: {:?}", String::from_utf8(file.clone()));
-                    } else {
-                        zip::ZipArchive::new(io::Cursor::new(zip_document))?
-                            .by_name(file_name)?
-                            .read_to_end(&mut file)?;
-                    }
+    /// Lines `paragraph` is broken into at the width of `ctx`.
+    fn measure_lines(&self, paragraph: &mut Paragraph, ctx: &DrawStateCtx) -> Vec<Line> {
+        let indent = self.get_line_indent(paragraph, ctx);
+        self.reset_letter_spacing(&mut paragraph.words, ctx.scale);
+        get_lines(
+            &paragraph.words,
+            ctx,
+            &indent,
+            Self::DEFAULT_VERTICAL_SPACING,
+        )
+    }
 
-                    new_archive.write_all(&file)?;
-                    new_archive = zip::ZipWriter::new_append(new_archive.finish()?)?;
-                }
-                let buf = new_archive.finish()?.get_ref().clone();
+    /// Switch to the page geometry of `section`, which starts after the
+    /// current paragraph, and break the page as its `w:type` asks.
+    fn start_section(
+        &self,
+        ctx: &mut DrawStateCtx,
+        pages: &mut Vec<Page>,
+        section: &SectrOfProperties,
+    ) {
+        ctx.page_properties = PageProperties::from(Some(section.clone()));
 
-                {
-                    let mut file = std::fs::File::create(file)?;
-                    file.write_all(&buf)?;
-                }
+        let parity = match section.page_type {
+            Some(PageType::Continuous) => {
+                ctx.start_below_columns();
+                return;
             }
+            Some(PageType::EvenPage) => Some(0),
+            Some(PageType::OddPage) => Some(1),
+            _ => None,
         };
-
-        Ok(())
+        self.break_page(ctx, pages);
+        // Pages are numbered from one, so page `pages.len()` is the new one.
+        if let Some(parity) = parity
+            && pages.len() % 2 != parity
+        {
+            self.break_page(ctx, pages);
+        }
     }
 
-    pub fn draw_document_draw<'a, 'b: 'a>(
-        &'b self,
-        rpass: &mut wgpu::RenderPass<'a>,
-        document: &'a DocumentDraw,
+    /// Make room at the bottom of page `page_idx` for the footnotes `words`
+    /// refer to, with space for the separator above the first of them.
+    fn reserve_footnotes(
+        &self,
+        notes: &mut [Note],
+        page_idx: usize,
+        words: &[Word],
+        ctx: &mut DrawStateCtx,
     ) {
-        document.for_prims(|prim| self.draw_prim(rpass, prim));
-    }
-}
-
+        for glyphs_view in words.iter().flat_map(|word| &word.glyphs_views) {
+            let Some(NoteMark {
+                kind: NoteKind::Footnote,
+                id: Some(id),
+            }) = glyphs_view.note
+            else {
+                continue;
+            };
+            let Some(note_idx) = notes
+                .iter()
+                .position(|note| note.kind == NoteKind::Footnote && note.id == id)
+            else {
+                continue;
+            };
+            if ctx.footnotes.iter().any(|&(_, idx, _)| idx == note_idx) {
+                continue;
+            }
+
+            // Notes always take the page width, also when referred to from a cell.
+            let content_rect = ctx.page_content_rect;
+            ctx.page_content_rect = ctx
+                .page_rect
+                .add_paddings(ctx.page_properties.paddings * ctx.scale);
+            let mut height =
+                self.update_extra_paragraphs(&mut notes[note_idx].paragraphs, ctx, None);
+            ctx.page_content_rect = content_rect;
+
+            if !ctx.footnotes.iter().any(|&(page, ..)| page == page_idx) {
+                height += Self::NOTE_SEPARATOR_SPACE * 2. * ctx.scale;
+            }
+            ctx.page_content_rect = ctx.page_content_rect.move_right_bottom((0., -height));
+            ctx.footnotes.push((page_idx, note_idx, height));
+        }
+    }
+
+    /// Lay out the endnotes after the body, below a separator.
+    fn update_endnotes(&self, document_draw: &mut DocumentDraw, ctx: &mut DrawStateCtx) {
+        if !document_draw
+            .notes
+            .iter()
+            .any(|note| note.kind == NoteKind::Endnote)
+        {
+            return;
+        }
+
+        let space = Self::NOTE_SEPARATOR_SPACE * ctx.scale;
+        self.vertical_offset_and_push(ctx, &mut document_draw.pages, space * 2.);
+        let separator = self.new_note_separator(ctx.page_content_rect, ctx.scale);
+        document_draw.note_separators.push(separator);
+        self.vertical_offset_and_push(ctx, &mut document_draw.pages, space);
+
+        for note in &mut document_draw.notes {
+            if note.kind == NoteKind::Endnote {
+                let pages = Some(&mut document_draw.pages);
+                self.update_extra_paragraphs(&mut note.paragraphs, ctx, pages);
+            }
+        }
+    }
+
+    /// Lay out the footnotes of every page above its bottom margin.
+    fn update_footnotes(&self, document_draw: &mut DocumentDraw, ctx: &mut DrawStateCtx) {
+        let footnotes = std::mem::take(&mut ctx.footnotes);
+        let space = Self::NOTE_SEPARATOR_SPACE * ctx.scale;
+
+        for (note_idx, note) in document_draw.notes.iter_mut().enumerate() {
+            if note.kind == NoteKind::Footnote {
+                note.laid_out = footnotes.iter().any(|&(_, idx, _)| idx == note_idx);
+            }
+        }
+
+        for (page_idx, page) in document_draw.pages.iter().enumerate() {
+            let on_page = footnotes
+                .iter()
+                .filter(|&&(page, ..)| page == page_idx)
+                .collect::<Vec<_>>();
+            if on_page.is_empty() {
+                continue;
+            }
+
+            let height = on_page.iter().map(|&&(.., height)| height).sum::<f32>();
+            let content_rect = page
+                .primitive
+                .get_rect()
+                .add_paddings(page.page_properties.paddings * ctx.scale);
+            ctx.page_content_rect =
+                content_rect.move_left_top((0., content_rect.height() - height));
+
+            let separator = self.new_note_separator(ctx.page_content_rect.add_y(space), ctx.scale);
+            document_draw.note_separators.push(separator);
+            ctx.page_content_rect = ctx.page_content_rect.move_left_top((0., space * 2.));
+
+            for &&(_, note_idx, _) in &on_page {
+                let paragraphs = &mut document_draw.notes[note_idx].paragraphs;
+                let height = self.update_extra_paragraphs(paragraphs, ctx, None);
+                ctx.page_content_rect = ctx.page_content_rect.move_left_top((0., height));
+            }
+        }
+    }
+
+    /// Short line at the left of `rect` that separates notes from the text.
+    fn new_note_separator(&self, rect: math::Rectangle, scale: f32) -> Primitive {
+        self.new_prim((
+            math::Rectangle::new(rect.left_top, (rect.width() / 3., scale)),
+            Color::BLACK,
+        ))
+    }
+
+    /// Lay out the header and footer of every page, creating the paragraphs
+    /// of a page whose part changed or that had none yet.
+    fn update_page_decorations(
+        &self,
+        document_draw: &mut DocumentDraw,
+        ctx: &DrawStateCtx,
+    ) -> anyhow::Result<()> {
+        let sections = document_draw.sections();
+        let even_and_odd = document_draw.even_and_odd_headers;
+
+        let mut decorations = std::mem::take(&mut document_draw.page_decorations);
+        decorations.resize_with(document_draw.pages.len(), Default::default);
+
+        for (page_idx, decoration) in decorations.iter_mut().enumerate() {
+            let page = &document_draw.pages[page_idx];
+            let section = &sections[page.section];
+            let first = section.title_page
+                && document_draw.pages[..page_idx]
+                    .iter()
+                    .all(|prev| prev.section != page.section);
+            let even = even_and_odd && page_idx % 2 == 1;
+
+            // Sections without a part of some type show the previous section's.
+            let part = |references: fn(&SectrOfProperties) -> &HeaderFooterReferences| {
+                sections[..=page.section]
+                    .iter()
+                    .rev()
+                    .find_map(|section| references(section).get(first, even))
+                    .map(str::to_string)
+            };
+            let header = part(|section| &section.headers);
+            let footer = part(|section| &section.footers);
+
+            let margin = &section.page_margin;
+            let page_properties = page.page_properties.clone();
+            let page_rect = page.primitive.get_rect();
+            let content_rect = page_rect.add_paddings(page_properties.paddings * ctx.scale);
+            let mut ctx = DrawStateCtx {
+                page_content_rect: content_rect,
+                page_properties,
+                bg_color: ctx.bg_color,
+                page_rect,
+                v_width: ctx.v_width,
+                scale: ctx.scale,
+                footnotes: Vec::new(),
+                section: ctx.section,
+                default_tab_stop: ctx.default_tab_stop,
+                do_not_expand_shift_return: ctx.do_not_expand_shift_return,
+                column: 0,
+                column_top: content_rect.y(),
+                columns_bottom: content_rect.y(),
+                cell_page_bottom: None,
+            };
+
+            for (slot, part) in [
+                (&mut decoration.header, header),
+                (&mut decoration.footer, footer),
+            ] {
+                if slot.as_ref().map(|slot| &slot.part) != part.as_ref() {
+                    *slot = part
+                        .map(|part| self.new_header_footer(document_draw, part, &ctx))
+                        .transpose()?;
+                }
+            }
+
+            let at = |y: f32| {
+                math::Rectangle::new(
+                    (content_rect.x(), y),
+                    (content_rect.width(), page_rect.height()),
+                )
+            };
+
+            if let Some(header) = &mut decoration.header {
+                ctx.page_content_rect = at(page_rect.y() + margin.header * ctx.scale);
+                self.update_extra_paragraphs(&mut header.paragraphs, &mut ctx, None);
+            }
+
+            if let Some(footer) = &mut decoration.footer {
+                // Footers grow upwards from the footer distance, so measure first.
+                ctx.page_content_rect = at(page_rect.y());
+                let height = self.update_extra_paragraphs(&mut footer.paragraphs, &mut ctx, None);
+                let bottom = page_rect.right_bottom.y - margin.footer * ctx.scale;
+                ctx.page_content_rect = at(bottom - height);
+                self.update_extra_paragraphs(&mut footer.paragraphs, &mut ctx, None);
+            }
+        }
+
+        document_draw.page_decorations = decorations;
+        Ok(())
+    }
+
+    /// Paragraphs of header or footer `part`, with the cell paragraphs of its
+    /// tables one after another.
+    fn new_header_footer(
+        &self,
+        document_draw: &mut DocumentDraw,
+        part: String,
+        ctx: &DrawStateCtx,
+    ) -> anyhow::Result<HeaderFooter> {
+        let nodes = document_draw
+            .headers_footers
+            .get(&part)
+            .cloned()
+            .unwrap_or_default();
+
+        let paragraphs = get_cell_paragraphs(&nodes)
+            .into_iter()
+            .map(|(properties, texts)| {
+                self.new_paragraph(document_draw, properties, texts, None, None, ctx)
+            })
+            .collect::<anyhow::Result<_>>()?;
+
+        Ok(HeaderFooter { part, paragraphs })
+    }
+
+    /// Lay out paragraphs outside the body, like headers, footers and notes,
+    /// from the top of `ctx` and return their height. With `pages` they break
+    /// onto new pages like the body, otherwise they never leave the page.
+    fn update_extra_paragraphs(
+        &self,
+        paragraphs: &mut [Paragraph],
+        ctx: &mut DrawStateCtx,
+        mut pages: Option<&mut Vec<Page>>,
+    ) -> f32 {
+        let top = ctx.page_content_rect.y();
+        let mut advance = |ctx: &mut DrawStateCtx, delta: f32| match pages.as_deref_mut() {
+            Some(pages) => self.vertical_offset_and_push(ctx, pages, delta),
+            None => ctx.page_content_rect = ctx.page_content_rect.move_left_top((0., delta)),
+        };
+
+        for (par_idx, paragraph) in paragraphs.iter_mut().enumerate() {
+            let spacing = &paragraph.properties.spacing;
+            if par_idx != 0 {
+                advance(ctx, spacing.before.unwrap_or_default() * ctx.scale);
+            }
+
+            let indent = self.get_line_indent(paragraph, ctx);
+            self.reset_letter_spacing(&mut paragraph.words, ctx.scale);
+            paragraph.lines = get_lines(
+                &paragraph.words,
+                ctx,
+                &indent,
+                Self::DEFAULT_VERTICAL_SPACING,
+            );
+
+            for (line_idx, line) in paragraph.lines.iter().enumerate() {
+                let (vertical_offset, vertical_space) = get_line_vertical_metrics(
+                    paragraph.properties.justify.clone(),
+                    indent.width(ctx, line_idx),
+                    &paragraph.words,
+                    line,
+                    line_idx + 1 == paragraph.lines.len(),
+                    ctx.do_not_expand_shift_return,
+                    Self::DEFAULT_VERTICAL_SPACING,
+                );
+
+                self.update_line(
+                    &mut paragraph.words,
+                    line,
+                    ctx,
+                    indent.start(line_idx) + vertical_offset,
+                    vertical_space,
+                );
+
+                let delta = if line_idx == paragraph.lines.len() - 1 {
+                    line.height + spacing.after.unwrap_or_default() * ctx.scale
+                } else {
+                    get_line_delta(line, spacing.line, ctx.scale)
+                };
+                advance(ctx, delta);
+            }
+        }
+
+        ctx.page_content_rect.y() - top
+    }
+
+    /// Indentation of a list paragraph comes from its numbering level: the
+    /// label starts at the first line position and the text at the tab stop
+    /// after it, or right after it for `space`/`nothing` suffixes.
+    fn get_line_indent(&self, paragraph: &Paragraph, ctx: &DrawStateCtx) -> LineIndent {
+        let scale = ctx.scale;
+        let default_tab = ctx.default_tab_stop * scale;
+        let mut tabs = paragraph
+            .properties
+            .tabs
+            .iter()
+            .map(|stop| TabStop {
+                position: stop.position * scale,
+                ..*stop
+            })
+            .collect::<Vec<_>>();
+
+        let Some(label) = &paragraph.label else {
+            let indent = paragraph.properties.indent.unwrap_or_default();
+            // The start of the lines after a hanging first line is a tab stop.
+            if indent.hanging > 0. {
+                tabs.push(TabStop {
+                    position: indent.left * scale,
+                    alignment: TabAlignment::Start,
+                    leader: TabLeader::None,
+                });
+                tabs.sort_by(|a, b| a.position.total_cmp(&b.position));
+            }
+            return LineIndent {
+                left: indent.left * scale,
+                right: indent.right * scale,
+                first_line: (indent.first_line - indent.hanging) * scale,
+                label: 0.,
+                tabs,
+                default_tab,
+            };
+        };
+        let indent = label.level.indent;
+        let left = indent.left * scale;
+
+        let label_start = (indent.left + indent.first_line - indent.hanging) * scale;
+        let (label_width, _, label_scale) = get_words_sizes(&label.word);
+        let label_end = label_start + label_width;
+
+        let text_start = match label.level.suffix {
+            LevelSuffix::Tab => {
+                let tab_stop = default_tab;
+                [
+                    label.level.tab.map(|tab| tab * scale),
+                    (indent.hanging > 0.).then_some(left),
+                ]
+                .into_iter()
+                .flatten()
+                .filter(|position| *position > label_end)
+                .reduce(f32::min)
+                .unwrap_or(((label_end / tab_stop).floor() + 1.) * tab_stop)
+            }
+            LevelSuffix::Space => label_end + Self::DEFAULT_VERTICAL_SPACING * label_scale,
+            LevelSuffix::Nothing => label_end,
+        };
+
+        LineIndent {
+            left,
+            right: indent.right * scale,
+            first_line: text_start - left,
+            label: label_start,
+            tabs,
+            default_tab,
+        }
+    }
+
+    /// Lay out the cell paragraphs `paragraphs` of table `table_idx` row by
+    /// row. A row that does not fit on the current page is moved to the next
+    /// one as a whole.
+    fn update_table(
+        &self,
+        document_draw: &mut DocumentDraw,
+        table_idx: usize,
+        paragraphs: Range<usize>,
+        ctx: &mut DrawStateCtx,
+    ) {
+        let table = &document_draw.tables[table_idx];
+        let columns = get_table_columns(table, ctx.page_content_rect.width(), ctx.scale);
+        let table_width = columns.iter().sum::<f32>();
+        let left = ctx.page_content_rect.x()
+            + match table.properties.justify {
+                Some(docx_document::Justification::Center) => {
+                    (ctx.page_content_rect.width() - table_width) / 2.
+                }
+                Some(docx_document::Justification::End | docx_document::Justification::Right) => {
+                    ctx.page_content_rect.width() - table_width
+                }
+                _ => 0.,
+            };
+        let page_height = ctx.page_height();
+        // Rows are split over pages only in the body, and in one column.
+        let can_split =
+            ctx.page_content_rect.height().is_finite() && ctx.page_properties.columns.len() <= 1;
+
+        let mut primitives = Vec::new();
+        for row_idx in 0..document_draw.tables[table_idx].rows.len() {
+            let row = CellRef {
+                table: table_idx,
+                row: row_idx,
+                cell: 0,
+            };
+            let cant_split = document_draw.tables[table_idx].rows[row_idx]
+                .properties
+                .cant_split;
+            let cursor_prims_len = document_draw.cursor_prims.len();
+            let footnotes_len = ctx.footnotes.len();
+            let mut row_height = self.update_table_row(
+                document_draw,
+                row,
+                &columns,
+                left,
+                paragraphs.clone(),
+                false,
+                ctx,
+            );
+
+            // A row that fits on a page or can't be split starts on the next
+            // one, a taller one is split at the lines past the page bottom.
+            let mut split = false;
+            if row_height > ctx.page_content_rect.height() {
+                split = can_split && !cant_split && row_height > page_height;
+                if split || !ctx.at_page_top() {
+                    document_draw.cursor_prims.truncate(cursor_prims_len);
+                    ctx.footnotes.truncate(footnotes_len);
+                    if !split {
+                        let delta = ctx.page_content_rect.height();
+                        self.vertical_offset_and_push(ctx, &mut document_draw.pages, delta);
+                    }
+                    row_height = self.update_table_row(
+                        document_draw,
+                        row,
+                        &columns,
+                        left,
+                        paragraphs.clone(),
+                        split,
+                        ctx,
+                    );
+                }
+                if !split && row_height > ctx.page_content_rect.height() {
+                    log::warn!(
+                        "Row {row_idx} of table {table_idx} is taller than the page and can't \
+                         be split, so it runs past the page bottom."
+                    );
+                }
+            }
+
+            let mut row_top = ctx.page_content_rect.y();
+            let row_bottom = row_top + row_height;
+            loop {
+                let page_bottom = ctx.page_content_rect.right_bottom.y;
+                let last = !split || row_bottom <= page_bottom;
+                let bottom = if last { row_bottom } else { page_bottom };
+                let row_rect = math::Rectangle::new(
+                    (left, row_top),
+                    (table_width, (bottom - row_top).max(0.)),
+                );
+                for rect_and_color in get_row_rects(
+                    &document_draw.tables[table_idx],
+                    row_idx,
+                    &columns,
+                    row_rect,
+                    ctx.scale,
+                ) {
+                    primitives.push(self.new_prim(rect_and_color));
+                }
+                if last {
+                    break;
+                }
+                self.break_page(ctx, &mut document_draw.pages);
+                row_top = ctx.page_content_rect.y();
+            }
+
+            let delta = (row_bottom - row_top).max(0.);
+            self.vertical_offset_and_push(ctx, &mut document_draw.pages, delta);
+        }
+
+        document_draw.tables[table_idx].primitives = primitives;
+    }
+
+    /// Lay out every cell of `row.row` at the top of `ctx` and return the
+    /// row height. With `split` the cells go on over the next pages, which
+    /// the height counts in.
+    #[allow(clippy::too_many_arguments)]
+    fn update_table_row(
+        &self,
+        document_draw: &mut DocumentDraw,
+        row: CellRef,
+        columns: &[f32],
+        left: f32,
+        paragraphs: Range<usize>,
+        split: bool,
+        ctx: &mut DrawStateCtx,
+    ) -> f32 {
+        let table = &document_draw.tables[row.table];
+        let default_margin = table.properties.cell_margin;
+        let table_row = &table.rows[row.row];
+        let cells = table_row
+            .cells
+            .iter()
+            .map(|cell| {
+                let margin = cell.properties.margin.unwrap_or(default_margin);
+                (
+                    cell.properties.grid_span,
+                    math::Paddings::from((margin.top, margin.right, margin.bottom, margin.left))
+                        * ctx.scale,
+                )
+            })
+            .collect::<Vec<_>>();
+
+        let top = ctx.page_content_rect.y();
+        let footnotes_len = ctx.footnotes.len();
+        let min_height = Self::DEFAULT_FONT_SIZE * 2. * ctx.scale;
+        let mut row_height = table_row.properties.height.unwrap_or_default() * ctx.scale;
+
+        let mut column = 0;
+        for (cell_idx, (grid_span, margin)) in cells.into_iter().enumerate() {
+            let end = (column + grid_span).min(columns.len());
+            let x = left + columns[..column.min(end)].iter().sum::<f32>();
+            let width = columns[column.min(end)..end].iter().sum::<f32>();
+            column = end;
+
+            let mut cell_ctx = DrawStateCtx {
+                page_content_rect: math::Rectangle::new(
+                    (x + margin.left, top + margin.top),
+                    ((width - margin.left - margin.right).max(0.), f32::INFINITY),
+                ),
+                page_properties: ctx.page_properties.clone(),
+                bg_color: ctx.bg_color,
+                page_rect: ctx.page_rect,
+                v_width: ctx.v_width,
+                scale: ctx.scale,
+                footnotes: std::mem::take(&mut ctx.footnotes),
+                section: ctx.section,
+                default_tab_stop: ctx.default_tab_stop,
+                do_not_expand_shift_return: ctx.do_not_expand_shift_return,
+                column: ctx.column,
+                column_top: ctx.column_top,
+                columns_bottom: ctx.columns_bottom,
+                cell_page_bottom: split.then_some(ctx.page_content_rect.right_bottom.y),
+            };
+
+            let cell = Some(CellRef {
+                cell: cell_idx,
+                ..row
+            });
+            let mut bottom = cell_ctx.page_content_rect.y();
+            let mut after = 0.;
+            let mut par_idx = paragraphs.start;
+            while par_idx < paragraphs.end {
+                let par_cell = document_draw.paragraphs[par_idx].cell;
+                let nested = document_draw.table_in(par_cell, cell);
+                if par_cell != cell && nested.is_none() {
+                    par_idx += 1;
+                    continue;
+                }
+                if bottom > cell_ctx.page_content_rect.y() {
+                    let delta = bottom - cell_ctx.page_content_rect.y() + after * ctx.scale;
+                    cell_ctx.page_content_rect =
+                        cell_ctx.page_content_rect.move_left_top((0., delta));
+                }
+                match nested {
+                    Some(table) => {
+                        let end = document_draw.table_end(par_idx, table);
+                        self.update_table(document_draw, table, par_idx..end, &mut cell_ctx);
+                        bottom = cell_ctx.page_content_rect.y();
+                        after = 0.;
+                        par_idx = end;
+                    }
+                    None => {
+                        bottom = self.update_paragraph(document_draw, par_idx, 0., &mut cell_ctx);
+                        let spacing = &document_draw.paragraphs[par_idx].properties.spacing;
+                        after = spacing.after.unwrap_or_default();
+                        par_idx += 1;
+                    }
+                }
+            }
+
+            let content_height = (bottom - top - margin.top).max(min_height);
+            row_height = row_height.max(margin.top + content_height + margin.bottom);
+            ctx.footnotes = cell_ctx.footnotes;
+        }
+
+        // Cells have their own rects, so footnotes take their room from the page here.
+        for &(.., height) in &ctx.footnotes[footnotes_len..] {
+            ctx.page_content_rect = ctx.page_content_rect.move_right_bottom((0., -height));
+        }
+
+        row_height
+    }
+
+    pub fn process_document_command(
+        &self,
+        document_draw: &mut DocumentDraw,
+        command: DocumentCommand,
+        state: Arc<Mutex<State>>,
+    ) -> anyhow::Result<()> {
+        match command {
+            DocumentCommand::NewScroll(new_scroll) => {
+                document_draw.scroll = new_scroll;
+            }
+            DocumentCommand::NewScale(scale) => {
+                let scale = scale.clamp(0.1, 2.);
+                let ratio = scale / document_draw.scale;
+                self.scale_by_ratio(document_draw, ratio);
+                document_draw.scale = scale.clamp(0.1, 2.);
+            }
+            DocumentCommand::DeltaScroll(delta) => document_draw.scroll += delta,
+            DocumentCommand::RatioScale(ratio) => {
+                let prev = document_draw.scale;
+                document_draw.scale = (document_draw.scale * ratio).clamp(0.1, 2.);
+                let ratio = document_draw.scale / prev;
+                self.scale_by_ratio(document_draw, ratio);
+            }
+            DocumentCommand::ChangeCharIdx(char_delta) => document_draw.change_char(char_delta),
+            DocumentCommand::ChangeLineIdx(line_delta) => document_draw.change_line(line_delta),
+            DocumentCommand::Remove => {
+                document_draw
+                    .remove()
+                    .iter()
+                    .for_each(|(par_idx, word_idx)| {
+                        let par = &mut document_draw.paragraphs[*par_idx];
+                        let par_tp = par.properties.text_properties.clone().unwrap_or_default();
+
+                        let word = &mut par.words[*word_idx];
+                        let scale = document_draw.scale;
+                        println!("{:?}", word);
+
+                        let _ = self.create_word_prim(
+                            word,
+                            &mut document_draw.fonts,
+                            &document_draw.images,
+                            &par_tp,
+                            scale,
+                        );
+                    });
+
+                let _ = self.update_document(document_draw);
+                document_draw.change_char(-1);
+            }
+            DocumentCommand::Add(data) => {
+                document_draw
+                    .insert(data)
+                    .iter()
+                    .for_each(|(par_idx, word_idx)| {
+                        let par = &mut document_draw.paragraphs[*par_idx];
+                        let par_tp = par.properties.text_properties.clone().unwrap_or_default();
+
+                        let word = &mut par.words[*word_idx];
+                        let scale = document_draw.scale;
+                        println!("{:?}", word);
+
+                        let _ = self.create_word_prim(
+                            word,
+                            &mut document_draw.fonts,
+                            &document_draw.images,
+                            &par_tp,
+                            scale,
+                        );
+                    });
+
+                document_draw.clear_document();
+                let _ = self.update_document(document_draw);
+                document_draw.change_char(1);
+            }
+            DocumentCommand::AddSpace => {
+                document_draw
+                    .insert_space()
+                    .iter()
+                    .for_each(|(par_idx, word_idx)| {
+                        let par = &mut document_draw.paragraphs[*par_idx];
+                        let par_tp = par.properties.text_properties.clone().unwrap_or_default();
+
+                        let word = &mut par.words[*word_idx];
+                        let scale = document_draw.scale;
+                        println!("{:?}", word);
+
+                        let _ = self.create_word_prim(
+                            word,
+                            &mut document_draw.fonts,
+                            &document_draw.images,
+                            &par_tp,
+                            scale,
+                        );
+                    });
+                document_draw.clear_document();
+                let _ = self.update_document(document_draw);
+                document_draw.change_char(1);
+            }
+            DocumentCommand::FollowLink => {
+                let message = match document_draw.get_cursor_link().cloned() {
+                    None => "no link under the cursor".to_string(),
+                    Some(Hyperlink {
+                        relationship_id: Some(id),
+                        anchor,
+                    }) => {
+                        let state = state.lock().to_anyhow()?;
+                        let target = state
+                            .document
+                            .as_ref()
+                            .and_then(|document| {
+                                document.document.relationships.relationships.get(&id)
+                            })
+                            .map_or(String::new(), |relationship| relationship.target.clone());
+                        match anchor {
+                            Some(anchor) => format!("link to {}#{}", target, anchor),
+                            None => format!("link to {}", target),
+                        }
+                    }
+                    Some(Hyperlink {
+                        relationship_id: None,
+                        anchor: Some(anchor),
+                    }) if document_draw.go_to_bookmark(&anchor) => format!("went to {}", anchor),
+                    Some(Hyperlink { anchor, .. }) => {
+                        format!("no bookmark {:?}", anchor.unwrap_or_default())
+                    }
+                };
+                state.lock().to_anyhow()?.status_message = message;
+            }
+            DocumentCommand::JumpBack => {
+                if !document_draw.jump_back() {
+                    state.lock().to_anyhow()?.status_message = "no link to go back from".into();
+                }
+            }
+            DocumentCommand::GoToBookmark(name) => {
+                if !document_draw.go_to_bookmark(&name) {
+                    state.lock().to_anyhow()?.status_message = format!("no bookmark {:?}", name);
+                }
+            }
+            DocumentCommand::ListBookmarks => {
+                let names = document_draw
+                    .get_bookmarks()
+                    .map(|bookmark| bookmark.name.as_str())
+                    .collect::<Vec<_>>();
+                state.lock().to_anyhow()?.status_message = if names.is_empty() {
+                    "no bookmarks".to_string()
+                } else {
+                    format!("bookmarks: {}", names.join(", "))
+                };
+            }
+            DocumentCommand::ChangeRevision(delta) => {
+                let message = match document_draw.go_to_revision(delta) {
+                    Some(revision) => revision.describe(),
+                    None => "no more tracked changes".to_string(),
+                };
+                state.lock().to_anyhow()?.status_message = message;
+            }
+            DocumentCommand::ResolveRevisions { accept, all } => {
+                let changed = document_draw.resolve_revisions(accept, all);
+                let message = if changed.is_empty() && all {
+                    "no tracked changes"
+                } else if changed.is_empty() {
+                    "no tracked change under the cursor"
+                } else if accept {
+                    "accepted"
+                } else {
+                    "rejected"
+                };
+
+                for par_idx in changed {
+                    let par = &mut document_draw.paragraphs[par_idx];
+                    let par_tp = par.properties.text_properties.clone().unwrap_or_default();
+                    for word in &mut par.words {
+                        self.create_word_prim(
+                            word,
+                            &mut document_draw.fonts,
+                            &document_draw.images,
+                            &par_tp,
+                            document_draw.scale,
+                        )?;
+                    }
+                }
+                document_draw.clear_document();
+                self.update_document(document_draw)?;
+                document_draw.clamp_cursor();
+                state.lock().to_anyhow()?.status_message = message.to_string();
+            }
+            DocumentCommand::AddComment { text, reply } => {
+                let message = match document_draw.add_comment(&text, reply) {
+                    Some(id) => format!("added comment {}", id),
+                    None if reply => "no comment under the cursor".to_string(),
+                    None => "no word under the cursor".to_string(),
+                };
+                state.lock().to_anyhow()?.status_message = message;
+            }
+            DocumentCommand::EditComment(text) => {
+                if !document_draw.edit_comment(&text) {
+                    state.lock().to_anyhow()?.status_message = "no comment under the cursor".into();
+                }
+            }
+            DocumentCommand::ResolveComment => {
+                let message = match document_draw.resolve_comment() {
+                    Some(true) => "resolved",
+                    Some(false) => "reopened",
+                    None => "no comment under the cursor",
+                };
+                state.lock().to_anyhow()?.status_message = message.to_string();
+            }
+            DocumentCommand::DeleteComment => {
+                let message = if document_draw.delete_comment() {
+                    "deleted thread"
+                } else {
+                    "no comment under the cursor"
+                };
+                state.lock().to_anyhow()?.status_message = message.to_string();
+            }
+            DocumentCommand::Save(file) => {
+                let state_clone = Arc::clone(&state);
+                let state_guard = state_clone.lock().to_anyhow()?;
+                let document = state_guard
+                    .document
+                    .as_ref()
+                    .context("[Document Command] Read document")?;
+                let zip_document = &document.zip_document;
+
+                let archive_data = Vec::new();
+                let mut new_archive = zip::ZipWriter::new(io::Cursor::new(archive_data));
+
+                let options =
+                    SimpleFileOptions::default().compression_method(zip::CompressionMethod::Stored);
+
+                let mut parts = document_draw.get_comment_parts(document)?;
+                for file_name in zip::ZipArchive::new(io::Cursor::new(zip_document))?.file_names() {
+                    new_archive.start_file(file_name, options)?;
+                    let mut file = Vec::new();
+
+                    if let Some(idx) = parts.iter().position(|(name, _)| name == file_name) {
+                        file = parts.remove(idx).1;
+                    } else if "word/document.xml" == file_name {
+                        let mut element = document_draw.get_word_xml_document()?;
+                        element.set_encoding(document.encoding);
+                        println!("document: {:?}", element);
+                        file = element.to_bytes(&word_xml::WriteOptions::default())?;
+                        println!("result: {:?}", String::from_utf8(file.clone()));
+                    } else {
+                        zip::ZipArchive::new(io::Cursor::new(zip_document))?
+                            .by_name(file_name)?
+                            .read_to_end(&mut file)?;
+                    }
+
+                    new_archive.write_all(&file)?;
+                    new_archive = zip::ZipWriter::new_append(new_archive.finish()?)?;
+                }
+                for (file_name, file) in parts {
+                    new_archive.start_file(file_name, options)?;
+                    new_archive.write_all(&file)?;
+                }
+                let buf = new_archive.finish()?.get_ref().clone();
+
+                {
+                    let mut file = std::fs::File::create(file)?;
+                    file.write_all(&buf)?;
+                }
+            }
+        };
+
+        Ok(())
+    }
+
+    pub fn draw_document_draw<'a, 'b: 'a>(
+        &'b self,
+        rpass: &mut wgpu::RenderPass<'a>,
+        document: &'a DocumentDraw,
+    ) {
+        document.for_prims(|prim| self.draw_prim(rpass, prim));
+    }
+}
+
 impl DrawState<'_> {
     #[allow(clippy::too_many_arguments)]
     fn update_cursor(
@@ -496,11 +1854,12 @@ impl DrawState<'_> {
         &self,
         words: &mut [Word],
         fonts_collection: &mut T,
+        images: &HashMap<String, Arc<Image>>,
         paragraph_tp: TextProperties,
         ctx: &DrawStateCtx,
     ) -> Result<(), anyhow::Error> {
         for word in words.iter_mut() {
-            self.create_word_prim(word, fonts_collection, &paragraph_tp, ctx.scale)?;
+            self.create_word_prim(word, fonts_collection, images, &paragraph_tp, ctx.scale)?;
         }
         Ok(())
     }
@@ -509,65 +1868,310 @@ impl DrawState<'_> {
         &self,
         word: &mut Word,
         fonts_collection: &mut T,
+        images: &HashMap<String, Arc<Image>>,
         paragraph_tp: &TextProperties,
         scale: f32,
     ) -> Result<(), anyhow::Error> {
+        // Tabs get their width, and their leader, from the line, and breaks
+        // take no room.
+        if word.is_control() {
+            return Ok(());
+        }
+
         for glyphs_view in word.glyphs_views.iter_mut() {
             glyphs_view.word_range.end = glyphs_view.word_range.end.min(word.word.len());
-            let content = word.word[glyphs_view.word_range.clone()].to_string();
 
-            let font = fonts_collection.get_or_load_font(glyphs_view.properties.get_font_idx())?;
+            if let Some(image) = &glyphs_view.image {
+                // Pictures that failed to load keep their place as a grey box.
+                let data = images
+                    .get(&image.relationship_id)
+                    .cloned()
+                    .unwrap_or_else(|| {
+                        Arc::new(Image {
+                            width: 1,
+                            height: 1,
+                            pixels: vec![0xCC, 0xCC, 0xCC, 0xFF],
+                        })
+                    });
+                glyphs_view.primitive = self.new_prim(ImageProperties {
+                    rect: math::Rectangle::new(
+                        (0., 0.),
+                        (image.width * scale, image.height * scale),
+                    ),
+                    image: data,
+                });
+                continue;
+            }
+
+            let mut content = word.word[glyphs_view.word_range.clone()].to_string();
+            let properties = glyphs_view.drawn_properties().for_script(&content);
 
-            let color = glyphs_view
-                .properties
+            let font = fonts_collection.get_or_load_font(properties.get_font_idx())?;
+
+            let color = properties
                 .color
                 .unwrap_or(paragraph_tp.color.unwrap_or(Color::BLACK));
 
-            let scale = scale
-                * 2.
-                * glyphs_view
-                    .properties
+            let size = properties.size.clone().map(|sz| sz.0).unwrap_or(
+                paragraph_tp
                     .size
                     .clone()
                     .map(|sz| sz.0)
-                    .unwrap_or(
-                        paragraph_tp
-                            .size
-                            .clone()
-                            .map(|sz| sz.0)
-                            .unwrap_or(Self::DEFAULT_FONT_SIZE),
-                    );
+                    .unwrap_or(Self::DEFAULT_FONT_SIZE),
+            );
+            let mut font_scale = scale * 2. * size;
+            if glyphs_view.vertical_align() != VerticalAlign::Baseline {
+                font_scale *= Self::VERTICAL_ALIGN_SCALE;
+            }
 
-            glyphs_view.primitive = self.new_prim(PlainTextProperties::new(
-                ((0., 0.), (0., scale)),
-                color,
-                content,
-                font,
-            ));
+            if properties.vanish {
+                // Hidden text stays in the word, but takes no room.
+                content.clear();
+            } else if properties.caps || properties.small_caps {
+                if properties.small_caps && content.chars().any(char::is_lowercase) {
+                    font_scale *= Self::SMALL_CAPS_SCALE;
+                }
+                content = content.to_uppercase();
+            }
+
+            glyphs_view.primitive = self.new_prim(PlainTextProperties {
+                letter_spacing: properties.spacing.unwrap_or_default() * scale,
+                stretch: properties.stretch.unwrap_or(100.) / 100.,
+                ..PlainTextProperties::new(((0., 0.), (0., font_scale)), color, content, font)
+            });
         }
         Ok(())
     }
 
+    fn update_line(
+        &self,
+        words: &mut [Word],
+        line: &Line,
+        ctx: &DrawStateCtx,
+        mut vertical_offset: f32,
+        vertical_space: VerticalSpacing,
+    ) {
+        let mut last_scale = 1f32;
+        let mut tabs = line.tabs.iter();
+        for word_idx in line.range.clone() {
+            let text_scale = words[word_idx].glyphs_views.iter().rev().find_map(|glyphs_view| {
+                match &glyphs_view.primitive.prop {
+                    PrimitiveProperties::PlainText(PlainTextProperties { scale, .. }) => {
+                        Some(*scale)
+                    }
+                    _ => None,
+                }
+            });
+            last_scale = text_scale.unwrap_or(last_scale);
+            let (space, word_extra, letter) = match vertical_space {
+                VerticalSpacing::Relative(vs) => (vs * last_scale, 0., 0.),
+                VerticalSpacing::Justified {
+                    space,
+                    word,
+                    letter,
+                    from,
+                } if word_idx >= from => (space * last_scale, word, letter),
+                VerticalSpacing::Justified { space, .. } => (space * last_scale, 0., 0.),
+            };
+            let space = get_word_spacing(words, word_idx, space);
+            let space = if space > 0. { space + word_extra } else { 0. };
+            let is_last = word_idx + 1 == line.range.end;
+
+            let word = &mut words[word_idx];
+            if word.break_type().is_some() {
+                continue;
+            }
+            if word.is_tab() {
+                let (width, leader) = tabs.next().copied().unwrap_or((0., TabLeader::None));
+                if let Some(glyphs_view) = word.glyphs_views.first_mut() {
+                    let properties = &glyphs_view.properties;
+                    let size = properties.size.clone().map_or(Self::DEFAULT_FONT_SIZE, |sz| sz.0);
+                    let height = size * 2. * ctx.scale;
+
+                    let mut position = ctx.page_content_rect.left_top;
+                    position.x += vertical_offset;
+                    position.y += (line.height - height) * ctx.scale;
+                    let rect = math::Rectangle::new(position, (width, height));
+                    let color = properties.color.unwrap_or(Color::BLACK);
+                    let leader = get_tab_leader(leader, rect, color);
+                    self.update_decorations(&mut glyphs_view.decorations, leader);
+                }
+                vertical_offset += width;
+                continue;
+            }
+
+            let glyphs_views_len = word.glyphs_views.len();
+            for (glyphs_view_idx, glyphs_view) in word.glyphs_views.iter_mut().enumerate() {
+                if letter > 0. {
+                    self.set_letter_spacing(glyphs_view, letter, ctx.scale);
+                }
+                let math::Size { width, height } = glyphs_view.primitive.get_rect().size();
+
+                let mut position = ctx.page_content_rect.left_top;
+                position.x += vertical_offset;
+                position.y += (line.height - height) * ctx.scale;
+                match glyphs_view.vertical_align() {
+                    VerticalAlign::Superscript => position.y -= height * 0.5,
+                    VerticalAlign::Subscript => position.y += height * 0.25,
+                    VerticalAlign::Baseline => {}
+                }
+                position.y -= glyphs_view.properties.position.unwrap_or_default() * ctx.scale;
+
+                let color = match &mut glyphs_view.primitive.prop {
+                    PrimitiveProperties::PlainText(PlainTextProperties {
+                        left_top, color, ..
+                    }) => {
+                        *left_top = position;
+                        *color
+                    }
+                    PrimitiveProperties::Image(ImageProperties { rect, .. }) => {
+                        *rect = math::Rectangle::new(position, rect.size());
+                        Color::BLACK
+                    }
+                    _ => continue,
+                };
+
+                // The space after a word is decorated like the end of the word.
+                let trailing = if glyphs_view_idx + 1 == glyphs_views_len && !is_last {
+                    space
+                } else {
+                    0.
+                };
+                let rect = math::Rectangle::new(position, (width, height));
+                let properties = glyphs_view.drawn_properties();
+                let decorations = get_decorations(&properties, rect, trailing, color);
+                self.update_decorations(&mut glyphs_view.decorations, decorations);
+
+                vertical_offset += width;
+                if glyphs_view.chars_count() > 0 {
+                    vertical_offset += letter;
+                }
+            }
+            vertical_offset += space;
+        }
+    }
+
+    /// Give the text of `glyphs_view` the letter spacing of its properties
+    /// with `extra` more.
+    fn set_letter_spacing(&self, glyphs_view: &mut GlyphsView, extra: f32, scale: f32) {
+        let PrimitiveProperties::PlainText(prop) = &glyphs_view.primitive.prop else {
+            return;
+        };
+        let letter_spacing = glyphs_view.properties.spacing.unwrap_or_default() * scale + extra;
+        if prop.letter_spacing != letter_spacing {
+            let prop = PlainTextProperties {
+                letter_spacing,
+                ..prop.clone()
+            };
+            self.update_prim(prop, &mut glyphs_view.primitive);
+        }
+    }
+
+    /// Undo the spacing distributed lines put between characters, so that
+    /// `words` are measured at their own width.
+    fn reset_letter_spacing(&self, words: &mut [Word], scale: f32) {
+        for glyphs_view in words.iter_mut().flat_map(|word| word.glyphs_views.iter_mut()) {
+            self.set_letter_spacing(glyphs_view, 0., scale);
+        }
+    }
+
+    /// Move `prims` to `decorations`, creating them again when their number
+    /// changed.
+    fn update_decorations(
+        &self,
+        prims: &mut Vec<Primitive>,
+        decorations: Vec<(math::Rectangle, Color)>,
+    ) {
+        if decorations.len() == prims.len() {
+            for (prim, (rect, _)) in prims.iter_mut().zip(decorations) {
+                if let Some(prim_rect) = prim.get_rect_mut() {
+                    *prim_rect = rect;
+                }
+            }
+        } else {
+            *prims = decorations
+                .into_iter()
+                .map(|decoration| self.new_prim(decoration))
+                .collect();
+        }
+    }
+
+    /// Move down by `delta`, or on to the next column, or page, when there
+    /// is no room for it.
     fn vertical_offset_and_push(&self, ctx: &mut DrawStateCtx, pages: &mut Vec<Page>, delta: f32) {
         if delta < ctx.page_content_rect.height() {
             ctx.page_content_rect = ctx.page_content_rect.move_left_top((0., delta));
         } else {
-            let offset = ctx.page_rect.right_bottom.y + Self::PAGE_SPACE_BETWEEN * ctx.scale;
-            let new_page = self.new_page_with_offset(
-                &ctx.page_properties,
-                ctx.v_width,
-                ctx.bg_color,
-                offset,
-                ctx.scale,
-            );
+            self.break_column(ctx, pages);
+        }
+    }
 
-            ctx.page_rect = new_page.primitive.get_rect();
-            ctx.page_content_rect = ctx.page_rect.add_paddings(ctx.page_properties.paddings);
+    /// Start the next column of the page, or a new page after the last one.
+    fn break_column(&self, ctx: &mut DrawStateCtx, pages: &mut Vec<Page>) {
+        if ctx.column + 1 >= ctx.page_properties.columns.len() {
+            self.break_page(ctx, pages);
+            return;
+        }
 
-            pages.push(new_page);
+        // Room taken by footnotes stays taken in the next column.
+        let bottom = ctx.page_content_rect.right_bottom.y;
+        ctx.columns_bottom = ctx.columns_bottom.max(ctx.page_content_rect.y());
+        ctx.column += 1;
+        ctx.page_content_rect = ctx.column_rect(ctx.column, bottom);
+
+        if ctx.page_properties.column_separator
+            && let Some(page) = pages.last_mut()
+        {
+            let prev = ctx.column_rect(ctx.column - 1, bottom);
+            let x = (prev.right_bottom.x + ctx.page_content_rect.x()) / 2.;
+            let rect = math::Rectangle::new(
+                (x, ctx.column_top),
+                (ctx.scale, (bottom - ctx.column_top).max(0.)),
+            );
+            page.separators.push(self.new_prim((rect, Color::BLACK)));
         }
     }
 
+    /// Start a new page at its first column.
+    fn break_page(&self, ctx: &mut DrawStateCtx, pages: &mut Vec<Page>) {
+        let offset = ctx.page_rect.right_bottom.y + Self::PAGE_SPACE_BETWEEN * ctx.scale;
+        let new_page = self.new_page_with_offset(
+            &ctx.page_properties,
+            ctx.v_width,
+            ctx.bg_color,
+            offset,
+            ctx.scale,
+            ctx.section,
+        );
+
+        ctx.page_rect = new_page.primitive.get_rect();
+        let content_rect = ctx
+            .page_rect
+            .add_paddings(ctx.page_properties.paddings * ctx.scale);
+        ctx.column = 0;
+        ctx.column_top = content_rect.y();
+        ctx.columns_bottom = content_rect.y();
+        ctx.page_content_rect = ctx.column_rect(0, content_rect.right_bottom.y);
+
+        pages.push(new_page);
+    }
+
+    /// Go on with a cell of a row split over pages at the top of the next
+    /// page, which the table pushes once the whole row is laid out.
+    fn next_cell_page(&self, ctx: &mut DrawStateCtx) {
+        let offset = ctx.page_rect.right_bottom.y + Self::PAGE_SPACE_BETWEEN * ctx.scale;
+        ctx.page_rect = math::Rectangle::new(
+            (ctx.page_rect.x(), offset),
+            (ctx.page_rect.width(), ctx.page_rect.height()),
+        );
+        let top = offset + ctx.page_properties.paddings.top * ctx.scale;
+        ctx.page_content_rect = math::Rectangle::new(
+            (ctx.page_content_rect.x(), top),
+            (ctx.page_content_rect.width(), f32::INFINITY),
+        );
+        ctx.cell_page_bottom = Some(top + ctx.page_height());
+    }
+
     fn new_page_with_offset(
         &self,
         page_properties: &PageProperties,
@@ -575,15 +2179,18 @@ impl DrawState<'_> {
         bg_color: docx_document::Color,
         offset: f32,
         scale: f32,
+        section: usize,
     ) -> Page {
         let size = page_properties.clone().size * scale;
 
         Page {
             page_properties: page_properties.clone(),
+            section,
             primitive: self.new_prim((
                 math::Rectangle::new(((v_width - size.width) * 0.5, offset), size),
                 bg_color,
             )),
+            separators: Vec::new(),
         }
     }
 }
@@ -662,13 +2269,27 @@ fn get_cursor_rect(
 
 impl DrawState<'_> {
     fn scale_by_ratio(&self, document_draw: &mut DocumentDraw, ratio: f32) {
-        document_draw.paragraphs.iter_mut().for_each(|par| {
+        // Headers and footers are cheap enough to create again at the new scale.
+        document_draw.page_decorations.clear();
+
+        let notes = document_draw
+            .notes
+            .iter_mut()
+            .flat_map(|note| &mut note.paragraphs);
+        let paragraphs = document_draw.paragraphs.iter_mut().chain(notes);
+        paragraphs.for_each(|par| {
             par.words.iter_mut().for_each(|word| {
                 word.glyphs_views.iter_mut().for_each(|gv| {
                     let prop = gv.primitive.prop.clone().scale(ratio);
                     self.update_prim(prop, &mut gv.primitive);
                 })
             });
+            if let Some(label) = &mut par.label {
+                label.word.glyphs_views.iter_mut().for_each(|gv| {
+                    let prop = gv.primitive.prop.clone().scale(ratio);
+                    self.update_prim(prop, &mut gv.primitive);
+                })
+            }
             par.lines.iter_mut().for_each(|line| {
                 line.widht_with_spacing /= ratio;
                 line.min_width /= ratio;
@@ -680,61 +2301,269 @@ impl DrawState<'_> {
 }
 fn get_line_vertical_metrics(
     justification: Option<docx_document::Justification>,
-    ctx: &DrawStateCtx,
+    width: f32,
+    words: &[Word],
     line: &Line,
+    is_last: bool,
+    do_not_expand_shift_return: bool,
     vertical_space: f32,
 ) -> (f32, VerticalSpacing) {
+    use docx_document::Justification::*;
     use VerticalSpacing::*;
+    let is_last = is_last
+        || match line.break_type(words) {
+            Some(BreakType::TextWrapping) => do_not_expand_shift_return,
+            Some(_) => true,
+            None => false,
+        };
     match justification {
-        Some(docx_document::Justification::Start) | None => (0f32, Relative(vertical_space)),
-        Some(docx_document::Justification::Width) => (
-            0f32,
-            Absolute((ctx.page_content_rect.width() - line.min_width) / line.range.len() as f32),
-        ),
-        Some(docx_document::Justification::Center) => (
-            (ctx.page_content_rect.width() - line.widht_with_spacing) / 2.,
+        Some(Start | Left) | None => (0f32, Relative(vertical_space)),
+        Some(Center) => (
+            (width - line.widht_with_spacing) / 2.,
             Relative(vertical_space),
         ),
-        Some(docx_document::Justification::End) => (
-            ctx.page_content_rect.width() - line.widht_with_spacing,
-            Relative(vertical_space),
+        Some(End | Right) => (width - line.widht_with_spacing, Relative(vertical_space)),
+        // The last line of a justified paragraph stays ragged, and so does a
+        // line ended by a page or column break. One ended by a text wrapping
+        // break is stretched unless the compatibility setting says not to.
+        Some(Width | Both) if is_last => (0f32, Relative(vertical_space)),
+        Some(Width | Both) => (
+            0f32,
+            get_justified_spacing(words, line, width, false, vertical_space),
+        ),
+        Some(Distribute) => (
+            0f32,
+            get_justified_spacing(words, line, width, true, vertical_space),
         ),
     }
 }
 
-fn update_line(
-    words: &mut [Word],
-    line: &Line,
+/// Distance from the top of `line` to the top of the next line.
+fn get_line_delta(line: &Line, line_spacing: Option<f32>, scale: f32) -> f32 {
+    line_spacing
+        .map(|sp| sp * scale)
+        .unwrap_or(DrawState::DEFAULT_LINE_SPACING * line.height)
+}
+
+/// Height from the top of the first of `lines` to the bottom of the last.
+fn get_lines_height(lines: &[Line], line_spacing: Option<f32>, scale: f32) -> f32 {
+    let Some((last, lines)) = lines.split_last() else {
+        return 0.;
+    };
+    let deltas = lines.iter().map(|line| get_line_delta(line, line_spacing, scale));
+    deltas.sum::<f32>() + last.height
+}
+
+/// Whether line `line_idx` of `paragraph` has to start a new page to stay on
+/// one page with the lines `keepLines` and widow control keep it with, and
+/// with the `keep_tail` high content `keepNext` keeps after the paragraph.
+fn keeps_to_next_page(
+    paragraph: &Paragraph,
+    line_idx: usize,
+    keep_tail: f32,
     ctx: &DrawStateCtx,
-    mut vertical_offset: f32,
-    vertical_space: VerticalSpacing,
-) {
-    let mut last_scale = 1f32;
-    for word in &mut words[line.range.clone()] {
-        for glyphs_view in &mut word.glyphs_views {
-            let math::Size { width, height } = glyphs_view.primitive.get_rect().size();
-
-            if let PrimitiveProperties::PlainText(PlainTextProperties {
-                left_top, scale, ..
-            }) = &mut glyphs_view.primitive.prop
-            {
-                *left_top = ctx.page_content_rect.left_top;
+) -> bool {
+    let (properties, lines) = (&paragraph.properties, &paragraph.lines);
+    let widow_line = line_idx == 0 || line_idx + 2 == lines.len();
+    let end = if properties.keep_lines && line_idx == 0 {
+        lines.len()
+    } else if properties.widow_control && lines.len() >= 2 && widow_line {
+        line_idx + 2
+    } else {
+        line_idx + 1
+    };
+    let tail = if end == lines.len() { keep_tail } else { 0. };
+    if end == line_idx + 1 && tail <= 0. {
+        return false;
+    }
+    // An explicit page or column break splits the lines anyway.
+    if lines[line_idx..end - 1]
+        .iter()
+        .any(|line| line.breaks_page(&paragraph.words))
+    {
+        return false;
+    }
 
-                left_top.x += vertical_offset;
-                left_top.y += (line.height - height) * ctx.scale;
-                vertical_offset += width;
+    // What doesn't fit on a whole page either is broken as usual.
+    let height = get_lines_height(&lines[line_idx..end], properties.spacing.line, ctx.scale) + tail;
+    !ctx.at_page_top() && height > ctx.page_content_rect.height() && height <= ctx.page_height()
+}
+
+/// Spacing that stretches `line` to `width` over the gaps between its words
+/// and, with `letters`, between all of its characters. Text before the last
+/// tab of the line is left as it is, since tabs end at fixed stops.
+fn get_justified_spacing(
+    words: &[Word],
+    line: &Line,
+    width: f32,
+    letters: bool,
+    vertical_space: f32,
+) -> VerticalSpacing {
+    let range = line.range.clone();
+    if range.is_empty() {
+        return VerticalSpacing::Relative(vertical_space);
+    }
+    let from = range
+        .clone()
+        .rev()
+        .find(|&word_idx| words[word_idx].is_tab())
+        .map_or(range.start, |word_idx| word_idx + 1);
+
+    // `widht_with_spacing` counts the space after the last word too.
+    let trailing = get_word_spacing(words, range.end - 1, vertical_space * line.last_scale);
+    let free = width - (line.widht_with_spacing - trailing);
+
+    let gaps = (from..range.end - 1)
+        .filter(|&word_idx| get_word_spacing(words, word_idx, 1.) > 0.)
+        .count();
+    let chars = match letters {
+        true => words[from..range.end]
+            .iter()
+            .flat_map(|word| word.glyphs_views.iter())
+            .map(GlyphsView::chars_count)
+            .sum::<usize>(),
+        false => 0,
+    };
+    let slots = gaps + chars.saturating_sub(1);
+    if free <= 0. || slots == 0 {
+        return VerticalSpacing::Relative(vertical_space);
+    }
+
+    let step = free / slots as f32;
+    VerticalSpacing::Justified {
+        space: vertical_space,
+        word: step,
+        letter: if letters { step } else { 0. },
+        from,
+    }
+}
+
+/// Dots or a line leading across the tab in `rect` to the text after it.
+fn get_tab_leader(
+    leader: TabLeader,
+    rect: math::Rectangle,
+    color: Color,
+) -> Vec<(math::Rectangle, Color)> {
+    let (left, top, width, height) = (rect.x(), rect.y(), rect.width(), rect.height());
+    let thickness = (height / 16.).max(1.);
+    // Height of the marks in the line, and lengths of a mark and of the gap
+    // after it in line thicknesses; no gap draws one solid line.
+    let (y, (dash, gap), thickness) = match leader {
+        TabLeader::None => return Vec::new(),
+        TabLeader::Dot => (0.8, (1., 3.), thickness),
+        TabLeader::MiddleDot => (0.5, (1., 3.), thickness),
+        TabLeader::Hyphen => (0.55, (3., 2.), thickness),
+        TabLeader::Underscore => (0.85, (1., 0.), thickness),
+        TabLeader::Heavy => (0.85, (1., 0.), thickness * 2.),
+    };
+    let y = top + height * y - thickness;
+
+    if gap == 0. {
+        return vec![(math::Rectangle::new((left, y), (width, thickness)), color)];
+    }
+
+    let mut marks = Vec::new();
+    let mut x = gap * thickness;
+    while x + (dash + gap) * thickness <= width {
+        let mark = math::Rectangle::new((left + x, y), (dash * thickness, thickness));
+        marks.push((mark, color));
+        x += (dash + gap) * thickness;
+    }
+    marks
+}
+
+/// Backgrounds and lines drawn with the text in `rect`, which are
+/// stretched over the `trailing` space after it, except for word underlines.
+fn get_decorations(
+    properties: &TextProperties,
+    rect: math::Rectangle,
+    trailing: f32,
+    color: Color,
+) -> Vec<(math::Rectangle, Color)> {
+    use docx_document::UnderlineStyle::*;
+
+    let (left, top, height) = (rect.x(), rect.y(), rect.height());
+    let width = rect.width() + trailing;
+    let thickness = (height / 16.).max(1.);
+    let mut decorations = Vec::new();
+
+    let backgrounds = [properties.shading, properties.highlight.map(Color::from)];
+    for background in backgrounds.into_iter().flatten() {
+        decorations.push((
+            math::Rectangle::new((left, top), (width, height)),
+            background,
+        ));
+    }
+
+    let line = |x: f32, y: f32, width: f32, thickness: f32| {
+        (
+            math::Rectangle::new((left + x, y), (width, thickness)),
+            color,
+        )
+    };
+
+    if let Some(underline) = properties.underline {
+        let y = top + height * 0.85;
+        let (thickness, width) = match underline {
+            Thick | DottedHeavy | DashedHeavy | DashLongHeavy | DashDotHeavy | DashDotDotHeavy
+            | WavyHeavy => (thickness * 2., width),
+            Words => (thickness, rect.width()),
+            _ => (thickness, width),
+        };
+        // Lengths of the dashes and gaps of the pattern, in line thicknesses.
+        let pattern: &[(f32, f32)] = match underline {
+            Dotted | DottedHeavy => &[(1., 1.)],
+            Dash | DashedHeavy => &[(4., 2.)],
+            DashLong | DashLongHeavy => &[(8., 3.)],
+            DotDash | DashDotHeavy => &[(4., 2.), (1., 2.)],
+            DotDotDash | DashDotDotHeavy => &[(4., 2.), (1., 2.), (1., 2.)],
+            Wave | WavyHeavy | WavyDouble => &[(3., 0.)],
+            _ => &[],
+        };
+        let rows: &[f32] = match underline {
+            Double | WavyDouble => &[0., 2.],
+            _ => &[0.],
+        };
+        let wave = matches!(underline, Wave | WavyHeavy | WavyDouble);
+
+        for row in rows {
+            let y = y + row * thickness;
+            if pattern.is_empty() {
+                decorations.push(line(0., y, width, thickness));
+                continue;
+            }
 
-                last_scale = *scale;
+            let (mut x, mut up) = (0., false);
+            for &(dash, gap) in pattern.iter().cycle() {
+                if x >= width {
+                    break;
+                }
+                let dash_width = (dash * thickness).min(width - x);
+                let dash_y = if wave && up { y - thickness } else { y };
+                decorations.push(line(x, dash_y, dash_width, thickness));
+                x += (dash + gap) * thickness;
+                up = !up;
             }
         }
-        match vertical_space {
-            VerticalSpacing::Relative(vs) => vertical_offset += vs * last_scale,
-            VerticalSpacing::Absolute(vs) => vertical_offset += vs,
-        };
     }
+
+    if properties.strike {
+        decorations.push(line(0., top + height * 0.55, width, thickness));
+    }
+    if properties.double_strike {
+        decorations.push(line(0., top + height * 0.5, width, thickness));
+        decorations.push(line(0., top + height * 0.6, width, thickness));
+    }
+
+    decorations
 }
 
-fn get_lines(words: &[Word], ctx: &DrawStateCtx, vertical_space: f32) -> Vec<Line> {
+fn get_lines(
+    words: &[Word],
+    ctx: &DrawStateCtx,
+    indent: &LineIndent,
+    vertical_space: f32,
+) -> Vec<Line> {
     let mut lines = Vec::new();
     let mut curr_line = Line {
         height: 0.,
@@ -742,31 +2571,346 @@ fn get_lines(words: &[Word], ctx: &DrawStateCtx, vertical_space: f32) -> Vec<Lin
         widht_with_spacing: 0.,
         last_scale: 0.,
         range: 0..0,
+        tabs: Vec::new(),
     };
-    for word in words.iter() {
+    for (word_idx, word) in words.iter().enumerate() {
         let (widht, height, last_scale) = get_words_sizes(word);
-        if curr_line.widht_with_spacing + widht > ctx.page_content_rect.width() {
+        let spacing = get_word_spacing(words, word_idx, vertical_space * last_scale);
+        let tab_width = |line_idx: usize, x: f32| {
+            let segment = get_tab_segment(&words[word_idx + 1..], vertical_space);
+            indent.tab_width(indent.start(line_idx) + x, segment)
+        };
+
+        let mut tab = word
+            .is_tab()
+            .then(|| tab_width(lines.len(), curr_line.widht_with_spacing));
+        let widht = tab.map_or(widht, |(width, _)| width);
+        let after_break = word_idx > 0 && words[word_idx - 1].break_type().is_some();
+        if after_break || curr_line.widht_with_spacing + widht > indent.width(ctx, lines.len()) {
             let end = curr_line.range.end;
             lines.push(curr_line);
 
+            tab = word.is_tab().then(|| tab_width(lines.len(), 0.));
+            let widht = tab.map_or(widht, |(width, _)| width);
             curr_line = Line {
                 height,
                 min_width: widht,
-                widht_with_spacing: widht + vertical_space * last_scale,
+                widht_with_spacing: widht + spacing,
                 last_scale,
                 range: end..(end + 1),
+                tabs: tab.into_iter().collect(),
             };
             continue;
         }
 
         curr_line.min_width += widht;
         curr_line.height = curr_line.height.max(height);
-        curr_line.widht_with_spacing += widht + vertical_space * last_scale;
+        curr_line.widht_with_spacing += widht + spacing;
         curr_line.last_scale = last_scale;
         curr_line.range.end += 1;
+        curr_line.tabs.extend(tab);
+    }
+    lines.push(curr_line);
+    lines
+}
+
+/// Space after word `word_idx`. Tabs and breaks take the place of the spaces
+/// around them.
+fn get_word_spacing(words: &[Word], word_idx: usize, spacing: f32) -> f32 {
+    let next_is_control = words.get(word_idx + 1).is_some_and(Word::is_control);
+    if words[word_idx].is_control() || next_is_control {
+        0.
+    } else {
+        spacing
+    }
+}
+
+/// Width of `words` up to the first tab or break, and of the part of it
+/// before the decimal point, which is all of it without one.
+fn get_tab_segment(words: &[Word], vertical_space: f32) -> (f32, f32) {
+    let (mut width, mut decimal, mut spacing) = (0f32, None, 0.);
+    for (word_idx, word) in words.iter().enumerate() {
+        if word.is_control() {
+            break;
+        }
+        width += spacing;
+        let (word_width, _, last_scale) = get_words_sizes(word);
+        if decimal.is_none()
+            && let Some(point) = word.word.find(['.', ','])
+        {
+            let ratio = point as f32 / word.word.len().max(1) as f32;
+            decimal = Some(width + word_width * ratio);
+        }
+        width += word_width;
+        spacing = get_word_spacing(words, word_idx, vertical_space * last_scale);
+    }
+    (width, decimal.unwrap_or(width))
+}
+
+/// Column widths of `table` in screen units, filling columns missing from
+/// `w:tblGrid` evenly and shrinking everything to fit `max_width`.
+fn get_table_columns(table: &Table, max_width: f32, scale: f32) -> Vec<f32> {
+    let count = table
+        .rows
+        .iter()
+        .map(|row| {
+            row.cells
+                .iter()
+                .map(|cell| cell.properties.grid_span)
+                .sum::<usize>()
+        })
+        .max()
+        .unwrap_or_default()
+        .max(table.grid.len());
+
+    let mut columns = (0..count)
+        .map(|idx| table.grid.get(idx).copied().unwrap_or_default() * scale)
+        .collect::<Vec<_>>();
+
+    let missing = columns.iter().filter(|width| **width <= 0.).count();
+    if missing != 0 {
+        let rest = (max_width - columns.iter().sum::<f32>()).max(0.) / missing as f32;
+        columns
+            .iter_mut()
+            .filter(|width| **width <= 0.)
+            .for_each(|width| *width = rest);
+    }
+
+    let total = columns.iter().sum::<f32>();
+    if total > max_width {
+        columns
+            .iter_mut()
+            .for_each(|width| *width *= max_width / total);
+    }
+    columns
+}
+
+/// Shading and border rectangles of one laid out row. Cell borders win over
+/// the table ones, and the edge between vertically merged cells is skipped.
+fn get_row_rects(
+    table: &Table,
+    row_idx: usize,
+    columns: &[f32],
+    row_rect: math::Rectangle,
+    scale: f32,
+) -> Vec<(math::Rectangle, Color)> {
+    let borders = &table.properties.borders;
+    let is_last_row = row_idx + 1 == table.rows.len();
+    let merged_below = |column: usize| {
+        table.rows.get(row_idx + 1).is_some_and(|next| {
+            cell_at_column(&next.cells, column)
+                .is_some_and(|cell| cell.properties.v_merge == Some(VerticalMerge::Continue))
+        })
+    };
+
+    let mut rects = Vec::new();
+    let mut edges = Vec::new();
+    let mut column = 0;
+    for cell in &table.rows[row_idx].cells {
+        let end = (column + cell.properties.grid_span).min(columns.len());
+        let x = row_rect.x() + columns[..column.min(end)].iter().sum::<f32>();
+        let width = columns[column.min(end)..end].iter().sum::<f32>();
+        let (y, height) = (row_rect.y(), row_rect.height());
+        let cell_borders = &cell.properties.borders;
+
+        if let Some(shading) = cell.properties.shading {
+            rects.push((math::Rectangle::new((x, y), (width, height)), shading));
+        }
+
+        if cell.properties.v_merge != Some(VerticalMerge::Continue) {
+            let top = if row_idx == 0 {
+                &borders.top
+            } else {
+                &borders.inside_h
+            };
+            edges.push((
+                cell_borders.top.as_ref().or(top.as_ref()),
+                (x, y),
+                width,
+                true,
+            ));
+        }
+        if !merged_below(column) {
+            let bottom = if is_last_row {
+                &borders.bottom
+            } else {
+                &borders.inside_h
+            };
+            edges.push((
+                cell_borders.bottom.as_ref().or(bottom.as_ref()),
+                (x, y + height),
+                width,
+                true,
+            ));
+        }
+
+        let left = if column == 0 {
+            &borders.left
+        } else {
+            &borders.inside_v
+        };
+        edges.push((
+            cell_borders.left.as_ref().or(left.as_ref()),
+            (x, y),
+            height,
+            false,
+        ));
+
+        let right = if end == columns.len() {
+            &borders.right
+        } else {
+            &borders.inside_v
+        };
+        edges.push((
+            cell_borders.right.as_ref().or(right.as_ref()),
+            (x + width, y),
+            height,
+            false,
+        ));
+
+        column = end;
+    }
+
+    for (border, (x, y), length, horizontal) in edges {
+        let Some(border) = border.filter(|border| border.is_visible()) else {
+            continue;
+        };
+        let thickness = border_thickness(border, scale);
+        let rect = if horizontal {
+            math::Rectangle::new((x, y - thickness / 2.), (length, thickness))
+        } else {
+            math::Rectangle::new((x - thickness / 2., y), (thickness, length))
+        };
+        rects.push((rect, border.color.unwrap_or(Color::BLACK)));
+    }
+    rects
+}
+
+fn cell_at_column(cells: &[TableCell], column: usize) -> Option<&TableCell> {
+    let mut start = 0;
+    cells.iter().find(|cell| {
+        let found = start == column;
+        start += cell.properties.grid_span;
+        found
+    })
+}
+
+/// `w:sz` is in eighths of a point, a point being two units.
+fn border_thickness(border: &Border, scale: f32) -> f32 {
+    (border.size as f32 / 4. * scale).max(1.)
+}
+
+/// Paragraphs of a cell in reading order, with nested tables flattened.
+fn get_cell_paragraphs(
+    content: &[docx_document::DocxNode],
+) -> Vec<(&ParagraphProperties, &[TextNode])> {
+    let mut paragraphs = Vec::new();
+    for node in content {
+        match node {
+            docx_document::DocxNode::Paragrapth {
+                properties, texts, ..
+            } => paragraphs.push((properties, texts.as_slice())),
+            docx_document::DocxNode::Table { rows, .. } => {
+                for cell in rows.iter().flat_map(|row| &row.cells) {
+                    paragraphs.extend(get_cell_paragraphs(&cell.content));
+                }
+            }
+            _ => {}
+        }
+    }
+    paragraphs
+}
+
+/// Properties, hyperlink, tracked change and comments a run is saved with.
+type RunKey = (TextProperties, Option<Hyperlink>, Option<Revision>, Vec<i64>);
+
+/// Run with the hyperlink, tracked change and comments it is in.
+type CommentedRun = (Option<Hyperlink>, Option<Revision>, Vec<i64>, word_xml::Element);
+
+/// `runs` without their comments, but with a `w:commentRangeStart` before
+/// the first run of each comment and a `w:commentRangeEnd` and the
+/// reference to it after the last one. `bounds(id)` tells if the range of
+/// comment `id` starts and ends in this paragraph.
+fn mark_comments(
+    runs: Vec<CommentedRun>,
+    bounds: impl Fn(i64) -> (bool, bool),
+) -> Vec<(Option<Hyperlink>, Option<Revision>, word_xml::Element)> {
+    let mut marked = Vec::new();
+    for (idx, (link, revision, comments, run)) in runs.iter().enumerate() {
+        for &id in comments {
+            let first = !runs[..idx].iter().any(|(.., comments, _)| comments.contains(&id));
+            if first && bounds(id).0 {
+                let start = word_xml::Element::new("w:commentRangeStart").with_attr("w:id", id);
+                marked.push((link.clone(), None, start));
+            }
+        }
+        marked.push((link.clone(), revision.clone(), run.clone()));
+        for &id in comments {
+            let last = !runs[idx + 1..].iter().any(|(.., comments, _)| comments.contains(&id));
+            if last && bounds(id).1 {
+                let end = word_xml::Element::new("w:commentRangeEnd").with_attr("w:id", id);
+                let reference = word_xml::Element::new("w:r")
+                    .with_element(word_xml::Element::new("w:rPr").with_element(
+                        word_xml::Element::new("w:rStyle").with_attr("w:val", "CommentReference"),
+                    ))
+                    .with_element(
+                        word_xml::Element::new("w:commentReference").with_attr("w:id", id),
+                    );
+                marked.push((link.clone(), None, end));
+                marked.push((link.clone(), None, reference));
+            }
+        }
+    }
+    marked
+}
+
+/// Paragraph content of `runs` with the runs of one hyperlink in a
+/// `w:hyperlink` and the runs of one tracked change in a `w:ins` or `w:del`.
+fn wrap_runs(
+    runs: Vec<(Option<Hyperlink>, Option<Revision>, word_xml::Element)>,
+) -> Vec<word_xml::Element> {
+    let runs = runs.into_iter().map(|(link, revision, run)| (link, (revision, run)));
+    group_consecutive(runs)
+        .into_iter()
+        .flat_map(|(link, runs)| {
+            let content = group_consecutive(runs).into_iter().flat_map(|(revision, runs)| {
+                match revision {
+                    Some(revision) => {
+                        let mut element = revision.to_element();
+                        runs.into_iter().for_each(|run| element.append_element(run));
+                        vec![element]
+                    }
+                    None => runs,
+                }
+            });
+            match link {
+                Some(link) => {
+                    let mut element = word_xml::Element::new("w:hyperlink");
+                    if let Some(relationship_id) = &link.relationship_id {
+                        element.append_attr("r:id", relationship_id);
+                    }
+                    if let Some(anchor) = &link.anchor {
+                        element.append_attr("w:anchor", anchor);
+                    }
+                    content.for_each(|inner| element.append_element(inner));
+                    vec![element]
+                }
+                None => content.collect(),
+            }
+        })
+        .collect()
+}
+
+/// Values with their keys, where runs of equal keys are one group.
+fn group_consecutive<K: PartialEq, V>(items: impl IntoIterator<Item = (K, V)>) -> Vec<(K, Vec<V>)> {
+    let mut groups: Vec<(K, Vec<V>)> = Vec::new();
+    for (key, value) in items {
+        match groups.last_mut() {
+            Some((last, values)) if *last == key => values.push(value),
+            _ => groups.push((key, vec![value])),
+        }
     }
-    lines.push(curr_line);
-    lines
+    groups
 }
 
 fn get_words_sizes(word: &Word) -> (f32, f32, f32) {
@@ -803,13 +2947,84 @@ fn get_words(texts: &[TextNode]) -> Vec<Word> {
             let TextNode {
                 properties,
                 content,
+                image,
+                note,
+                br,
+                link,
+                revision,
+                comments,
             } = text;
 
+            if let Some(image) = image {
+                finish_curr_word(&mut words, &mut curr_word);
+                words.push(Finished(Word {
+                    word: Word::IMAGE.to_string(),
+                    glyphs_views: vec![GlyphsView {
+                        word_range: 0..Word::IMAGE.len(),
+                        properties: properties.clone(),
+                        image: Some(image.clone()),
+                        link: link.clone(),
+                        revision: revision.clone(),
+                        comments: comments.clone(),
+                        ..Default::default()
+                    }],
+                }));
+            }
+
+            if let Some(br) = br {
+                finish_curr_word(&mut words, &mut curr_word);
+                words.push(Finished(Word {
+                    word: Word::BREAK.to_string(),
+                    glyphs_views: vec![GlyphsView {
+                        word_range: 0..Word::BREAK.len(),
+                        properties: properties.clone(),
+                        br: Some(*br),
+                        link: link.clone(),
+                        revision: revision.clone(),
+                        comments: comments.clone(),
+                        ..Default::default()
+                    }],
+                }));
+                return words;
+            }
+
+            if text.is_tab() {
+                finish_curr_word(&mut words, &mut curr_word);
+                words.push(Finished(Word {
+                    word: TextNode::TAB.to_string(),
+                    glyphs_views: vec![GlyphsView {
+                        word_range: 0..TextNode::TAB.len(),
+                        properties: properties.clone(),
+                        link: link.clone(),
+                        revision: revision.clone(),
+                        comments: comments.clone(),
+                        ..Default::default()
+                    }],
+                }));
+                return words;
+            }
+
+            if let Some(note) = note {
+                let start = curr_word.word.len();
+                curr_word.word.push_str(content);
+                curr_word.glyphs_views.push(GlyphsView {
+                    word_range: start..curr_word.word.len(),
+                    properties: properties.clone(),
+                    note: Some(note.clone()),
+                    link: link.clone(),
+                    revision: revision.clone(),
+                    comments: comments.clone(),
+                    ..Default::default()
+                });
+                words.push(Unfinished(curr_word));
+                return words;
+            }
+
             for grapheme in content.graphemes(true) {
                 if grapheme.trim().is_empty() {
                     finish_curr_word(&mut words, &mut curr_word);
                 } else {
-                    push_grapheme_to_curr_word(properties, &mut curr_word, grapheme);
+                    push_grapheme_to_curr_word(text, &mut curr_word, grapheme);
                 }
             }
 
@@ -824,18 +3039,33 @@ fn get_words(texts: &[TextNode]) -> Vec<Word> {
         .collect()
 }
 
-fn push_grapheme_to_curr_word(properties: &TextProperties, curr_word: &mut Word, g: &str) {
+fn push_grapheme_to_curr_word(text: &TextNode, curr_word: &mut Word, g: &str) {
+    let TextNode {
+        properties,
+        link,
+        revision,
+        comments,
+        ..
+    } = text;
     let properties = properties.clone();
 
     curr_word.word.push_str(g);
     if let Some(last_glyphs_view) = curr_word.glyphs_views.last_mut() {
-        if last_glyphs_view.properties == properties {
+        if last_glyphs_view.properties == properties
+            && last_glyphs_view.link == *link
+            && last_glyphs_view.revision == *revision
+            && last_glyphs_view.comments == *comments
+            && !last_glyphs_view.is_object()
+        {
             last_glyphs_view.word_range.end += g.len();
         } else {
             let last = last_glyphs_view.word_range.end;
             curr_word.glyphs_views.push(GlyphsView {
-                word_range: last..(last + 1),
+                word_range: last..last + g.len(),
                 properties,
+                link: link.clone(),
+                revision: revision.clone(),
+                comments: comments.clone(),
                 ..Default::default()
             })
         }
@@ -843,6 +3073,9 @@ fn push_grapheme_to_curr_word(properties: &TextProperties, curr_word: &mut Word,
         curr_word.glyphs_views.push(GlyphsView {
             properties,
             word_range: 0..g.len(),
+            link: link.clone(),
+            revision: revision.clone(),
+            comments: comments.clone(),
             ..Default::default()
         })
     }
@@ -850,28 +3083,65 @@ fn push_grapheme_to_curr_word(properties: &TextProperties, curr_word: &mut Word,
 
 fn finish_curr_word(words: &mut Vec<WordState>, curr_word: &mut Word) {
     use WordState::*;
+    if curr_word.word.is_empty() {
+        return;
+    }
     words.push(Finished(curr_word.clone_without_primitive()));
     *curr_word = Word::default();
 }
 
 impl Word {
+    const IMAGE: &'static str = "\u{FFFC}";
+    const BREAK: &'static str = "\n";
+
+    fn is_tab(&self) -> bool {
+        self.word == TextNode::TAB
+    }
+
+    fn break_type(&self) -> Option<BreakType> {
+        self.glyphs_views.first().and_then(|glyphs_view| glyphs_view.br)
+    }
+
+    /// Tabs and breaks, which stand between words in place of a space.
+    fn is_control(&self) -> bool {
+        self.is_tab() || self.break_type().is_some()
+    }
+
     fn clear_glyphs(&mut self) {
         let mut glyphs = Vec::new();
         if let Some(prev) = self.glyphs_views.first_mut() {
             let mut prev = GlyphsView {
                 word_range: prev.word_range.clone(),
                 properties: prev.properties.clone(),
+                image: prev.image.clone(),
+                note: prev.note.clone(),
+                br: prev.br,
+                link: prev.link.clone(),
+                revision: prev.revision.clone(),
+                comments: prev.comments.clone(),
                 ..Default::default()
             };
 
             for glyphs_view in &self.glyphs_views[1..] {
-                if prev.properties == glyphs_view.properties {
+                if prev.properties == glyphs_view.properties
+                    && prev.link == glyphs_view.link
+                    && prev.revision == glyphs_view.revision
+                    && prev.comments == glyphs_view.comments
+                    && !prev.is_object()
+                    && !glyphs_view.is_object()
+                {
                     prev.word_range.end = glyphs_view.word_range.end;
                 } else {
                     glyphs.push(prev);
                     prev = GlyphsView {
                         word_range: glyphs_view.word_range.clone(),
                         properties: glyphs_view.properties.clone(),
+                        image: glyphs_view.image.clone(),
+                        note: glyphs_view.note.clone(),
+                        br: glyphs_view.br,
+                        link: glyphs_view.link.clone(),
+                        revision: glyphs_view.revision.clone(),
+                        comments: glyphs_view.comments.clone(),
                         ..Default::default()
                     };
                 }
@@ -890,6 +3160,12 @@ impl Word {
                 .map(|glyphs_view| GlyphsView {
                     word_range: glyphs_view.word_range.clone(),
                     properties: glyphs_view.properties.clone(),
+                    image: glyphs_view.image.clone(),
+                    note: glyphs_view.note.clone(),
+                    br: glyphs_view.br,
+                    link: glyphs_view.link.clone(),
+                    revision: glyphs_view.revision.clone(),
+                    comments: glyphs_view.comments.clone(),
                     ..Default::default()
                 })
                 .collect(),
@@ -897,6 +3173,118 @@ impl Word {
     }
 }
 
+impl Paragraph {
+    fn ends_section(&self) -> bool {
+        self.cell.is_none() && self.properties.section.is_some()
+    }
+}
+
+impl Line {
+    fn break_type(&self, words: &[Word]) -> Option<BreakType> {
+        words.get(self.range.end.checked_sub(1)?)?.break_type()
+    }
+
+    /// Whether the line ends with a page or column break.
+    fn breaks_page(&self, words: &[Word]) -> bool {
+        matches!(
+            self.break_type(words),
+            Some(BreakType::Page | BreakType::Column)
+        )
+    }
+}
+
+impl GlyphsView {
+    /// Word's `Hyperlink` style colour.
+    const LINK_COLOR: Color = Color {
+        r: 0.02,
+        g: 0.39,
+        b: 0.76,
+        a: 1.,
+    };
+
+    /// Colours of the authors of tracked changes.
+    const REVISION_COLORS: [Color; 6] = [
+        Color::rgb(0.71, 0.03, 0.18),
+        Color::rgb(0.0, 0.36, 0.72),
+        Color::rgb(0.18, 0.55, 0.16),
+        Color::rgb(0.5, 0.19, 0.62),
+        Color::rgb(0.85, 0.42, 0.0),
+        Color::rgb(0.0, 0.5, 0.5),
+    ];
+
+    /// Background of commented text.
+    const COMMENT_COLOR: Color = Color::rgb(1.0, 0.93, 0.6);
+
+    /// Properties the view is drawn with. Linked text without a colour or an
+    /// underline of its own is drawn in the link colour and underlined. A
+    /// tracked change is drawn in the colour of its author, underlined when
+    /// inserted and struck through when deleted. Commented text without a
+    /// shading of its own gets the comment background.
+    fn drawn_properties(&self) -> Cow<'_, TextProperties> {
+        let styled_link = self.link.is_some()
+            && (self.properties.color.is_none() || self.properties.underline.is_none());
+        let styled_comment = !self.comments.is_empty() && self.properties.shading.is_none();
+        if !styled_link && !styled_comment && self.revision.is_none() {
+            return Cow::Borrowed(&self.properties);
+        }
+
+        let mut properties = self.properties.clone();
+        if self.link.is_some() {
+            properties.color.get_or_insert(Self::LINK_COLOR);
+            properties.underline.get_or_insert(docx_document::UnderlineStyle::Single);
+        }
+        if let Some(revision) = &self.revision {
+            properties.color = Some(Self::revision_color(revision));
+            match revision.kind {
+                RevisionKind::Insertion => {
+                    properties.underline = Some(docx_document::UnderlineStyle::Single)
+                }
+                RevisionKind::Deletion => properties.strike = true,
+            }
+        }
+        if styled_comment {
+            properties.shading = Some(Self::COMMENT_COLOR);
+        }
+        Cow::Owned(properties)
+    }
+
+    /// Every author keeps one colour, picked by their name.
+    fn revision_color(revision: &Revision) -> Color {
+        let hash = revision
+            .author
+            .as_deref()
+            .unwrap_or_default()
+            .bytes()
+            .fold(0usize, |hash, byte| hash.wrapping_mul(31).wrapping_add(byte as usize));
+        Self::REVISION_COLORS[hash % Self::REVISION_COLORS.len()]
+    }
+
+    /// Pictures, note marks and breaks are never merged with the text around
+    /// them.
+    fn is_object(&self) -> bool {
+        self.image.is_some() || self.note.is_some() || self.br.is_some()
+    }
+
+    /// Note marks are raised even without the reference style.
+    fn vertical_align(&self) -> VerticalAlign {
+        match (&self.note, self.properties.vertical_align) {
+            (Some(_), _) => VerticalAlign::Superscript,
+            (None, vertical_align) => vertical_align.unwrap_or(VerticalAlign::Baseline),
+        }
+    }
+
+    /// Characters drawn by the view, where a picture is one.
+    fn chars_count(&self) -> usize {
+        match &self.primitive.prop {
+            PrimitiveProperties::PlainText(PlainTextProperties { content, .. }) => {
+                content.chars().count()
+            }
+            PrimitiveProperties::Image(_) => 1,
+            _ => 0,
+        }
+    }
+}
+
 pub trait GetOrLoadFont {
     fn get_or_load_font(
         &mut self,
@@ -961,143 +3349,719 @@ pub enum CursorTargetMut<'a> {
 
 impl DocumentDraw {
     const WORD_DOCUMENT_DEFAULT: &'static str = include_str!("./docx/word/document.xml");
+    /// Distance from the top of the window to the line jumped to.
+    const JUMP_TOP: f32 = 100.;
 
     pub fn get_word_xml_document(&self) -> anyhow::Result<word_xml::WordXMLDocument> {
-        let process_rpr = |rpr: TextProperties| {
+        // A toggle turned off over a style that turns it on is kept as off.
+        let toggle_element = |name: &str, toggle: bool| {
+            let element = word_xml::Element::new(name);
+            if toggle {
+                element
+            } else {
+                element.with_attr("w:val", "0")
+            }
+        };
+
+        // Only what differs from `base`, the properties of the styles, is
+        // direct formatting.
+        let process_rpr = |rpr: TextProperties, base: &TextProperties| {
             let mut builder = word_xml::Element::new("w:rPr");
 
-            if let docx_document::TextWeight::Bold = rpr.weight {
-                builder.append_element(word_xml::Element::new("w:b"));
+            if let Some(style) = &rpr.style {
+                builder
+                    .append_element(word_xml::Element::new("w:rStyle").with_attr("w:val", style));
+            }
+
+            let slots = |rpr: &TextProperties| {
+                let fonts = &rpr.fonts;
+                [
+                    (
+                        "w:ascii",
+                        rpr.font_name.clone(),
+                        "w:asciiTheme",
+                        fonts.ascii_theme,
+                    ),
+                    (
+                        "w:hAnsi",
+                        fonts.h_ansi.clone().or(rpr.font_name.clone()),
+                        "w:hAnsiTheme",
+                        fonts.h_ansi_theme,
+                    ),
+                    (
+                        "w:eastAsia",
+                        fonts.east_asia.clone(),
+                        "w:eastAsiaTheme",
+                        fonts.east_asia_theme,
+                    ),
+                    (
+                        "w:cs",
+                        fonts.complex_script.clone().or(rpr.font_name.clone()),
+                        "w:cstheme",
+                        fonts.complex_script_theme,
+                    ),
+                ]
+            };
+            let mut r_fonts = word_xml::Element::new("w:rFonts");
+            for ((attr, font_name, theme_attr, theme), (_, base_name, _, base_theme)) in
+                slots(&rpr).into_iter().zip(slots(base))
+            {
+                if font_name != base_name
+                    && let Some(font_name) = font_name
+                {
+                    r_fonts.append_attr(attr, font_name);
+                }
+                if theme != base_theme
+                    && let Some(theme) = theme
+                {
+                    r_fonts.append_attr(theme_attr, theme);
+                }
+            }
+            if !r_fonts.attrs.is_empty() {
+                builder.append_element(r_fonts);
+            }
+
+            let bold = |weight: &docx_document::TextWeight| {
+                *weight == docx_document::TextWeight::Bold
+            };
+            let toggles = [
+                ("w:b", bold(&rpr.weight), bold(&base.weight)),
+                ("w:bCs", bold(&rpr.weight_cs), bold(&base.weight_cs)),
+                ("w:i", rpr.italic, base.italic),
+                ("w:iCs", rpr.italic_cs, base.italic_cs),
+                ("w:caps", rpr.caps, base.caps),
+                ("w:smallCaps", rpr.small_caps, base.small_caps),
+                ("w:strike", rpr.strike, base.strike),
+                ("w:dstrike", rpr.double_strike, base.double_strike),
+                ("w:vanish", rpr.vanish, base.vanish),
+            ];
+            for (name, toggle, base) in toggles {
+                if toggle != base {
+                    builder.append_element(toggle_element(name, toggle));
+                }
+            }
+
+            if rpr.color != base.color
+                && let Some(color) = &rpr.color
+            {
+                builder.append_element(
+                    word_xml::Element::new("w:color").with_attr("w:val", color.to_xml_val()),
+                )
+            }
+
+            if rpr.spacing != base.spacing
+                && let Some(spacing) = rpr.spacing
+            {
+                builder.append_element(
+                    word_xml::Element::new("w:spacing")
+                        .with_attr("w:val", (spacing * 10.).round() as i32),
+                );
+            }
+
+            if rpr.stretch != base.stretch
+                && let Some(stretch) = rpr.stretch
+            {
+                builder.append_element(
+                    word_xml::Element::new("w:w").with_attr("w:val", stretch.round() as i32),
+                );
+            }
+
+            if rpr.position != base.position
+                && let Some(position) = rpr.position
+            {
+                builder.append_element(
+                    word_xml::Element::new("w:position")
+                        .with_attr("w:val", position.round() as i32),
+                );
+            }
+
+            if rpr.size != base.size
+                && let Some(size) = &rpr.size
+            {
+                builder.append_element(
+                    word_xml::Element::new("w:sz").with_attr("w:val", size.to_string()),
+                );
+            }
+
+            if rpr.size_cs != base.size_cs
+                && let Some(size_cs) = &rpr.size_cs
+            {
+                builder.append_element(
+                    word_xml::Element::new("w:szCs").with_attr("w:val", size_cs.to_string()),
+                )
+            }
+
+            if rpr.highlight != base.highlight
+                && let Some(highlight) = rpr.highlight
+            {
+                builder.append_element(
+                    word_xml::Element::new("w:highlight").with_attr("w:val", highlight),
+                );
+            }
+
+            if rpr.underline != base.underline
+                && let Some(underline) = rpr.underline
+            {
+                builder.append_element(word_xml::Element::new("w:u").with_attr("w:val", underline));
+            }
+
+            if rpr.shading != base.shading
+                && let Some(shading) = rpr.shading
+            {
+                builder.append_element(
+                    word_xml::Element::new("w:shd")
+                        .with_attr("w:val", "clear")
+                        .with_attr("w:color", "auto")
+                        .with_attr("w:fill", shading.to_xml_val()),
+                );
+            }
+
+            if rpr.vertical_align != base.vertical_align
+                && let Some(vertical_align) = rpr.vertical_align
+            {
+                builder.append_element(
+                    word_xml::Element::new("w:vertAlign").with_attr("w:val", vertical_align),
+                );
+            }
+
+            let (lang, base_lang) = (&rpr.lang, &base.lang);
+            let mut lang_element = word_xml::Element::new("w:lang");
+            let langs = [
+                ("w:val", &lang.latin, &base_lang.latin),
+                ("w:eastAsia", &lang.east_asia, &base_lang.east_asia),
+                ("w:bidi", &lang.bidi, &base_lang.bidi),
+            ];
+            for (attr, lang, base) in langs {
+                if lang != base
+                    && let Some(lang) = lang
+                {
+                    lang_element.append_attr(attr, lang);
+                }
+            }
+            if !lang_element.attrs.is_empty() {
+                builder.append_element(lang_element);
+            }
+            builder
+        };
+
+        let process_spacing = |spacing: SpacingProperties, base: &SpacingProperties| {
+            let mut builder = word_xml::Element::new("w:spacing");
+
+            if spacing.line != base.line
+                && let Some(line) = spacing.line
+            {
+                builder.append_attr("w:line", line);
+            }
+            if spacing.line_rule != base.line_rule
+                && let Some(line_rule) = spacing.line_rule
+            {
+                builder.append_attr( "w:lineRule", line_rule );
+            }
+
+            if spacing.after != base.after
+                && let Some(after) = spacing.after
+            {
+                builder.append_attr( "w:after", after );
+            }
+
+            if spacing.before != base.before
+                && let Some(before) = spacing.before
+            {
+                builder.append_attr( "w:before", before );
+            }
+            builder
+        };
+
+        let process_sect_of_properties = |sect_properties: SectrOfProperties| {
+            let to_string_mul_by = |num: f32, mul: f32| ((num * mul) as usize).to_string();
+            // Children in the order of CT_SectPr.
+            let mut builder = word_xml::Element::new("w:sectPr");
+
+            let references = [
+                ("w:headerReference", &sect_properties.headers),
+                ("w:footerReference", &sect_properties.footers),
+            ];
+            for (name, references) in references {
+                let by_type = [
+                    ("default", &references.default),
+                    ("first", &references.first),
+                    ("even", &references.even),
+                ];
+                for (reference_type, id) in by_type {
+                    if let Some(id) = id {
+                        builder.append_element(
+                            word_xml::Element::new(name)
+                                .with_attr("w:type", reference_type)
+                                .with_attr("r:id", id),
+                        )
+                    }
+                }
+            }
+
+            if let Some(page_type) = &sect_properties.page_type {
+                builder.append_element(
+                    word_xml::Element::new("w:type").with_attr("w:val", page_type.to_string()),
+                )
+            }
+
+            let mut page_size = word_xml::Element::new("w:pgSz")
+                .with_attr("w:w", to_string_mul_by(sect_properties.get_size().0, 10.))
+                .with_attr("w:h", to_string_mul_by(sect_properties.get_size().1, 10.));
+            if let orientation @ Orientation::Landscape = sect_properties.page_size.orientation {
+                page_size.append_attr("w:orient", orientation);
+            }
+            builder.append_element(page_size);
+
+            let margin = &sect_properties.page_margin;
+            builder.append_element(
+                word_xml::Element::new("w:pgMar")
+                    .with_attr("w:top", to_string_mul_by(margin.top, 10.))
+                    .with_attr("w:right", to_string_mul_by(margin.right, 10.))
+                    .with_attr("w:bottom", to_string_mul_by(margin.bottom, 10.))
+                    .with_attr("w:left", to_string_mul_by(margin.left, 10.))
+                    .with_attr("w:header", to_string_mul_by(margin.header, 10.))
+                    .with_attr("w:footer", to_string_mul_by(margin.footer, 10.))
+                    .with_attr("w:gutter", to_string_mul_by(margin.gutter, 10.)),
+            );
+
+            if let Some(num_type) = &sect_properties.page_num_type {
+                builder.append_element(
+                    word_xml::Element::new("w:pgNumType").with_attr("w:fmt", num_type.to_string()),
+                )
+            }
+
+            if let Some(columns) = &sect_properties.columns {
+                let mut cols = word_xml::Element::new("w:cols")
+                    .with_attr("w:num", columns.count)
+                    .with_attr("w:space", to_string_mul_by(columns.space, 10.));
+                if columns.separator {
+                    cols.append_attr("w:sep", "1");
+                }
+                if !columns.equal_width {
+                    cols.append_attr("w:equalWidth", "0");
+                    for column in &columns.columns {
+                        cols.append_element(
+                            word_xml::Element::new("w:col")
+                                .with_attr("w:w", to_string_mul_by(column.width, 10.))
+                                .with_attr("w:space", to_string_mul_by(column.space, 10.)),
+                        );
+                    }
+                }
+                builder.append_element(cols);
+            }
+
+            if let Some(form_prot) = &sect_properties.form_prot {
+                builder.append_element(
+                    word_xml::Element::new("w:formProt").with_attr("w:val", form_prot.to_string()),
+                )
+            }
+
+            if sect_properties.title_page {
+                builder.append_element(word_xml::Element::new("w:titlePg"));
+            }
+
+            builder.with_element(
+                word_xml::Element::new("w:textDirection")
+                    .with_attr("w:val", sect_properties.text_direction.to_string()),
+            )
+        };
+
+        let process_ppr = |ppr: ParagraphProperties| {
+            let style = ppr.style.as_deref();
+            let base = self.styles.paragraph_properties(style);
+
+            // Children in the order of CT_PPr.
+            let mut builder = word_xml::Element::new("w:pPr");
+            if let Some(style) = style {
+                builder
+                    .append_element(word_xml::Element::new("w:pStyle").with_attr("w:val", style));
+            }
+            for (name, toggle, base) in [
+                ("w:keepNext", ppr.keep_next, base.keep_next),
+                ("w:keepLines", ppr.keep_lines, base.keep_lines),
+                ("w:pageBreakBefore", ppr.page_break_before, base.page_break_before),
+                ("w:widowControl", ppr.widow_control, base.widow_control),
+            ] {
+                if toggle != base {
+                    builder.append_element(toggle_element(name, toggle));
+                }
+            }
+            if ppr.numbering != base.numbering {
+                // `w:numId` 0 removes the numbering of the style.
+                let (level, num_id) = ppr
+                    .numbering
+                    .map_or((0, 0), |numbering| (numbering.level, numbering.num_id));
+                builder.append_element(
+                    word_xml::Element::new("w:numPr")
+                        .with_element(word_xml::Element::new("w:ilvl").with_attr("w:val", level))
+                        .with_element(word_xml::Element::new("w:numId").with_attr("w:val", num_id)),
+                );
+            }
+            // Stops replace the style's at their position or clear them.
+            let mut tabs = word_xml::Element::new("w:tabs");
+            for stop in &base.tabs {
+                if !ppr.tabs.iter().any(|tab| tab.position == stop.position) {
+                    tabs.append_element(
+                        word_xml::Element::new("w:tab")
+                            .with_attr("w:val", TabAlignment::Clear)
+                            .with_attr("w:pos", (stop.position * 10.).round() as i64),
+                    );
+                }
+            }
+            for stop in ppr.tabs.iter().filter(|stop| !base.tabs.contains(stop)) {
+                let mut tab = word_xml::Element::new("w:tab")
+                    .with_attr("w:val", stop.alignment)
+                    .with_attr("w:pos", (stop.position * 10.).round() as i64);
+                if stop.leader != TabLeader::None {
+                    tab.append_attr("w:leader", stop.leader);
+                }
+                tabs.append_element(tab);
+            }
+            if !tabs.inners.is_empty() {
+                builder.append_element(tabs);
+            }
+            builder.append_element(word_xml::Element::new("w:bidi").with_attr("w:val", "0"));
+            let spacing = process_spacing(ppr.spacing, &base.spacing);
+            if !spacing.attrs.is_empty() {
+                builder.append_element(spacing);
+            }
+            if ppr.indent != base.indent
+                && let Some(indent) = ppr.indent
+            {
+                let mut ind = word_xml::Element::new("w:ind")
+                    .with_attr("w:left", (indent.left * 10.).round() as i64)
+                    .with_attr("w:right", (indent.right * 10.).round() as i64);
+                if indent.hanging > 0. {
+                    ind.append_attr("w:hanging", (indent.hanging * 10.).round() as i64);
+                } else {
+                    ind.append_attr("w:firstLine", (indent.first_line * 10.).round() as i64);
+                }
+                builder.append_element(ind);
+            }
+            if ppr.justify != base.justify
+                && let Some(justify) = ppr.justify
+            {
+                builder.append_element(word_xml::Element::new("w:jc").with_attr("w:val", justify));
+            }
+            if let Some(rpr) = ppr.text_properties {
+                let base = self.style_text_properties(style, rpr.style.as_deref());
+                let rpr = process_rpr(rpr, &base);
+                if !rpr.inners.is_empty() {
+                    builder.append_element(rpr);
+                }
+            }
+            if let Some(section) = ppr.section {
+                builder.append_element(process_sect_of_properties(section));
+            }
+            builder
+        };
+
+        let text_element = |name: &str, text: &str| {
+            let t_elem = word_xml::Element::new(name).with_text(text);
+            if text.trim().len() == text.len() {
+                t_elem
+            } else {
+                t_elem.with_attr("xml:space", "preserve")
+            }
+        };
+
+        // First and last paragraph of every comment's range.
+        let mut comment_paragraphs = HashMap::<i64, (usize, usize)>::new();
+        for (par_idx, par) in self.paragraphs.iter().enumerate() {
+            let glyphs_views = par.words.iter().flat_map(|word| &word.glyphs_views);
+            for id in glyphs_views.flat_map(|glyphs_view| &glyphs_view.comments) {
+                comment_paragraphs
+                    .entry(*id)
+                    .and_modify(|(_, last)| *last = par_idx)
+                    .or_insert((par_idx, par_idx));
+            }
+        }
+
+        let paragraph_element = |par_idx: usize| {
+            let par = &self.paragraphs[par_idx];
+            let mut par_elem =
+                word_xml::Element::new("w:p").with_element(process_ppr(par.properties.clone()));
+            for bookmark in &par.properties.bookmarks {
+                par_elem.append_element(
+                    word_xml::Element::new("w:bookmarkStart")
+                        .with_attr("w:id", bookmark.id)
+                        .with_attr("w:name", &bookmark.name),
+                );
+            }
+
+            let run_properties = |prop: TextProperties| {
+                let paragraph_style = par.properties.style.as_deref();
+                let base = self.style_text_properties(paragraph_style, prop.style.as_deref());
+                process_rpr(prop, &base)
             };
+            let text_run = |prop: TextProperties, revision: &Option<Revision>, text_cont: &str| {
+                let name = match revision {
+                    Some(Revision {
+                        kind: RevisionKind::Deletion,
+                        ..
+                    }) => "w:delText",
+                    _ => "w:t",
+                };
+                word_xml::Element::new("w:r")
+                    .with_element(run_properties(prop))
+                    .with_element(text_element(name, text_cont))
+            };
+
+            let mut runs = Vec::new();
+            let mut text_cont = String::new();
+            let mut text_prop: Option<RunKey> = None;
+            for (word_idx, word) in par.words.iter().enumerate() {
+                if word_idx > 0 && !word.is_control() && !par.words[word_idx - 1].is_control() {
+                    text_cont += " ";
+                }
+                for glyph_view in &word.glyphs_views {
+                    let object = match (&glyph_view.image, &glyph_view.note) {
+                        (Some(image), _) => Some(image.drawing.clone()),
+                        (None, Some(note)) => Some(match note.id {
+                            Some(id) => word_xml::Element::new(note.kind.reference_name())
+                                .with_attr("w:id", id.to_string()),
+                            None => word_xml::Element::new(note.kind.mark_name()),
+                        }),
+                        (None, None) if word.is_tab() => Some(word_xml::Element::new("w:tab")),
+                        (None, None) => glyph_view.br.map(|br| match br {
+                            BreakType::TextWrapping => word_xml::Element::new("w:br"),
+                            br => word_xml::Element::new("w:br").with_attr("w:type", br),
+                        }),
+                    };
+                    let glyph_text = word
+                        .word
+                        .get(glyph_view.word_range.clone())
+                        .with_context(|| {
+                            format!("Glyph range outside of word {:?}", word.word)
+                        })?;
+                    if let Some(object) = object {
+                        if let Some((prop, link, revision, comments)) = text_prop.take() {
+                            let run = text_run(prop, &revision, &text_cont);
+                            runs.push((link, revision, comments, run));
+                            text_cont = String::new();
+                        }
 
-            if rpr.italic {
-                builder.append_element(word_xml::Element::new("w:i"));
+                        let r_elem = word_xml::Element::new("w:r")
+                            .with_element(run_properties(glyph_view.properties.clone()))
+                            .with_element(object);
+                        runs.push((
+                            glyph_view.link.clone(),
+                            glyph_view.revision.clone(),
+                            glyph_view.comments.clone(),
+                            r_elem,
+                        ));
+                        continue;
+                    }
+                    match text_prop.take() {
+                        Some((prop, link, revision, comments))
+                            if prop != glyph_view.properties
+                                || link != glyph_view.link
+                                || revision != glyph_view.revision
+                                || comments != glyph_view.comments =>
+                        {
+                            let run = text_run(prop, &revision, &text_cont);
+                            runs.push((link, revision, comments, run));
+                            text_cont = glyph_text.to_string();
+                        }
+                        _ => text_cont += glyph_text,
+                    }
+                    text_prop = Some((
+                        glyph_view.properties.clone(),
+                        glyph_view.link.clone(),
+                        glyph_view.revision.clone(),
+                        glyph_view.comments.clone(),
+                    ));
+                }
+            }
+            let (prop, link, revision, comments) = text_prop.unwrap_or_default();
+            let run = text_run(prop, &revision, &text_cont);
+            runs.push((link, revision, comments, run));
+            let runs = mark_comments(runs, |id| {
+                let (first, last) = comment_paragraphs.get(&id).copied().unwrap_or_default();
+                (first == par_idx, last == par_idx)
+            });
+            for element in wrap_runs(runs) {
+                par_elem.append_element(element);
             }
-
-            if let Some(size) = &rpr.size {
-                builder.append_element(
-                    word_xml::Element::new("w:sz").with_attr("w:val", size.to_string()),
+            for bookmark in &par.properties.bookmarks {
+                par_elem.append_element(
+                    word_xml::Element::new("w:bookmarkEnd").with_attr("w:id", bookmark.id),
                 );
             }
+            anyhow::Ok(par_elem)
+        };
 
-            if let Some(size_cs) = &rpr.size {
-                builder.append_element(
-                    word_xml::Element::new("w:szCs").with_attr("w:val", size_cs.to_string()),
-                )
-            }
+        let width_element = |name: &str, width: &docx_document::TableWidth| {
+            let value = match width.width_type {
+                docx_document::WidthType::Dxa => width.value * 10.,
+                _ => width.value,
+            };
+            word_xml::Element::new(name)
+                .with_attr("w:w", value as i64)
+                .with_attr("w:type", width.width_type)
+        };
 
-            if let Some(font_name) = &rpr.font_name {
-                builder.append_element(
-                    word_xml::Element::new("w:rFonts")
-                        .with_attr("w:ascii", font_name)
-                        .with_attr("w:hAnsi", font_name)
-                        .with_attr("w:cs", font_name),
-                )
+        let borders_element = |name: &str, borders: &docx_document::TableBorders| {
+            let mut builder = word_xml::Element::new(name);
+            for (name, border) in [
+                ("w:top", &borders.top),
+                ("w:left", &borders.left),
+                ("w:bottom", &borders.bottom),
+                ("w:right", &borders.right),
+                ("w:insideH", &borders.inside_h),
+                ("w:insideV", &borders.inside_v),
+            ] {
+                if let Some(border) = border {
+                    builder.append_element(
+                        word_xml::Element::new(name)
+                            .with_attr("w:val", &border.style)
+                            .with_attr("w:sz", border.size)
+                            .with_attr("w:space", "0")
+                            .with_attr(
+                                "w:color",
+                                border
+                                    .color
+                                    .map_or("auto".to_string(), |color| color.to_xml_val()),
+                            ),
+                    );
+                }
             }
-            if let Some(color) = &rpr.color {
+            builder
+        };
+
+        let margins_element = |name: &str, margins: &docx_document::CellMargins| {
+            let mut builder = word_xml::Element::new(name);
+            for (name, value) in [
+                ("w:top", margins.top),
+                ("w:left", margins.left),
+                ("w:bottom", margins.bottom),
+                ("w:right", margins.right),
+            ] {
                 builder.append_element(
-                    word_xml::Element::new("w:color").with_attr("w:val", color.to_xml_val()),
-                )
+                    word_xml::Element::new(name)
+                        .with_attr("w:w", (value * 10.) as i64)
+                        .with_attr("w:type", "dxa"),
+                );
             }
             builder
         };
 
-        let process_spacing = |spacing: SpacingProperties| {
-            let mut builder = word_xml::Element::new("w:spacing");
-
-            if let Some(line) = spacing.line {
-                builder.append_attr("w:line", line);
+        // Tables nested in a cell come after it, so they are built first.
+        let table_element = |table_idx: usize,
+                             paragraphs: Range<usize>,
+                             nested: &mut HashMap<usize, word_xml::Element>| {
+            let table = &self.tables[table_idx];
+            let properties = &table.properties;
+
+            let mut tbl_pr = word_xml::Element::new("w:tblPr");
+            if let Some(style) = &properties.style {
+                tbl_pr
+                    .append_element(word_xml::Element::new("w:tblStyle").with_attr("w:val", style));
             }
-            if let Some(line_rule) = spacing.line_rule {
-                builder.append_attr( "w:lineRule", line_rule );
+            if let Some(width) = &properties.width {
+                tbl_pr.append_element(width_element("w:tblW", width));
             }
-
-            if let Some(after) = spacing.after {
-                builder.append_attr( "w:after", after );
+            if let Some(justify) = &properties.justify {
+                tbl_pr.append_element(word_xml::Element::new("w:jc").with_attr("w:val", justify));
             }
+            tbl_pr.append_element(borders_element("w:tblBorders", &properties.borders));
+            tbl_pr.append_element(margins_element("w:tblCellMar", &properties.cell_margin));
 
-            if let Some(before) = spacing.before {
-                builder.append_attr( "w:before", before );
+            let mut tbl_grid = word_xml::Element::new("w:tblGrid");
+            for width in &table.grid {
+                tbl_grid.append_element(
+                    word_xml::Element::new("w:gridCol").with_attr("w:w", (width * 10.) as i64),
+                );
             }
-            builder
-        };
 
-        let process_ppr = |ppr: ParagraphProperties| {
-            let mut builder = word_xml::Element::new("w:pPr")
-                .with_element(word_xml::Element::new("w:pStyle").with_attr("w:val", "Normal"))
-                .with_element(word_xml::Element::new("w:bidi").with_attr("w:val", "0"));
+            let mut tbl_elem = word_xml::Element::new("w:tbl")
+                .with_element(tbl_pr)
+                .with_element(tbl_grid);
 
-            if let Some(justify) = ppr.justify {
-                builder.append_element(word_xml::Element::new("w:jc").with_attr("w:val", justify));
-            }
-            if let Some(rpr) = ppr.text_properties {
-                builder.append_element(process_rpr(rpr));
-            }
-            builder.with_element(process_spacing(ppr.spacing))
-        };
+            for (row_idx, row) in table.rows.iter().enumerate() {
+                let mut tr_pr = word_xml::Element::new("w:trPr");
+                if row.properties.cant_split {
+                    tr_pr.append_element(word_xml::Element::new("w:cantSplit"));
+                }
+                if let Some(height) = row.properties.height {
+                    tr_pr.append_element(
+                        word_xml::Element::new("w:trHeight")
+                            .with_attr("w:val", (height * 10.) as i64),
+                    );
+                }
+                if row.properties.header {
+                    tr_pr.append_element(word_xml::Element::new("w:tblHeader"));
+                }
+                let mut tr_elem = word_xml::Element::new("w:tr").with_element(tr_pr);
 
-        let process_sect_of_properties = |sect_properties: SectrOfProperties| {
-            let to_string_mul_by = |num: f32, mul: f32| ((num * mul) as usize).to_string();
-            let mut builder = word_xml::Element::new("w:sectPr");
+                for (cell_idx, cell) in row.cells.iter().enumerate() {
+                    let properties = &cell.properties;
 
-            if let Some(num_type) = &sect_properties.page_num_type {
-                builder.append_element(
-                    word_xml::Element::new("w:pgNumType").with_attr("w:fmt", num_type.to_string()),
-                )
-            }
+                    let mut tc_pr = word_xml::Element::new("w:tcPr");
+                    if let Some(width) = &properties.width {
+                        tc_pr.append_element(width_element("w:tcW", width));
+                    }
+                    if properties.grid_span > 1 {
+                        tc_pr.append_element(
+                            word_xml::Element::new("w:gridSpan")
+                                .with_attr("w:val", properties.grid_span),
+                        );
+                    }
+                    match properties.v_merge {
+                        Some(VerticalMerge::Restart) => tc_pr.append_element(
+                            word_xml::Element::new("w:vMerge")
+                                .with_attr("w:val", VerticalMerge::Restart),
+                        ),
+                        Some(VerticalMerge::Continue) => {
+                            tc_pr.append_element(word_xml::Element::new("w:vMerge"))
+                        }
+                        None => {}
+                    }
+                    tc_pr.append_element(borders_element("w:tcBorders", &properties.borders));
+                    if let Some(shading) = &properties.shading {
+                        tc_pr.append_element(
+                            word_xml::Element::new("w:shd")
+                                .with_attr("w:val", "clear")
+                                .with_attr("w:color", "auto")
+                                .with_attr("w:fill", shading.to_xml_val()),
+                        );
+                    }
+                    if let Some(margin) = &properties.margin {
+                        tc_pr.append_element(margins_element("w:tcMar", margin));
+                    }
 
-            if let Some(page_type) = &sect_properties.page_type {
-                builder.append_element(
-                    word_xml::Element::new("w:type").with_attr("w:val", page_type.to_string()),
-                )
-            }
-            if let Some(form_prot) = &sect_properties.form_prot {
-                builder.append_element(
-                    word_xml::Element::new("w:formProt").with_attr("w:val", form_prot.to_string()),
-                )
+                    let mut tc_elem = word_xml::Element::new("w:tc").with_element(tc_pr);
+                    let cell_ref = Some(CellRef {
+                        table: table_idx,
+                        row: row_idx,
+                        cell: cell_idx,
+                    });
+                    let mut ends_with_paragraph = false;
+                    let mut par_idx = paragraphs.start;
+                    while par_idx < paragraphs.end {
+                        let cell = self.paragraphs[par_idx].cell;
+                        if cell == cell_ref {
+                            tc_elem.append_element(paragraph_element(par_idx)?);
+                            ends_with_paragraph = true;
+                        } else if let Some(table) = self.table_in(cell, cell_ref) {
+                            if let Some(tbl_elem) = nested.remove(&table) {
+                                tc_elem.append_element(tbl_elem);
+                                ends_with_paragraph = false;
+                            }
+                            par_idx = self.table_end(par_idx, table);
+                            continue;
+                        }
+                        par_idx += 1;
+                    }
+                    // A cell must end with a paragraph.
+                    if !ends_with_paragraph {
+                        tc_elem.append_element(word_xml::Element::new("w:p"));
+                    }
+                    tr_elem.append_element(tc_elem);
+                }
+                tbl_elem.append_element(tr_elem);
             }
-
-            builder
-                .with_element(
-                    word_xml::Element::new("w:textDirection")
-                        .with_attr("w:val", sect_properties.text_direction.to_string()),
-                )
-                .with_element(
-                    word_xml::Element::new("w:pgSz")
-                        .with_attr("w:w", to_string_mul_by(sect_properties.get_size().0, 10.))
-                        .with_attr("w:h", to_string_mul_by(sect_properties.get_size().1, 10.)),
-                )
-                .with_element(
-                    word_xml::Element::new("w:pgMar")
-                        .with_attr(
-                            "w:top",
-                            to_string_mul_by(sect_properties.page_margin.top, 10.),
-                        )
-                        .with_attr(
-                            "w:right",
-                            to_string_mul_by(sect_properties.page_margin.right, 10.),
-                        )
-                        .with_attr(
-                            "w:bottom",
-                            to_string_mul_by(sect_properties.page_margin.bottom, 10.),
-                        )
-                        .with_attr(
-                            "w:left",
-                            to_string_mul_by(sect_properties.page_margin.left, 10.),
-                        )
-                        .with_attr(
-                            "w:header",
-                            to_string_mul_by(sect_properties.page_margin.header, 10.),
-                        )
-                        .with_attr(
-                            "w:footer",
-                            to_string_mul_by(sect_properties.page_margin.footer, 10.),
-                        )
-                        .with_attr(
-                            "w:gutter",
-                            to_string_mul_by(sect_properties.page_margin.gutter, 10.),
-                        ),
-                )
+            anyhow::Ok(tbl_elem)
         };
 
         let mut document = Self::WORD_DOCUMENT_DEFAULT
@@ -1109,58 +4073,35 @@ impl DocumentDraw {
             .get_child_mut("w:body")
             .context("Default document doesnot contain body. (how?) ;o")?;
 
-        for par in &self.paragraphs {
-            let mut par_elem =
-                word_xml::Element::new("w:p").with_element(process_ppr(par.properties.clone()));
-
-            let mut text_cont = String::new();
-            let mut text_prop: Option<TextProperties> = None;
-            for word in &par.words {
-                if text_cont.len() > 0 {
-                    text_cont += " ";
-                }
-                for glyph_view in &word.glyphs_views {
-                    if let Some(prop) = text_prop.clone() {
-                        if prop != glyph_view.properties {
-                            let mut r_elem = word_xml::Element::new("w:r");
-                            r_elem.append_element(
-                                word_xml::Element::new("w:rPr").with_element(process_rpr(prop)),
-                            );
-                            par_elem.append_element(r_elem.with_element(
-                                word_xml::Element::new("w:t").with_text(text_cont.as_str()),
-                            ));
+        let mut tables = HashMap::new();
+        for table_idx in (0..self.tables.len()).rev() {
+            let Some(start) = self
+                .paragraphs
+                .iter()
+                .position(|paragraph| self.is_in_table(paragraph.cell, table_idx))
+            else {
+                continue;
+            };
+            let end = self.table_end(start, table_idx);
+            let tbl_elem = table_element(table_idx, start..end, &mut tables)?;
+            tables.insert(table_idx, tbl_elem);
+        }
 
-                            text_prop = Some(glyph_view.properties.clone());
-                            text_cont = word
-                                .word
-                                .get(glyph_view.word_range.clone())
-                                .expect("Failed to get word range from glyph")
-                                .to_string();
-                        } else {
-                            text_cont += word
-                                .word
-                                .get(glyph_view.word_range.clone())
-                                .expect("Failed to get word range from glyph");
-                        }
-                    } else {
-                        text_prop = Some(glyph_view.properties.clone());
-                        text_cont = word
-                            .word
-                            .get(glyph_view.word_range.clone())
-                            .expect("Failed to get word range from glyph")
-                            .to_string();
+        let mut par_idx = 0;
+        while par_idx < self.paragraphs.len() {
+            match self.table_in(self.paragraphs[par_idx].cell, None) {
+                Some(table) => {
+                    let end = self.table_end(par_idx, table);
+                    if let Some(tbl_elem) = tables.remove(&table) {
+                        body.append_element(tbl_elem);
                     }
+                    par_idx = end;
+                }
+                None => {
+                    body.append_element(paragraph_element(par_idx)?);
+                    par_idx += 1;
                 }
             }
-            let mut r_elem = word_xml::Element::new("w:r");
-            r_elem.append_element(
-                word_xml::Element::new("w:rPr")
-                    .with_element(process_rpr(text_prop.unwrap_or_default())),
-            );
-            par_elem.append_element(
-                r_elem.with_element(word_xml::Element::new("w:t").with_text(text_cont.as_str())),
-            );
-            body.append_element(par_elem);
         }
 
         println!("\n{:?}", self.sect_properties.clone());
@@ -1169,10 +4110,189 @@ impl DocumentDraw {
         Ok(document)
     }
 
+    /// `comments.xml` and `commentsExtended.xml` of the comments anchored in
+    /// the document, and if `document` has no such parts yet its
+    /// relationships and content types with them added. Nothing if there
+    /// are no comments to save or remove.
+    pub fn get_comment_parts(
+        &self,
+        document: &state::Document,
+    ) -> anyhow::Result<Vec<(String, Vec<u8>)>> {
+        const RELATIONSHIPS_PART: &str = "word/_rels/document.xml.rels";
+        const CONTENT_TYPES_PART: &str = "[Content_Types].xml";
+        const RELATIONSHIPS_DEFAULT: &str = concat!(
+            r#"<?xml version="1.0" encoding="UTF-8" standalone="yes"?>"#,
+            r#"<Relationships "#,
+            r#"xmlns="http://schemas.openxmlformats.org/package/2006/relationships">"#,
+            r#"</Relationships>"#,
+        );
+
+        let relationships = &document.document.relationships;
+        let existing = ["comments", "commentsExtended"].map(|rel_type| {
+            relationships
+                .get_of_type(rel_type)
+                .find_map(|(id, _)| relationships.get_part(id))
+        });
+        let comments = self
+            .get_comment_anchors()
+            .into_iter()
+            .map(|(comment, _)| comment.clone())
+            .collect::<Vec<_>>();
+        if comments.is_empty() && existing[0].is_none() {
+            return Ok(Vec::new());
+        }
+
+        let read_part = |name: &str| -> anyhow::Result<Option<word_xml::WordXMLDocument>> {
+            let mut archive = zip::ZipArchive::new(io::Cursor::new(&document.zip_document))?;
+            let Ok(mut file) = archive.by_name(name) else {
+                return Ok(None);
+            };
+            let mut bytes = Vec::new();
+            file.read_to_end(&mut bytes)?;
+            Ok(Some(word_xml::WordXMLDocument::parse_part_bytes(name, &bytes)?))
+        };
+
+        let comments_read = match &existing[0] {
+            Some(name) => read_part(name)?,
+            None => None,
+        };
+        let options = word_xml::WriteOptions::default();
+        let new_parts = [
+            (
+                Comment::comments_part(&comments, comments_read)?,
+                Comment::RELATIONSHIP_TYPE,
+                Comment::CONTENT_TYPE,
+                "comments.xml",
+            ),
+            (
+                Comment::comments_extended_part(&comments)?,
+                Comment::EXTENDED_RELATIONSHIP_TYPE,
+                Comment::EXTENDED_CONTENT_TYPE,
+                "commentsExtended.xml",
+            ),
+        ];
+        let mut parts = Vec::new();
+        let mut rels: Option<word_xml::WordXMLDocument> = None;
+        let mut content_types: Option<word_xml::WordXMLDocument> = None;
+        let mut rel_idx = relationships.relationships.len();
+        let parts_to_save = existing.into_iter().zip(new_parts);
+        for (existing, (part, rel_type, content_type, target)) in parts_to_save {
+            let name = match existing {
+                Some(name) => name,
+                None => {
+                    let id = loop {
+                        rel_idx += 1;
+                        let id = format!("rId{}", rel_idx);
+                        if !relationships.relationships.contains_key(&id) {
+                            break id;
+                        }
+                    };
+                    let rels = match &mut rels {
+                        Some(rels) => rels,
+                        None => rels.insert(match read_part(RELATIONSHIPS_PART)? {
+                            Some(rels) => rels,
+                            None => RELATIONSHIPS_DEFAULT.parse()?,
+                        }),
+                    };
+                    rels.root.append_element(
+                        word_xml::Element::new("Relationship")
+                            .with_attr("Id", id)
+                            .with_attr("Type", rel_type)
+                            .with_attr("Target", target),
+                    );
+
+                    let content_types = match &mut content_types {
+                        Some(content_types) => content_types,
+                        None => content_types.insert(
+                            read_part(CONTENT_TYPES_PART)?
+                                .context("Document has no content types")?,
+                        ),
+                    };
+                    let name = format!("word/{}", target);
+                    content_types.root.append_element(
+                        word_xml::Element::new("Override")
+                            .with_attr("PartName", format!("/{}", name))
+                            .with_attr("ContentType", content_type),
+                    );
+                    name
+                }
+            };
+            parts.push((name, part.to_bytes(&options)?));
+        }
+        if let Some(rels) = rels {
+            parts.push((RELATIONSHIPS_PART.to_string(), rels.to_bytes(&options)?));
+        }
+        if let Some(content_types) = content_types {
+            parts.push((CONTENT_TYPES_PART.to_string(), content_types.to_bytes(&options)?));
+        }
+        Ok(parts)
+    }
+
+    /// Properties of every section in document order, the body `w:sectPr`
+    /// last.
+    pub fn sections(&self) -> Vec<SectrOfProperties> {
+        self.paragraphs
+            .iter()
+            .filter(|paragraph| paragraph.ends_section())
+            .filter_map(|paragraph| paragraph.properties.section.clone())
+            .chain([self.sect_properties.clone()])
+            .collect()
+    }
+
+    /// Run properties the styles give a run of character style `run_style`
+    /// in a paragraph of style `paragraph_style`.
+    fn style_text_properties(
+        &self,
+        paragraph_style: Option<&str>,
+        run_style: Option<&str>,
+    ) -> TextProperties {
+        let mut properties = self.styles.text_properties(paragraph_style, run_style);
+        properties.resolve_theme_fonts(&self.theme_fonts);
+        properties
+    }
+
+    /// Whether a paragraph in `cell` is in table `table`, in one of its
+    /// cells or in a table nested in them.
+    fn is_in_table(&self, cell: Option<CellRef>, table: usize) -> bool {
+        let mut cell = cell;
+        while let Some(curr) = cell {
+            if curr.table == table {
+                return true;
+            }
+            cell = self.tables[curr.table].parent;
+        }
+        false
+    }
+
+    /// Table right in `parent`, or in the body for none, that a paragraph in
+    /// `cell` is in.
+    fn table_in(&self, cell: Option<CellRef>, parent: Option<CellRef>) -> Option<usize> {
+        let mut cell = cell?;
+        loop {
+            let outer = self.tables[cell.table].parent;
+            if outer == parent {
+                return Some(cell.table);
+            }
+            cell = outer?;
+        }
+    }
+
+    /// Index after the run of paragraphs of table `table` that goes on at
+    /// `par_idx`.
+    fn table_end(&self, par_idx: usize, table: usize) -> usize {
+        self.paragraphs[par_idx..]
+            .iter()
+            .position(|paragraph| !self.is_in_table(paragraph.cell, table))
+            .map_or(self.paragraphs.len(), |len| par_idx + len)
+    }
+
     pub fn clear_document(&mut self) {
         let mut idx = 0;
         while idx < self.paragraphs.len() {
-            if self.paragraphs[idx].words.is_empty() {
+            // Empty cells keep their paragraph so the table stays laid out,
+            // and empty section breaks so the sections stay apart.
+            let paragraph = &self.paragraphs[idx];
+            if paragraph.words.is_empty() && paragraph.cell.is_none() && !paragraph.ends_section() {
                 self.paragraphs.remove(idx);
             } else {
                 idx += 1;
@@ -1224,6 +4344,9 @@ impl DocumentDraw {
                         word_glyphs.push(GlyphsView {
                             word_range: glyphs_view.word_range.clone(),
                             properties: glyphs_view.properties.clone(),
+                            link: glyphs_view.link.clone(),
+                            revision: glyphs_view.revision.clone(),
+                            comments: glyphs_view.comments.clone(),
                             ..Default::default()
                         });
                     } else if glyphs_view.word_range.start < end
@@ -1232,11 +4355,17 @@ impl DocumentDraw {
                         word_glyphs.push(GlyphsView {
                             word_range: glyphs_view.word_range.start..end,
                             properties: glyphs_view.properties.clone(),
+                            link: glyphs_view.link.clone(),
+                            revision: glyphs_view.revision.clone(),
+                            comments: glyphs_view.comments.clone(),
                             ..Default::default()
                         });
                         new_word.glyphs_views.push(GlyphsView {
                             word_range: 0..(glyphs_view.word_range.end - end),
                             properties: glyphs_view.properties.clone(),
+                            link: glyphs_view.link.clone(),
+                            revision: glyphs_view.revision.clone(),
+                            comments: glyphs_view.comments.clone(),
                             ..Default::default()
                         });
                     } else {
@@ -1244,6 +4373,9 @@ impl DocumentDraw {
                             word_range: (glyphs_view.word_range.start - end)
                                 ..(glyphs_view.word_range.end - end),
                             properties: glyphs_view.properties.clone(),
+                            link: glyphs_view.link.clone(),
+                            revision: glyphs_view.revision.clone(),
+                            comments: glyphs_view.comments.clone(),
                             ..Default::default()
                         });
                     }
@@ -1482,84 +4614,409 @@ impl DocumentDraw {
                         return CursorTargetIdx::LineEnd { end: word_idx };
                     }
                 }
-                Nothing => {}
+                Nothing => {}
+            }
+        }
+        CursorTargetIdx::Nothing
+    }
+
+    pub fn change_char(&mut self, char_delta: i64) {
+        let cursor = self.get_cursor_pos();
+
+        if cursor.char_idx as i64 + char_delta < 0 {
+            self.change_line(-1);
+            self.get_cursor_pos_mut().char_idx = self.get_curr_line_len();
+        } else if cursor.char_idx as i64 + char_delta >= self.get_curr_line_len() as i64 {
+            self.change_line(1);
+            self.get_cursor_pos_mut().char_idx = 0;
+        } else {
+            self.get_cursor_pos_mut().char_idx = (cursor.char_idx as i64 + char_delta) as usize;
+        }
+    }
+
+    fn get_curr_line_len(&self) -> usize {
+        let cursor = self.get_cursor_pos();
+        let paragraph = &self.paragraphs[cursor.par_idx];
+        let words = &paragraph.words;
+        let line = &paragraph.lines[cursor.line_idx];
+
+        let mut len = 0;
+        for word in &words[line.range.clone()] {
+            for glyphs_view in &word.glyphs_views {
+                len += glyphs_view.primitive.get_glyphs().unwrap_or_default().len();
+            }
+            len += 1;
+        }
+
+        len
+    }
+
+    fn get_curr_par_lines_len(&self) -> usize {
+        self.paragraphs[self.get_cursor_pos().par_idx].lines.len()
+    }
+
+    fn get_cursor_pos_mut(&mut self) -> &mut CursorPos {
+        match &mut self.cursor {
+            Cursor::View(cursor)
+            | Cursor::Normal(cursor)
+            | Cursor::Edit(cursor)
+            | Cursor::Select { end: cursor, .. } => cursor,
+        }
+    }
+
+    fn get_cursor_pos(&self) -> &CursorPos {
+        match &self.cursor {
+            Cursor::View(cursor)
+            | Cursor::Normal(cursor)
+            | Cursor::Edit(cursor)
+            | Cursor::Select { end: cursor, .. } => cursor,
+        }
+    }
+
+    pub fn change_line(&mut self, line_delta: i64) {
+        let cursor = self.get_cursor_pos();
+
+        if cursor.line_idx as i64 + line_delta < 0 {
+            self.change_par(-1);
+            self.get_cursor_pos_mut().char_idx = self.get_curr_par_lines_len();
+        } else if cursor.line_idx as i64 + line_delta >= self.get_curr_par_lines_len() as i64 {
+            self.change_par(1);
+            self.get_cursor_pos_mut().line_idx = 0;
+        } else {
+            self.get_cursor_pos_mut().line_idx = (cursor.line_idx as i64 + line_delta) as usize;
+        }
+    }
+
+    pub fn change_par(&mut self, par_delta: i64) {
+        self.get_cursor_pos_mut().par_idx = (self.get_cursor_pos().par_idx as i64 + par_delta)
+            .max(0)
+            .min(self.paragraphs.len() as i64 - 1)
+            as usize;
+    }
+
+    /// Paragraph and word index of the word under the cursor.
+    fn get_cursor_word_idx(&self) -> Option<(usize, usize)> {
+        let cursor = self.get_cursor_pos();
+        let paragraph = self.paragraphs.get(cursor.par_idx)?;
+        let offset = paragraph.lines.get(cursor.line_idx)?.range.start;
+
+        let CursorTargetIdx::WordTarget { word, .. } = self.get_cursor_target() else {
+            return None;
+        };
+        Some((cursor.par_idx, offset + word))
+    }
+
+    /// Glyphs view of the text under the cursor.
+    fn get_cursor_glyphs_view(&self) -> Option<&GlyphsView> {
+        let (par_idx, word_idx) = self.get_cursor_word_idx()?;
+        let CursorTargetIdx::WordTarget { idx, .. } = self.get_cursor_target() else {
+            return None;
+        };
+        let word = &self.paragraphs[par_idx].words[word_idx];
+        let (byte_idx, _) = word.word.grapheme_indices(true).nth(idx)?;
+        word.glyphs_views
+            .iter()
+            .find(|glyphs_view| glyphs_view.word_range.contains(&byte_idx))
+    }
+
+    /// Hyperlink of the text under the cursor.
+    pub fn get_cursor_link(&self) -> Option<&Hyperlink> {
+        self.get_cursor_glyphs_view()?.link.as_ref()
+    }
+
+    /// Bookmarks in document order, without the hidden ones.
+    pub fn get_bookmarks(&self) -> impl Iterator<Item = &Bookmark> {
+        self.paragraphs
+            .iter()
+            .flat_map(|paragraph| &paragraph.properties.bookmarks)
+            .filter(|bookmark| !bookmark.is_hidden())
+    }
+
+    /// Move the cursor to the paragraph the bookmark `name` starts in and
+    /// remember where it was. False if there is no such bookmark.
+    pub fn go_to_bookmark(&mut self, name: &str) -> bool {
+        let Some(par_idx) = self.paragraphs.iter().position(|paragraph| {
+            paragraph.properties.bookmarks.iter().any(|bookmark| bookmark.name == name)
+        }) else {
+            return false;
+        };
+
+        self.link_history.push(self.get_cursor_pos().clone());
+        self.go_to(CursorPos {
+            par_idx,
+            ..Default::default()
+        });
+        true
+    }
+
+    /// Move the cursor back to where the last link was followed from. False
+    /// if no link was followed.
+    pub fn jump_back(&mut self) -> bool {
+        let Some(pos) = self.link_history.pop() else {
+            return false;
+        };
+        self.go_to(pos);
+        true
+    }
+
+    /// Put the cursor at `pos` and scroll its line to the top of the window.
+    fn go_to(&mut self, pos: CursorPos) {
+        let paragraph = &self.paragraphs[pos.par_idx];
+        let top = paragraph
+            .lines
+            .get(pos.line_idx)
+            .into_iter()
+            .flat_map(|line| &paragraph.words[line.range.clone()])
+            .flat_map(|word| &word.glyphs_views)
+            .map(|glyphs_view| glyphs_view.primitive.get_rect().y())
+            .next();
+        if let Some(top) = top {
+            self.scroll -= top - Self::JUMP_TOP;
+        }
+        *self.get_cursor_pos_mut() = pos;
+    }
+
+    /// Cursor positions where tracked changes start, in document order.
+    fn get_revision_starts(&self) -> Vec<(CursorPos, &Revision)> {
+        let mut starts = Vec::new();
+        for (par_idx, paragraph) in self.paragraphs.iter().enumerate() {
+            let mut prev = None;
+            for (line_idx, line) in paragraph.lines.iter().enumerate() {
+                let mut char_idx = 0;
+                for word in &paragraph.words[line.range.clone()] {
+                    for glyphs_view in &word.glyphs_views {
+                        let revision = glyphs_view.revision.as_ref();
+                        if let Some(revision) = revision
+                            && prev != Some(revision)
+                        {
+                            let before = word.word.get(..glyphs_view.word_range.start);
+                            starts.push((
+                                CursorPos {
+                                    par_idx,
+                                    line_idx,
+                                    char_idx: char_idx
+                                        + before.unwrap_or_default().graphemes(true).count(),
+                                },
+                                revision,
+                            ));
+                        }
+                        prev = revision;
+                    }
+                    char_idx += word.word.graphemes(true).count() + 1;
+                }
             }
         }
-        CursorTargetIdx::Nothing
+        starts
     }
 
-    pub fn change_char(&mut self, char_delta: i64) {
+    /// Move the cursor to the start of the next tracked change, or of the
+    /// previous one for a negative `delta`. The change moved to if any.
+    pub fn go_to_revision(&mut self, delta: i64) -> Option<Revision> {
         let cursor = self.get_cursor_pos();
+        let key = |pos: &CursorPos| (pos.par_idx, pos.line_idx, pos.char_idx);
+        let cursor = key(cursor);
 
-        if cursor.char_idx as i64 + char_delta < 0 {
-            self.change_line(-1);
-            self.get_cursor_pos_mut().char_idx = self.get_curr_line_len();
-        } else if cursor.char_idx as i64 + char_delta >= self.get_curr_line_len() as i64 {
-            self.change_line(1);
-            self.get_cursor_pos_mut().char_idx = 0;
+        let starts = self.get_revision_starts();
+        let (pos, revision) = if delta > 0 {
+            starts.into_iter().find(|(pos, _)| key(pos) > cursor)
         } else {
-            self.get_cursor_pos_mut().char_idx = (cursor.char_idx as i64 + char_delta) as usize;
-        }
+            starts.into_iter().rev().find(|(pos, _)| key(pos) < cursor)
+        }?;
+        let revision = revision.clone();
+        self.go_to(pos);
+        Some(revision)
     }
 
-    fn get_curr_line_len(&self) -> usize {
-        let cursor = self.get_cursor_pos();
-        let paragraph = &self.paragraphs[cursor.par_idx];
-        let words = &paragraph.words;
-        let line = &paragraph.lines[cursor.line_idx];
+    /// Accept or reject the tracked change under the cursor, or every one
+    /// with `all`. An accepted insertion or rejected deletion becomes plain
+    /// text, the text of the others is removed. Returns the paragraphs that
+    /// changed, to create their words' primitives again.
+    pub fn resolve_revisions(&mut self, accept: bool, all: bool) -> Vec<usize> {
+        let target = if all {
+            None
+        } else {
+            match self.get_cursor_glyphs_view() {
+                Some(GlyphsView {
+                    revision: Some(revision),
+                    ..
+                }) => Some(revision.clone()),
+                _ => return Vec::new(),
+            }
+        };
 
-        let mut len = 0;
-        for word in &words[line.range.clone()] {
-            for glyphs_view in &word.glyphs_views {
-                len += glyphs_view.primitive.get_glyphs().unwrap_or_default().len();
+        let mut changed = Vec::new();
+        for (par_idx, paragraph) in self.paragraphs.iter_mut().enumerate() {
+            let mut par_changed = false;
+            for word in &mut paragraph.words {
+                let glyphs_views = std::mem::take(&mut word.glyphs_views);
+                let mut text = String::new();
+                for mut glyphs_view in glyphs_views {
+                    let content = word.word.get(glyphs_view.word_range.clone());
+                    let resolved = match &glyphs_view.revision {
+                        Some(revision) => target.as_ref().is_none_or(|target| target == revision),
+                        None => false,
+                    };
+                    if resolved {
+                        par_changed = true;
+                        let inserted = glyphs_view
+                            .revision
+                            .take()
+                            .is_some_and(|revision| revision.kind == RevisionKind::Insertion);
+                        if inserted != accept {
+                            continue;
+                        }
+                    }
+                    let start = text.len();
+                    text += content.unwrap_or_default();
+                    glyphs_view.word_range = start..text.len();
+                    word.glyphs_views.push(glyphs_view);
+                }
+                word.word = text;
+            }
+            if par_changed {
+                // Words all in removed text go with their views.
+                paragraph.words.retain(|word| !word.glyphs_views.is_empty());
+                changed.push(par_idx);
             }
-            len += 1;
         }
+        changed
+    }
 
-        len
+    /// Thread under the cursor by the comment it starts with. Where threads
+    /// overlap it is the one commented on or answered last.
+    fn get_cursor_thread(&self) -> Option<i64> {
+        let id = *self.get_cursor_glyphs_view()?.comments.last()?;
+        Some(self.get_thread_root(id))
     }
 
-    fn get_curr_par_lines_len(&self) -> usize {
-        self.paragraphs[self.get_cursor_pos().par_idx].lines.len()
+    /// Comment the thread of comment `id` starts with.
+    fn get_thread_root(&self, id: i64) -> i64 {
+        let mut id = id;
+        while let Some(parent) = self
+            .comments
+            .iter()
+            .find(|comment| comment.id == id)
+            .and_then(|comment| comment.parent)
+        {
+            id = parent;
+        }
+        id
     }
 
-    fn get_cursor_pos_mut(&mut self) -> &mut CursorPos {
-        match &mut self.cursor {
-            Cursor::View(cursor)
-            | Cursor::Normal(cursor)
-            | Cursor::Edit(cursor)
-            | Cursor::Select { end: cursor, .. } => cursor,
+    /// Comments of the thread `root` starts, with the replies to replies.
+    fn get_thread(&self, root: i64) -> Vec<i64> {
+        self.comments
+            .iter()
+            .map(|comment| comment.id)
+            .filter(|id| self.get_thread_root(*id) == root)
+            .collect()
+    }
+
+    /// Glyphs views of the document's paragraphs.
+    fn glyphs_views_mut(&mut self) -> impl Iterator<Item = &mut GlyphsView> {
+        self.paragraphs
+            .iter_mut()
+            .flat_map(|paragraph| &mut paragraph.words)
+            .flat_map(|word| &mut word.glyphs_views)
+    }
+
+    /// Comment the word under the cursor with `text`, or with `reply` answer
+    /// the thread of the comment under it on the same text. The new
+    /// comment's id, none if there is nothing to comment or answer.
+    pub fn add_comment(&mut self, text: &str, reply: bool) -> Option<i64> {
+        let id = self.comments.iter().map(|comment| comment.id + 1).max().unwrap_or(0);
+        if reply {
+            let parent = self.get_cursor_thread()?;
+            self.glyphs_views_mut()
+                .filter(|glyphs_view| glyphs_view.comments.contains(&parent))
+                .for_each(|glyphs_view| glyphs_view.comments.push(id));
+            self.comments.push(Comment::new(id, text, Some(parent)));
+        } else {
+            let (par_idx, word_idx) = self.get_cursor_word_idx()?;
+            self.paragraphs[par_idx].words[word_idx]
+                .glyphs_views
+                .iter_mut()
+                .for_each(|glyphs_view| glyphs_view.comments.push(id));
+            self.comments.push(Comment::new(id, text, None));
         }
+        Some(id)
     }
 
-    fn get_cursor_pos(&self) -> &CursorPos {
-        match &self.cursor {
-            Cursor::View(cursor)
-            | Cursor::Normal(cursor)
-            | Cursor::Edit(cursor)
-            | Cursor::Select { end: cursor, .. } => cursor,
+    /// Replace the text of the comment the thread under the cursor starts
+    /// with. Replies keep the text they were written with. False if there is
+    /// no comment.
+    pub fn edit_comment(&mut self, text: &str) -> bool {
+        let Some(root) = self.get_cursor_thread() else {
+            return false;
+        };
+        let comment = self.comments.iter_mut().find(|comment| comment.id == root);
+        if let Some(comment) = comment {
+            comment.paragraphs = text.lines().map(ToString::to_string).collect();
+            comment.element = None;
         }
+        true
     }
 
-    pub fn change_line(&mut self, line_delta: i64) {
-        let cursor = self.get_cursor_pos();
+    /// Mark the thread under the cursor resolved, or open again if it was.
+    /// Whether it is resolved now, none if there is no comment.
+    pub fn resolve_comment(&mut self) -> Option<bool> {
+        let root = self.get_cursor_thread()?;
+        let done = !self.comments.iter().any(|comment| comment.id == root && comment.done);
+        let thread = self.get_thread(root);
+        for comment in &mut self.comments {
+            if thread.contains(&comment.id) {
+                comment.done = done;
+            }
+        }
+        Some(done)
+    }
 
-        if cursor.line_idx as i64 + line_delta < 0 {
-            self.change_par(-1);
-            self.get_cursor_pos_mut().char_idx = self.get_curr_par_lines_len();
-        } else if cursor.line_idx as i64 + line_delta >= self.get_curr_par_lines_len() as i64 {
-            self.change_par(1);
-            self.get_cursor_pos_mut().line_idx = 0;
-        } else {
-            self.get_cursor_pos_mut().line_idx = (cursor.line_idx as i64 + line_delta) as usize;
+    /// Remove the thread under the cursor, the comment it starts with and
+    /// every reply in it. False if there is no comment.
+    pub fn delete_comment(&mut self) -> bool {
+        let Some(root) = self.get_cursor_thread() else {
+            return false;
+        };
+        let removed = self.get_thread(root);
+        self.comments.retain(|comment| !removed.contains(&comment.id));
+        self.glyphs_views_mut()
+            .for_each(|glyphs_view| glyphs_view.comments.retain(|id| !removed.contains(id)));
+        true
+    }
+
+    /// Comments with the top of the text they are on, in document order.
+    pub fn get_comment_anchors(&self) -> Vec<(&Comment, f32)> {
+        let mut anchors = Vec::<(&Comment, f32)>::new();
+        let glyphs_views = self
+            .paragraphs
+            .iter()
+            .flat_map(|paragraph| &paragraph.words)
+            .flat_map(|word| &word.glyphs_views);
+        for glyphs_view in glyphs_views {
+            for id in &glyphs_view.comments {
+                if anchors.iter().any(|(comment, _)| comment.id == *id) {
+                    continue;
+                }
+                if let Some(comment) = self.comments.iter().find(|comment| comment.id == *id) {
+                    anchors.push((comment, glyphs_view.primitive.get_rect().y()));
+                }
+            }
         }
+        anchors
     }
 
-    pub fn change_par(&mut self, par_delta: i64) {
-        self.get_cursor_pos_mut().par_idx = (self.get_cursor_pos().par_idx as i64 + par_delta)
-            .max(0)
-            .min(self.paragraphs.len() as i64 - 1)
-            as usize;
+    /// Keep the cursor on the document after its content changed.
+    pub fn clamp_cursor(&mut self) {
+        let par_idx = self.get_cursor_pos().par_idx.min(self.paragraphs.len().saturating_sub(1));
+        let lines = self.paragraphs.get(par_idx).map_or(0, |paragraph| paragraph.lines.len());
+        let cursor = self.get_cursor_pos_mut();
+        cursor.par_idx = par_idx;
+        cursor.line_idx = cursor.line_idx.min(lines.saturating_sub(1));
+        if lines > 0 {
+            let len = self.get_curr_line_len();
+            let cursor = self.get_cursor_pos_mut();
+            cursor.char_idx = cursor.char_idx.min(len.saturating_sub(1));
+        }
     }
 
     pub fn prims(&self) -> PrimIter<'_> {
@@ -1571,7 +5028,18 @@ impl DocumentDraw {
 
     pub fn for_prims<'document>(&'document self, mut f: impl FnMut(&'document Primitive)) {
         for page in &self.pages {
-            f(&page.primitive)
+            f(&page.primitive);
+            page.separators.iter().for_each(&mut f);
+        }
+
+        for table in &self.tables {
+            for prim in &table.primitives {
+                f(prim);
+            }
+        }
+
+        for separator in &self.note_separators {
+            f(separator);
         }
 
         for cursor_prim in &self.cursor_prims {
@@ -1579,9 +5047,20 @@ impl DocumentDraw {
             f(cursor_prim);
         }
 
-        for par in &self.paragraphs {
-            for word in &par.words {
+        let decorations = self
+            .page_decorations
+            .iter()
+            .flat_map(|decoration| decoration.header.iter().chain(&decoration.footer))
+            .flat_map(|header_footer| &header_footer.paragraphs);
+        let notes = self
+            .notes
+            .iter()
+            .filter(|note| note.laid_out)
+            .flat_map(|note| &note.paragraphs);
+        for par in self.paragraphs.iter().chain(notes).chain(decorations) {
+            for word in par.label.iter().map(|label| &label.word).chain(&par.words) {
                 for glyphs_view in &word.glyphs_views {
+                    glyphs_view.decorations.iter().for_each(&mut f);
                     f(&glyphs_view.primitive)
                 }
             }
@@ -1593,12 +5072,31 @@ impl DocumentDraw {
         mut f: impl FnMut(&'document mut Primitive),
     ) {
         for page in &mut self.pages {
-            f(&mut page.primitive)
+            f(&mut page.primitive);
+            page.separators.iter_mut().for_each(&mut f);
+        }
+
+        for table in &mut self.tables {
+            for prim in &mut table.primitives {
+                f(prim);
+            }
         }
 
-        for par in &mut self.paragraphs {
-            for word in &mut par.words {
+        for separator in &mut self.note_separators {
+            f(separator);
+        }
+
+        let decorations = self
+            .page_decorations
+            .iter_mut()
+            .flat_map(|decoration| decoration.header.iter_mut().chain(&mut decoration.footer))
+            .flat_map(|header_footer| &mut header_footer.paragraphs);
+        let notes = self.notes.iter_mut().flat_map(|note| &mut note.paragraphs);
+        for par in self.paragraphs.iter_mut().chain(notes).chain(decorations) {
+            let label = par.label.iter_mut().map(|label| &mut label.word);
+            for word in label.chain(&mut par.words) {
                 for glyphs_view in &mut word.glyphs_views {
+                    glyphs_view.decorations.iter_mut().for_each(&mut f);
                     f(&mut glyphs_view.primitive)
                 }
             }
@@ -1641,14 +5139,25 @@ impl<'a> Iterator for PrimIter<'a> {
 pub struct PageProperties {
     pub size: math::Size,
     pub paddings: math::Paddings,
+    /// Start, from the left padding, and width of every column. Empty for a
+    /// single column.
+    pub columns: Vec<(f32, f32)>,
+    pub column_separator: bool,
 }
 
 impl From<Option<docx_document::SectrOfProperties>> for PageProperties {
     fn from(page_properties: Option<docx_document::SectrOfProperties>) -> Self {
         if let Some(page_properties) = page_properties {
+            let (width, _) = page_properties.get_size();
+            let (_, right, _, left) = page_properties.get_margins();
+            let columns = page_properties.columns.as_ref();
             Self {
                 size: page_properties.get_size().into(),
                 paddings: page_properties.get_margins().into(),
+                columns: columns.map_or_else(Vec::new, |columns| {
+                    columns.get_layout(width - left - right)
+                }),
+                column_separator: columns.is_some_and(|columns| columns.separator),
             }
         } else {
             PageProperties::default()
@@ -1661,6 +5170,8 @@ impl Default for PageProperties {
         Self {
             size: (100., 100.).into(),
             paddings: (10.).into(),
+            columns: Vec::new(),
+            column_separator: false,
         }
     }
 }
@@ -1674,11 +5185,24 @@ impl Default for DocumentDraw {
             scale: 1.,
 
             sect_properties: Default::default(),
+            headers_footers: Default::default(),
+            even_and_odd_headers: Default::default(),
+            default_tab_stop: Default::default(),
+            do_not_expand_shift_return: Default::default(),
+            page_decorations: Default::default(),
+            notes: Default::default(),
+            note_separators: Default::default(),
             pages: Default::default(),
             fonts: Default::default(),
+            images: Default::default(),
             paragraphs: Default::default(),
+            tables: Default::default(),
             cursor_prims: Default::default(),
             cursor: Cursor::Normal(Default::default()),
+            link_history: Default::default(),
+            comments: Default::default(),
+            styles: Default::default(),
+            theme_fonts: Default::default(),
         }
     }
 }
@@ -1794,3 +5318,818 @@ impl CursorPos {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn paragraph(style: &str, cell: Option<CellRef>) -> Paragraph {
+        Paragraph {
+            properties: ParagraphProperties {
+                style: Some(style.to_string()),
+                ..Default::default()
+            },
+            words: Vec::new(),
+            lines: Vec::new(),
+            cell,
+            label: None,
+        }
+    }
+
+    fn table(parent: Option<CellRef>) -> Table {
+        Table {
+            properties: TableProperties::default(),
+            grid: vec![100.],
+            rows: vec![TableRow {
+                cells: vec![Default::default()],
+                ..Default::default()
+            }],
+            primitives: Vec::new(),
+            parent,
+        }
+    }
+
+    fn word(text: &str, properties: TextProperties) -> Word {
+        Word {
+            word: text.to_string(),
+            glyphs_views: vec![GlyphsView {
+                word_range: 0..text.len(),
+                properties,
+                ..Default::default()
+            }],
+        }
+    }
+
+    #[test]
+    fn justified_line_ended_by_a_text_wrapping_break_is_stretched() {
+        let line_ended_by = |br: BreakType| {
+            let words = vec![
+                word("one", TextProperties::default()),
+                word("two", TextProperties::default()),
+                Word {
+                    word: String::new(),
+                    glyphs_views: vec![GlyphsView {
+                        br: Some(br),
+                        ..Default::default()
+                    }],
+                },
+            ];
+            let line = Line {
+                height: 10.,
+                min_width: 50.,
+                widht_with_spacing: 50.,
+                last_scale: 1.,
+                range: 0..words.len(),
+                tabs: Vec::new(),
+            };
+            (words, line)
+        };
+        let justified = |br: BreakType, is_last: bool, do_not_expand_shift_return: bool| {
+            let (words, line) = line_ended_by(br);
+            let (_, spacing) = get_line_vertical_metrics(
+                Some(docx_document::Justification::Both),
+                100.,
+                &words,
+                &line,
+                is_last,
+                do_not_expand_shift_return,
+                1.,
+            );
+            matches!(spacing, VerticalSpacing::Justified { .. })
+        };
+
+        assert!(justified(BreakType::TextWrapping, false, false));
+        assert!(!justified(BreakType::TextWrapping, false, true));
+        assert!(!justified(BreakType::Page, false, false));
+        assert!(!justified(BreakType::TextWrapping, true, false));
+    }
+
+    /// Context of a 100 by 100 page with 10 wide margins whose content so
+    /// far ends at `y`.
+    fn page_ctx(y: f32) -> DrawStateCtx {
+        let page_rect = math::Rectangle::new((0., 0.), (100., 100.));
+        DrawStateCtx {
+            page_content_rect: math::Rectangle::new((10., y), (80., 90. - y)),
+            page_properties: PageProperties::default(),
+            bg_color: docx_document::Color::BLACK,
+            page_rect,
+            v_width: 100.,
+            scale: 1.,
+            footnotes: Vec::new(),
+            section: 0,
+            default_tab_stop: DrawState::DEFAULT_TAB_STOP,
+            do_not_expand_shift_return: false,
+            column: 0,
+            column_top: 10.,
+            columns_bottom: y,
+            cell_page_bottom: None,
+        }
+    }
+
+    /// Paragraph of `count` lines 10 high.
+    fn lines_paragraph(count: usize, properties: ParagraphProperties) -> Paragraph {
+        let line = Line {
+            height: 10.,
+            min_width: 0.,
+            widht_with_spacing: 0.,
+            last_scale: 1.,
+            range: 0..0,
+            tabs: Vec::new(),
+        };
+        Paragraph {
+            properties,
+            words: Vec::new(),
+            lines: vec![line; count],
+            cell: None,
+            label: None,
+        }
+    }
+
+    #[test]
+    fn keep_next_moves_paragraph_to_next_page() {
+        let paragraph = lines_paragraph(1, ParagraphProperties::default());
+        // 15 left on the page: the line fits, but not with what follows it.
+        assert!(!keeps_to_next_page(&paragraph, 0, 0., &page_ctx(75.)));
+        assert!(keeps_to_next_page(&paragraph, 0, 10., &page_ctx(75.)));
+        // At the top of a page moving on doesn't help.
+        assert!(!keeps_to_next_page(&paragraph, 0, 10., &page_ctx(10.)));
+        // Nor does it when the kept content doesn't fit on a whole page.
+        assert!(!keeps_to_next_page(&paragraph, 0, 100., &page_ctx(75.)));
+    }
+
+    #[test]
+    fn keep_lines_moves_the_whole_paragraph() {
+        let properties = ParagraphProperties {
+            keep_lines: true,
+            ..Default::default()
+        };
+        let kept = lines_paragraph(3, properties);
+        let split = lines_paragraph(3, ParagraphProperties::default());
+        assert!(keeps_to_next_page(&kept, 0, 0., &page_ctx(65.)));
+        assert!(!keeps_to_next_page(&split, 0, 0., &page_ctx(65.)));
+        // Only the first line decides, the rest follow it.
+        assert!(!keeps_to_next_page(&kept, 1, 0., &page_ctx(85.)));
+    }
+
+    #[test]
+    fn widow_control_pulls_a_line_over() {
+        let properties = ParagraphProperties {
+            widow_control: true,
+            ..Default::default()
+        };
+        let controlled = lines_paragraph(3, properties);
+        let uncontrolled = lines_paragraph(3, ParagraphProperties::default());
+        // The second line fits, but would leave the last one alone.
+        assert!(keeps_to_next_page(&controlled, 1, 0., &page_ctx(75.)));
+        assert!(!keeps_to_next_page(&uncontrolled, 1, 0., &page_ctx(75.)));
+        // The first line doesn't go alone at the bottom either.
+        assert!(keeps_to_next_page(&controlled, 0, 0., &page_ctx(75.)));
+        assert!(!keeps_to_next_page(&controlled, 0, 0., &page_ctx(65.)));
+    }
+
+    #[test]
+    fn continuous_section_starts_below_the_previous_columns() {
+        let mut ctx = page_ctx(10.);
+        ctx.page_properties.columns = vec![(0., 35.), (45., 35.)];
+        ctx.column = 1;
+        // The first column ends lower than the second, where the section ends.
+        ctx.columns_bottom = 50.;
+        ctx.page_content_rect = math::Rectangle::new((55., 30.), (35., 60.));
+
+        // The new section has a single column.
+        ctx.page_properties.columns = Vec::new();
+        ctx.start_below_columns();
+        assert_eq!(ctx.column, 0);
+        assert_eq!(ctx.column_top, 50.);
+        assert_eq!(ctx.columns_bottom, 50.);
+        assert_eq!(ctx.page_content_rect.x(), 10.);
+        assert_eq!(ctx.page_content_rect.y(), 50.);
+        assert_eq!(ctx.page_content_rect.width(), 80.);
+        assert_eq!(ctx.page_content_rect.height(), 40.);
+    }
+
+    /// Styles of the paragraphs right in `elem`.
+    fn styles(elem: &word_xml::Element) -> Vec<&str> {
+        elem.find_path("w:p/w:pPr/w:pStyle")
+            .filter_map(|style| style.get_attr("w:val"))
+            .collect()
+    }
+
+    #[test]
+    fn nested_table_is_saved_in_its_cell() {
+        let outer = CellRef {
+            table: 0,
+            row: 0,
+            cell: 0,
+        };
+        let inner = CellRef { table: 1, ..outer };
+        let document_draw = DocumentDraw {
+            paragraphs: vec![
+                paragraph("Before", Some(outer)),
+                paragraph("Nested", Some(inner)),
+                paragraph("After", Some(outer)),
+                paragraph("Body", None),
+            ],
+            tables: vec![table(None), table(Some(outer))],
+            ..Default::default()
+        };
+        assert_eq!(document_draw.table_in(Some(inner), None), Some(0));
+        assert_eq!(document_draw.table_in(Some(inner), Some(outer)), Some(1));
+        assert_eq!(document_draw.table_end(0, 0), 3);
+        assert_eq!(document_draw.table_end(1, 1), 2);
+
+        let document = document_draw.get_word_xml_document().unwrap();
+        let body = document.root.get_child("w:body").unwrap();
+        assert_eq!(body.find_path("w:tbl").count(), 1);
+        assert_eq!(styles(body), ["Body"]);
+
+        let cell = body.find_path_first("w:tbl/w:tr/w:tc").unwrap();
+        assert_eq!(styles(cell), ["Before", "After"]);
+        let nested = cell.find_path("w:tbl/w:tr/w:tc").collect::<Vec<_>>();
+        assert_eq!(nested.len(), 1);
+        assert_eq!(styles(nested[0]), ["Nested"]);
+        let names = cell.find_path("*").map(|elem| elem.name.as_str()).collect::<Vec<_>>();
+        assert_eq!(names, ["w:tcPr", "w:p", "w:tbl", "w:p"]);
+    }
+
+    #[test]
+    fn run_properties_are_written_back() {
+        let properties = TextProperties {
+            font_name: Some("Arial".to_string()),
+            size: Some(docx_document::TextSize::from(28)),
+            weight: docx_document::TextWeight::Bold,
+            color: Some("FF0000".parse().unwrap()),
+            underline: Some(docx_document::UnderlineStyle::Double),
+            italic: true,
+            strike: true,
+            vertical_align: Some(VerticalAlign::Superscript),
+            small_caps: true,
+            highlight: Some(docx_document::HighlightColor::Yellow),
+            spacing: Some(2.),
+            stretch: Some(150.),
+            position: Some(-4.),
+            ..Default::default()
+        };
+        let mut par = paragraph("Normal", None);
+        par.words = vec![word("Styled", properties.clone())];
+        let document_draw = DocumentDraw {
+            paragraphs: vec![par],
+            ..Default::default()
+        };
+
+        let document = document_draw.get_word_xml_document().unwrap();
+        let run = document.root.find_path_first("w:body/w:p/w:r").unwrap();
+        assert_eq!(run.find_path("w:rPr").count(), 1);
+        assert!(run.find_path_first("w:rPr/w:rPr").is_none());
+        assert_eq!(run.get_childs_texts("w:t").as_deref(), Some("Styled"));
+
+        let mut parsed = TextProperties::default();
+        let rpr = run.get_child("w:rPr").unwrap();
+        docx_document::from_word_xml::apply_run_properties(rpr, &mut parsed);
+        assert_eq!(parsed.fonts.h_ansi.as_deref(), Some("Arial"));
+        parsed.fonts = Default::default();
+        assert_eq!(parsed, properties);
+    }
+
+    /// Document of the `w:p` elements `body`, parsed with `styles`.
+    fn parse_document(body: &str, styles: StyleSheet) -> docx_document::DocxDocument {
+        let xml = format!(
+            r#"<w:document xmlns:w="{}"><w:body>{body}</w:body></w:document>"#,
+            word_xml::namespaces::W_NS
+        );
+        let fonts = format!(
+            r#"<w:fonts xmlns:w="{}"><w:font w:name="Calibri"/></w:fonts>"#,
+            word_xml::namespaces::W_NS
+        );
+        let fonts = fonts.parse::<word_xml::WordXMLDocument>().unwrap();
+        let mut reader = word_xml::XmlReader::new(&xml);
+        let theme_fonts = ThemeFonts::default();
+        docx_document::DocxDocument::from_reader(&mut reader, &fonts, styles, theme_fonts).unwrap()
+    }
+
+    /// Paragraphs of `document` laid out nowhere, enough to be saved.
+    fn document_draw_of(document: &docx_document::DocxDocument) -> DocumentDraw {
+        let nodes = document.content.nodes.iter().flatten();
+        let paragraphs = nodes
+            .filter_map(|node| match node {
+                docx_document::DocxNode::Paragrapth {
+                    properties, texts, ..
+                } => {
+                    let words = get_words(texts);
+                    // One line of all the words, for the cursor to find them.
+                    let line = Line {
+                        height: 0.,
+                        min_width: 0.,
+                        widht_with_spacing: 0.,
+                        last_scale: 1.,
+                        range: 0..words.len(),
+                        tabs: Vec::new(),
+                    };
+                    Some(Paragraph {
+                        properties: properties.clone(),
+                        words,
+                        lines: vec![line],
+                        cell: None,
+                        label: None,
+                    })
+                }
+                _ => None,
+            })
+            .collect();
+        DocumentDraw {
+            paragraphs,
+            styles: document.styles.clone(),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn only_direct_formatting_is_saved_over_styles() {
+        let body = concat!(
+            r#"<w:p><w:pPr><w:pStyle w:val="Heading1"/></w:pPr>"#,
+            r#"<w:r><w:t>Title</w:t></w:r>"#,
+            r#"<w:r><w:rPr><w:b w:val="0"/><w:i/></w:rPr><w:t>plain</w:t></w:r></w:p>"#,
+            r#"<w:p><w:pPr><w:jc w:val="right"/></w:pPr>"#,
+            r#"<w:r><w:rPr><w:sz w:val="22"/></w:rPr><w:t>Body</w:t></w:r></w:p>"#,
+        );
+        let styles = docx_document::styles::tests::styles;
+        let document = parse_document(body, styles());
+        let document_draw = document_draw_of(&document);
+        let [title, text] = &document_draw.paragraphs[..] else {
+            panic!("expected two paragraphs");
+        };
+        assert!(title.properties.keep_next);
+        assert_eq!(text.properties.justify, Some(docx_document::Justification::Right));
+        let properties = |par: &Paragraph| {
+            par.words
+                .iter()
+                .flat_map(|word| &word.glyphs_views)
+                .map(|glyphs_view| glyphs_view.properties.clone())
+                .collect::<Vec<_>>()
+        };
+        let title_runs = properties(title);
+        assert_eq!(title_runs[0].weight, docx_document::TextWeight::Bold);
+        assert_eq!(title_runs[1].weight, docx_document::TextWeight::Regular);
+        assert!(title_runs[1].italic);
+
+        let saved = document_draw.get_word_xml_document().unwrap();
+        let paragraphs = saved.root.find_path("w:body/w:p").collect::<Vec<_>>();
+        assert_eq!(names(paragraphs[0], "w:pPr/*"), ["w:pStyle", "w:bidi"]);
+        assert!(names(paragraphs[0], "w:r/w:rPr/*").iter().all(|name| name != "w:sz"));
+        let plain = paragraphs[0].find_path("w:r").nth(1).unwrap();
+        assert_eq!(names(plain, "w:rPr/*"), ["w:b", "w:i"]);
+        assert_eq!(plain.find_path_first("w:rPr/w:b").unwrap().get_attr("w:val"), Some("0"));
+        assert_eq!(names(paragraphs[1], "w:pPr/*"), ["w:bidi", "w:jc"]);
+        assert!(names(paragraphs[1], "w:r/w:rPr/*").is_empty());
+
+        // Read again, the saved paragraphs resolve to what was read.
+        let saved = String::from_utf8(saved.to_bytes(&Default::default()).unwrap()).unwrap();
+        let start = saved.find("<w:body>").unwrap() + "<w:body>".len();
+        let end = saved.find("<w:sectPr").unwrap();
+        let reread = parse_document(&saved[start..end], styles());
+        let reread = document_draw_of(&reread);
+        for (par, reread) in document_draw.paragraphs.iter().zip(&reread.paragraphs) {
+            assert_eq!(par.properties.style, reread.properties.style);
+            assert_eq!(par.properties.justify, reread.properties.justify);
+            assert_eq!(par.properties.keep_next, reread.properties.keep_next);
+            assert_eq!(par.properties.spacing.after, reread.properties.spacing.after);
+            assert_eq!(properties(par), properties(reread));
+        }
+    }
+
+    #[test]
+    fn header_root_is_found_by_namespace() {
+        let mut document = docx_document::DocxDocument::default();
+        let mut parse = |xml: String| {
+            let part = xml.parse::<word_xml::WordXMLDocument>().unwrap();
+            document.parse_header_footer(&part).is_ok()
+        };
+        let w_ns = word_xml::namespaces::W_NS;
+        assert!(parse(format!(r#"<w:hdr xmlns:w="{w_ns}"><w:p/></w:hdr>"#)));
+        assert!(parse(format!(r#"<wx:ftr xmlns:wx="{w_ns}"><wx:p/></wx:ftr>"#)));
+        assert!(parse(format!(r#"<ftr xmlns="{w_ns}"><p/></ftr>"#)));
+        assert!(!parse(r#"<w:hdr xmlns:w="urn:other"><w:p/></w:hdr>"#.to_string()));
+        assert!(!parse(format!(r#"<w:body xmlns:w="{w_ns}"><w:p/></w:body>"#)));
+    }
+
+    #[test]
+    fn inline_image_is_a_word_of_its_own_sized_from_its_extent() {
+        use word_xml::namespaces::{A_NS, R_NS, WP_NS};
+        let drawing = format!(
+            concat!(
+                r#"<w:drawing xmlns:wp="{WP_NS}" xmlns:a="{A_NS}" xmlns:r="{R_NS}">"#,
+                r#"<wp:inline><wp:extent cx="635000" cy="317500"/>"#,
+                r#"<wp:docPr id="1" name="Picture 1" descr="chart"/>"#,
+                r#"<a:graphic><a:graphicData><a:blip r:embed="rId7"/></a:graphicData>"#,
+                r#"</a:graphic></wp:inline></w:drawing>"#,
+            ),
+            WP_NS = WP_NS,
+            A_NS = A_NS,
+            R_NS = R_NS,
+        );
+        let body = format!(
+            r#"<w:p xml:space="preserve"><w:r><w:t>ab </w:t></w:r><w:r>{drawing}</w:r>{}</w:p>"#,
+            r#"<w:r><w:t> cd</w:t></w:r>"#
+        );
+        let document = parse_document(&body, StyleSheet::default());
+        let document_draw = document_draw_of(&document);
+        let words = &document_draw.paragraphs[0].words;
+        let texts = words.iter().map(|word| word.word.as_str()).collect::<Vec<_>>();
+        assert_eq!(texts, ["ab", Word::IMAGE, "cd"]);
+        let glyphs_view = &words[1].glyphs_views[0];
+        assert!(glyphs_view.is_object());
+        let image = glyphs_view.image.as_ref().unwrap();
+        assert_eq!(image.relationship_id, "rId7");
+        assert_eq!((image.width, image.height), (100., 50.));
+        assert_eq!(image.description.as_deref(), Some("chart"));
+
+        let saved = document_draw.get_word_xml_document().unwrap();
+        let extent = saved
+            .root
+            .find_path_first("w:body/w:p/w:r/w:drawing/wp:inline/wp:extent")
+            .unwrap();
+        assert_eq!(extent.get_attr("cx"), Some("635000"));
+        // The text around the picture keeps its spaces.
+        assert_eq!(saved_text(saved.root.find_path_first("w:body/w:p").unwrap()), "ab  cd");
+    }
+
+    #[test]
+    fn notes_are_numbered_in_reference_order() {
+        let body = concat!(
+            r#"<w:p><w:r><w:t>a</w:t></w:r><w:r><w:footnoteReference w:id="5"/></w:r>"#,
+            r#"<w:r><w:endnoteReference w:id="3"/></w:r></w:p>"#,
+            r#"<w:p><w:r><w:footnoteReference w:id="2"/></w:r>"#,
+            r#"<w:r><w:footnoteReference w:id="5"/></w:r></w:p>"#,
+        );
+        let mut document = parse_document(body, StyleSheet::default());
+        let marks = |texts: &[docx_document::TextNode]| {
+            texts
+                .iter()
+                .filter_map(|text| Some((text.note.as_ref()?.id, text.content.clone())))
+                .collect::<Vec<_>>()
+        };
+        let texts = document.content.nodes.iter().flatten().flat_map(|node| match node {
+            docx_document::DocxNode::Paragrapth { texts, .. } => texts.clone(),
+            _ => Vec::new(),
+        });
+        let marks_in_body = marks(&texts.collect::<Vec<_>>());
+        let expected = [(5, "1"), (3, "i"), (2, "2"), (5, "1")];
+        let expected = expected.map(|(id, number)| (Some(id), number.to_string()));
+        assert_eq!(marks_in_body, expected);
+
+        let footnotes = format!(
+            r#"<w:footnotes xmlns:w="{}">{}{}{}</w:footnotes>"#,
+            word_xml::namespaces::W_NS,
+            r#"<w:footnote w:type="separator" w:id="0"/>"#,
+            r#"<w:footnote w:id="2"><w:p><w:r><w:footnoteRef/></w:r>"#,
+            r#"<w:r><w:t>note</w:t></w:r></w:p></w:footnote>"#,
+        );
+        let footnotes = footnotes.parse::<word_xml::WordXMLDocument>().unwrap();
+        document.parse_notes(&footnotes, NoteKind::Footnote).unwrap();
+        assert_eq!(document.notes.len(), 1);
+        let note = &document.notes[&(NoteKind::Footnote, 2)];
+        let docx_document::DocxNode::Paragrapth { texts, .. } = &note[0] else {
+            panic!("Note must start with a paragraph");
+        };
+        assert_eq!(marks(texts), [(None, "2".to_string())]);
+
+        let saved = document_draw_of(&document).get_word_xml_document().unwrap();
+        let ids = saved
+            .root
+            .find_path("w:body/w:p/w:r/w:footnoteReference")
+            .filter_map(|reference| reference.get_attr("w:id"))
+            .collect::<Vec<_>>();
+        assert_eq!(ids, ["5", "2", "5"]);
+    }
+
+    #[test]
+    fn whitespace_is_kept_where_preserve_is_in_scope() {
+        let body = concat!(
+            r#"<w:p xml:space="preserve"><w:r><w:t>a </w:t></w:r><w:r><w:t>b</w:t></w:r></w:p>"#,
+            r#"<w:p><w:r xml:space="preserve"><w:t>c </w:t></w:r><w:r><w:t>d</w:t></w:r></w:p>"#,
+            r#"<w:p xml:space="preserve"><w:r><w:t xml:space="default">e </w:t></w:r>"#,
+            r#"<w:r><w:t>f</w:t></w:r></w:p>"#,
+            r#"<w:p><w:r><w:t>g </w:t></w:r><w:r><w:t>h</w:t></w:r></w:p>"#,
+        );
+        let document = parse_document(body, StyleSheet::default());
+        let document_draw = document_draw_of(&document);
+        let words = document_draw
+            .paragraphs
+            .iter()
+            .map(|par| par.words.iter().map(|word| word.word.as_str()).collect::<Vec<_>>())
+            .collect::<Vec<_>>();
+        assert_eq!(words, [vec!["a", "b"], vec!["c", "d"], vec!["ef"], vec!["gh"]]);
+    }
+
+    #[test]
+    fn link_starting_with_a_multibyte_character_is_saved_whole() {
+        let body = concat!(
+            r#"<w:p><w:r><w:t>see</w:t></w:r>"#,
+            r#"<w:hyperlink w:anchor="x"><w:r><w:t>—here</w:t></w:r></w:hyperlink></w:p>"#,
+        );
+        let document = parse_document(body, StyleSheet::default());
+        let document_draw = document_draw_of(&document);
+        let word = &document_draw.paragraphs[0].words[0];
+        let ranges = word.glyphs_views.iter().map(|view| view.word_range.clone());
+        assert_eq!(ranges.collect::<Vec<_>>(), [0..3, 3..word.word.len()]);
+
+        let saved = document_draw.get_word_xml_document().unwrap();
+        let paragraph = saved.root.find_path_first("w:body/w:p").unwrap();
+        let link = paragraph.find_path_first("w:hyperlink").unwrap();
+        assert_eq!(link.get_attr("w:anchor"), Some("x"));
+        let linked = link.find_path_first("w:r").unwrap();
+        assert_eq!(linked.get_childs_texts("w:t").as_deref(), Some("—here"));
+        let before = paragraph.find_path_first("w:r").unwrap();
+        assert_eq!(before.get_childs_texts("w:t").as_deref(), Some("see"));
+    }
+
+    /// Names of the elements of `elem` at `path`.
+    fn names(elem: &word_xml::Element, path: &str) -> Vec<String> {
+        elem.find_path(path).map(|child| child.name.clone()).collect()
+    }
+
+    #[test]
+    fn numbering_is_saved_before_bidi() {
+        let mut par = paragraph("Normal", None);
+        par.properties.numbering = Some(docx_document::NumberingReference {
+            num_id: 1,
+            level: 0,
+        });
+        par.words = vec![word("Item", TextProperties::default())];
+        let document_draw = DocumentDraw {
+            paragraphs: vec![par],
+            ..Default::default()
+        };
+
+        let saved = document_draw.get_word_xml_document().unwrap();
+        let paragraph = saved.root.find_path_first("w:body/w:p").unwrap();
+        assert_eq!(names(paragraph, "w:pPr/*"), ["w:pStyle", "w:numPr", "w:bidi"]);
+    }
+
+    #[test]
+    fn paragraph_properties_are_saved_in_schema_order() {
+        let mut par = paragraph("Normal", None);
+        par.properties.keep_next = true;
+        par.properties.tabs = vec![docx_document::TabStop {
+            position: 50.,
+            alignment: TabAlignment::Center,
+            leader: TabLeader::None,
+        }];
+        par.properties.spacing.after = Some(12.);
+        par.properties.indent = Some(docx_document::Indentation {
+            left: 20.,
+            hanging: 10.,
+            ..Default::default()
+        });
+        par.properties.justify = Some(docx_document::Justification::Center);
+        par.words = vec![word("Text", TextProperties::default())];
+        let document_draw = DocumentDraw {
+            paragraphs: vec![par],
+            ..Default::default()
+        };
+
+        let saved = document_draw.get_word_xml_document().unwrap();
+        let paragraph = saved.root.find_path_first("w:body/w:p").unwrap();
+        assert_eq!(
+            names(paragraph, "w:pPr/*"),
+            ["w:pStyle", "w:keepNext", "w:tabs", "w:bidi", "w:spacing", "w:ind", "w:jc"]
+        );
+    }
+
+    #[test]
+    fn columns_are_saved_after_the_page_numbering() {
+        let sect_properties = SectrOfProperties {
+            columns: Some(docx_document::Columns {
+                count: 2,
+                space: 20.,
+                equal_width: false,
+                separator: true,
+                columns: vec![
+                    docx_document::Column {
+                        width: 200.,
+                        space: 20.,
+                    },
+                    docx_document::Column {
+                        width: 100.,
+                        space: 0.,
+                    },
+                ],
+            }),
+            ..Default::default()
+        };
+        let document_draw = DocumentDraw {
+            sect_properties,
+            ..Default::default()
+        };
+
+        let saved = document_draw.get_word_xml_document().unwrap();
+        let sect_pr = saved.root.find_path_first("w:body/w:sectPr").unwrap();
+        let names = names(sect_pr, "*");
+        let position = |name: &str| names.iter().position(|other| other == name);
+        assert!(position("w:pgNumType") < position("w:cols"));
+        assert!(position("w:cols") < position("w:formProt"));
+
+        let cols = sect_pr.get_child("w:cols").unwrap();
+        assert_eq!(cols.get_attr("w:num"), Some("2"));
+        assert_eq!(cols.get_attr("w:sep"), Some("1"));
+        assert_eq!(cols.get_attr("w:equalWidth"), Some("0"));
+        let widths = cols.get_children("w:col").filter_map(|col| col.get_attr("w:w"));
+        assert_eq!(widths.collect::<Vec<_>>(), ["2000", "1000"]);
+    }
+
+    #[test]
+    fn section_properties_are_saved_in_schema_order() {
+        let mut sect_properties = SectrOfProperties {
+            page_type: Some(PageType::NextPage),
+            title_page: true,
+            ..Default::default()
+        };
+        sect_properties.headers.first = Some("rId1".to_string());
+        let document_draw = DocumentDraw {
+            sect_properties,
+            ..Default::default()
+        };
+
+        let saved = document_draw.get_word_xml_document().unwrap();
+        let sect_pr = saved.root.find_path_first("w:body/w:sectPr").unwrap();
+        assert_eq!(
+            names(sect_pr, "*"),
+            [
+                "w:headerReference",
+                "w:type",
+                "w:pgSz",
+                "w:pgMar",
+                "w:pgNumType",
+                "w:formProt",
+                "w:titlePg",
+                "w:textDirection"
+            ]
+        );
+    }
+
+    const REVISIONS: &str = concat!(
+        r#"<w:p><w:r><w:t>Keep</w:t></w:r>"#,
+        r#"<w:ins w:id="1" w:author="Ann">"#,
+        r#"<w:r><w:t xml:space="preserve"> added</w:t></w:r></w:ins>"#,
+        r#"<w:del w:id="2" w:author="Bob">"#,
+        r#"<w:r><w:delText xml:space="preserve"> gone</w:delText></w:r></w:del></w:p>"#,
+    );
+
+    /// Words of `par` and the kinds of the tracked changes in it.
+    fn words_and_revisions(par: &Paragraph) -> (Vec<&str>, Vec<RevisionKind>) {
+        let words = par.words.iter().map(|word| word.word.as_str()).collect();
+        let revisions = par
+            .words
+            .iter()
+            .flat_map(|word| &word.glyphs_views)
+            .filter_map(|glyphs_view| glyphs_view.revision.as_ref())
+            .map(|revision| revision.kind)
+            .collect();
+        (words, revisions)
+    }
+
+    /// Text of the `w:t` runs right in `paragraph`, as saved.
+    fn saved_text(paragraph: &word_xml::Element) -> String {
+        paragraph.find_path("w:r/w:t").map(|t| t.get_texts()).collect()
+    }
+
+    #[test]
+    fn all_revisions_are_accepted_or_rejected() {
+        let document = parse_document(REVISIONS, StyleSheet::default());
+        let mut document_draw = document_draw_of(&document);
+        assert_eq!(
+            words_and_revisions(&document_draw.paragraphs[0]),
+            (
+                vec!["Keep", "added", "gone"],
+                vec![RevisionKind::Insertion, RevisionKind::Deletion]
+            )
+        );
+        assert_eq!(document_draw.resolve_revisions(true, true), [0]);
+        let accepted = words_and_revisions(&document_draw.paragraphs[0]);
+        assert_eq!(accepted, (vec!["Keep", "added"], Vec::new()));
+
+        let saved = document_draw.get_word_xml_document().unwrap();
+        let paragraph = saved.root.find_path_first("w:body/w:p").unwrap();
+        assert!(paragraph.find_path_first("w:ins").is_none());
+        assert!(paragraph.find_path_first("w:del").is_none());
+        assert_eq!(saved_text(paragraph), "Keep added");
+
+        let mut document_draw = document_draw_of(&document);
+        assert_eq!(document_draw.resolve_revisions(false, true), [0]);
+        let rejected = words_and_revisions(&document_draw.paragraphs[0]);
+        assert_eq!(rejected, (vec!["Keep", "gone"], Vec::new()));
+        let saved = document_draw.get_word_xml_document().unwrap();
+        let paragraph = saved.root.find_path_first("w:body/w:p").unwrap();
+        assert_eq!(saved_text(paragraph), "Keep gone");
+    }
+
+    #[test]
+    fn insertion_inside_a_word_is_saved_whole() {
+        let body = concat!(
+            r#"<w:p><w:r><w:t>a</w:t></w:r>"#,
+            r#"<w:ins w:id="1" w:author="Ann"><w:r><w:t>éé</w:t></w:r></w:ins></w:p>"#,
+        );
+        let document = parse_document(body, StyleSheet::default());
+        let document_draw = document_draw_of(&document);
+        let word = &document_draw.paragraphs[0].words[0];
+        let ranges = word.glyphs_views.iter().map(|view| view.word_range.clone());
+        assert_eq!(ranges.collect::<Vec<_>>(), [0..1, 1..5]);
+
+        let saved = document_draw.get_word_xml_document().unwrap();
+        let paragraph = saved.root.find_path_first("w:body/w:p").unwrap();
+        assert_eq!(saved_text(paragraph), "a");
+        let inserted = paragraph.find_path_first("w:ins/w:r").unwrap();
+        assert_eq!(inserted.get_childs_texts("w:t").as_deref(), Some("éé"));
+    }
+
+    #[test]
+    fn only_the_revision_under_the_cursor_is_resolved() {
+        let document = parse_document(REVISIONS, StyleSheet::default());
+        let mut document_draw = document_draw_of(&document);
+        document_draw.cursor = Cursor::Normal(CursorPos {
+            char_idx: "Keep added ".len(),
+            ..Default::default()
+        });
+        assert_eq!(document_draw.resolve_revisions(true, false), [0]);
+        let (words, revisions) = words_and_revisions(&document_draw.paragraphs[0]);
+        assert_eq!(words, ["Keep", "added"]);
+        assert_eq!(revisions, [RevisionKind::Insertion]);
+
+        let saved = document_draw.get_word_xml_document().unwrap();
+        let paragraph = saved.root.find_path_first("w:body/w:p").unwrap();
+        let insertion = paragraph.find_path_first("w:ins").unwrap();
+        assert_eq!(insertion.get_attr("w:author"), Some("Ann"));
+        assert!(paragraph.find_path_first("w:del").is_none());
+    }
+
+    const COMMENTED: &str = concat!(
+        r#"<w:p><w:commentRangeStart w:id="1"/><w:r><w:t>Commented</w:t></w:r></w:p>"#,
+        r#"<w:p><w:r><w:t>text</w:t></w:r><w:commentRangeEnd w:id="1"/>"#,
+        r#"<w:r><w:commentReference w:id="1"/></w:r></w:p>"#,
+        r#"<w:p><w:r><w:t>Plain</w:t></w:r></w:p>"#,
+    );
+
+    fn comment_ids(par: &Paragraph) -> Vec<i64> {
+        let glyphs_views = par.words.iter().flat_map(|word| &word.glyphs_views);
+        let mut ids = glyphs_views
+            .flat_map(|glyphs_view| glyphs_view.comments.clone())
+            .collect::<Vec<_>>();
+        ids.sort();
+        ids.dedup();
+        ids
+    }
+
+    #[test]
+    fn comment_threads_are_edited_as_a_whole_and_kept_as_read() {
+        let document = parse_document(COMMENTED, StyleSheet::default());
+        let mut document_draw = document_draw_of(&document);
+        let ids = document_draw.paragraphs.iter().map(comment_ids).collect::<Vec<_>>();
+        assert_eq!(ids, [vec![1], vec![1], vec![]]);
+
+        let read = format!(
+            r#"<w:comments xmlns:w="{}">{}</w:comments>"#,
+            word_xml::namespaces::W_NS,
+            concat!(
+                r#"<w:comment w:id="1"><w:p w:rsidR="00A1">"#,
+                r#"<w:r><w:t>Why?</w:t></w:r></w:p></w:comment>"#,
+            ),
+        );
+        let read = || read.parse::<word_xml::WordXMLDocument>().unwrap();
+        let mut root = Comment::new(1, "Why?", None);
+        root.element = read().root.find_path_first("w:comment").cloned();
+        // Replies to replies come from other editors, still in the same thread.
+        document_draw.comments = vec![
+            root,
+            Comment::new(2, "Because", Some(1)),
+            Comment::new(3, "Thanks", Some(2)),
+        ];
+        for glyphs_view in document_draw.glyphs_views_mut() {
+            if !glyphs_view.comments.is_empty() {
+                glyphs_view.comments.extend([2, 3]);
+            }
+        }
+        document_draw.cursor = Cursor::Normal(CursorPos::default());
+
+        assert_eq!(document_draw.resolve_comment(), Some(true));
+        assert!(document_draw.comments.iter().all(|comment| comment.done));
+
+        let part = Comment::comments_part(&document_draw.comments, Some(read())).unwrap();
+        let saved = part.root.get_children("w:comment").collect::<Vec<_>>();
+        assert_eq!(saved.len(), 3);
+        assert_eq!(saved[0].find_path_first("w:p").unwrap().get_attr("w:rsidR"), Some("00A1"));
+        assert_eq!(saved[1].get_attr("w:id"), Some("2"));
+
+        assert!(document_draw.edit_comment("Why not?"));
+        assert_eq!(document_draw.comments[0].text(), "Why not?");
+        assert_eq!(document_draw.comments[2].text(), "Thanks");
+        let part = Comment::comments_part(&document_draw.comments, Some(read())).unwrap();
+        let edited = part.root.find_path_first("w:comment").unwrap();
+        assert_eq!(edited.get_attr("w:id"), Some("1"));
+        assert_eq!(edited.find_path_first("w:p").unwrap().get_attr("w:rsidR"), None);
+
+        assert!(document_draw.delete_comment());
+        assert!(document_draw.comments.is_empty());
+        let ids = document_draw.paragraphs.iter().map(comment_ids).collect::<Vec<_>>();
+        assert_eq!(ids, [Vec::<i64>::new(), vec![], vec![]]);
+    }
+}
@@ -0,0 +1,184 @@
+use anyhow::Context;
+
+use super::Comment;
+
+impl Comment {
+    pub const RELATIONSHIP_TYPE: &'static str =
+        "http://schemas.openxmlformats.org/officeDocument/2006/relationships/comments";
+    pub const EXTENDED_RELATIONSHIP_TYPE: &'static str =
+        "http://schemas.microsoft.com/office/2011/relationships/commentsExtended";
+    pub const CONTENT_TYPE: &'static str =
+        "application/vnd.openxmlformats-officedocument.wordprocessingml.comments+xml";
+    pub const EXTENDED_CONTENT_TYPE: &'static str =
+        "application/vnd.openxmlformats-officedocument.wordprocessingml.commentsExtended+xml";
+
+    const W14_NS: &'static str = "http://schemas.microsoft.com/office/word/2010/wordml";
+    const COMMENTS_DEFAULT: &'static str = concat!(
+        r#"<?xml version="1.0" encoding="UTF-8" standalone="yes"?>"#,
+        r#"<w:comments xmlns:w="http://schemas.openxmlformats.org/wordprocessingml/2006/main" "#,
+        r#"xmlns:w14="http://schemas.microsoft.com/office/word/2010/wordml"></w:comments>"#,
+    );
+    const COMMENTS_EXTENDED_DEFAULT: &'static str = concat!(
+        r#"<?xml version="1.0" encoding="UTF-8" standalone="yes"?>"#,
+        r#"<w15:commentsEx xmlns:w15="http://schemas.microsoft.com/office/word/2012/wordml">"#,
+        r#"</w15:commentsEx>"#,
+    );
+
+    /// Comment written now by the user, replying to `parent` if any.
+    pub fn new(id: i64, text: &str, parent: Option<i64>) -> Self {
+        Self {
+            id,
+            author: std::env::var("USER").or_else(|_| std::env::var("USERNAME")).ok(),
+            initials: None,
+            date: Some(current_date()),
+            paragraphs: text.lines().map(ToString::to_string).collect(),
+            para_id: None,
+            parent,
+            done: false,
+            element: None,
+        }
+    }
+
+    /// Text of the comment with its paragraphs on lines of their own.
+    pub fn text(&self) -> String {
+        self.paragraphs.join("\n")
+    }
+
+    /// `w14:paraId` to save the comment with. New comments have none, so it
+    /// is made from the id, below the `0x80000000` Word allows.
+    pub fn get_para_id(&self) -> String {
+        self.para_id
+            .clone()
+            .unwrap_or_else(|| format!("{:08X}", 0x7000_0000 + self.id))
+    }
+
+    /// Author's initials, or the first letters of their name.
+    pub fn get_initials(&self) -> String {
+        self.initials.clone().unwrap_or_else(|| {
+            self.author
+                .as_deref()
+                .unwrap_or_default()
+                .split_whitespace()
+                .filter_map(|name| name.chars().next())
+                .collect()
+        })
+    }
+
+    fn to_element(&self) -> word_xml::Element {
+        if let Some(element) = &self.element {
+            return element.clone();
+        }
+
+        let mut element = word_xml::Element::new("w:comment").with_attr("w:id", self.id);
+        if let Some(author) = &self.author {
+            element.append_attr("w:author", author);
+        }
+        if let Some(date) = &self.date {
+            element.append_attr("w:date", date);
+        }
+        element.append_attr("w:initials", self.get_initials());
+
+        // A comment has at least one paragraph.
+        let empty = [String::new()];
+        let paragraphs = if self.paragraphs.is_empty() {
+            &empty[..]
+        } else {
+            &self.paragraphs[..]
+        };
+        for (idx, paragraph) in paragraphs.iter().enumerate() {
+            let mut p_elem = word_xml::Element::new("w:p");
+            if idx + 1 == paragraphs.len() {
+                p_elem.append_attr("w14:paraId", self.get_para_id());
+            }
+            if idx == 0 {
+                p_elem.append_element(
+                    word_xml::Element::new("w:r")
+                        .with_element(word_xml::Element::new("w:annotationRef")),
+                );
+            }
+            p_elem.append_element(
+                word_xml::Element::new("w:r").with_element(
+                    word_xml::Element::new("w:t")
+                        .with_attr("xml:space", "preserve")
+                        .with_text(paragraph),
+                ),
+            );
+            element.append_element(p_elem);
+        }
+        element
+    }
+
+    /// `comments.xml` with `comments`, in the part as it was read if there
+    /// is one, which declares the namespaces of the comments kept as read.
+    pub fn comments_part(
+        comments: &[Comment],
+        read: Option<word_xml::WordXMLDocument>,
+    ) -> anyhow::Result<word_xml::WordXMLDocument> {
+        let mut part = match read {
+            Some(part) => part,
+            None => Self::COMMENTS_DEFAULT
+                .parse::<word_xml::WordXMLDocument>()
+                .context("Failed to parse default comments")?,
+        };
+        part.root.retain_children(|element| element.name != "w:comment");
+        // Comments written from their text have a `w14:paraId`.
+        if part.root.get_attr("xmlns:w14").is_none() {
+            part.root.append_attr("xmlns:w14", Self::W14_NS);
+        }
+        for comment in comments {
+            part.root.append_element(comment.to_element());
+        }
+        Ok(part)
+    }
+
+    /// `commentsExtended.xml` with the threads and state of `comments`.
+    pub fn comments_extended_part(
+        comments: &[Comment],
+    ) -> anyhow::Result<word_xml::WordXMLDocument> {
+        let mut part = Self::COMMENTS_EXTENDED_DEFAULT
+            .parse::<word_xml::WordXMLDocument>()
+            .context("Failed to parse default comments extended")?;
+        for comment in comments {
+            let mut comment_ex = word_xml::Element::new("w15:commentEx")
+                .with_attr("w15:paraId", comment.get_para_id());
+            let parent = comment
+                .parent
+                .and_then(|parent| comments.iter().find(|comment| comment.id == parent));
+            if let Some(parent) = parent {
+                comment_ex.append_attr("w15:paraIdParent", parent.get_para_id());
+            }
+            comment_ex.append_attr("w15:done", if comment.done { "1" } else { "0" });
+            part.root.append_element(comment_ex);
+        }
+        Ok(part)
+    }
+}
+
+/// Current UTC time the way `w:date` has it, like `2024-05-01T10:00:00Z`.
+fn current_date() -> String {
+    let secs = std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .map_or(0, |duration| duration.as_secs() as i64);
+    let (days, secs) = (secs.div_euclid(86400), secs.rem_euclid(86400));
+
+    // Civil date of the days since 1970-01-01, after Howard Hinnant's
+    // `civil_from_days`, with years starting in March.
+    let z = days + 719468;
+    let (era, doe) = (z.div_euclid(146097), z.rem_euclid(146097));
+    let yoe = (doe - doe / 1460 + doe / 36524 - doe / 146096) / 365;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let day = doy - (153 * mp + 2) / 5 + 1;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 };
+    let year = yoe + era * 400 + i64::from(month <= 2);
+
+    format!(
+        "{:04}-{:02}-{:02}T{:02}:{:02}:{:02}Z",
+        year,
+        month,
+        day,
+        secs / 3600,
+        secs % 3600 / 60,
+        secs % 60
+    )
+}
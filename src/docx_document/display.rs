@@ -14,6 +14,12 @@ impl std::fmt::Display for DocxDocument {
         writeln!(f, "*")?;
         display_property("fonts", &self.fonts, 1, f)?;
 
+        writeln!(f, "*")?;
+        display_property("numbering", format!("{:?}", self.numbering), 1, f)?;
+
+        writeln!(f, "*")?;
+        display_property("styles", format!("{:?}", self.styles), 1, f)?;
+
         writeln!(f, ":( end ):")
     }
 }
@@ -96,6 +102,10 @@ impl std::fmt::Display for DocxNode {
                 form_prot,
                 text_direction,
                 document_grid,
+                headers,
+                footers,
+                title_page,
+                columns,
             } => {
                 writeln!(f, ":( paragrapth ):")?;
 
@@ -120,6 +130,53 @@ impl std::fmt::Display for DocxNode {
                 writeln!(f, "*")?;
                 display_property("document grid", format!("{:?}", document_grid), 2, f)?;
 
+                writeln!(f, "*")?;
+                display_property("headers", format!("{:?}", headers), 2, f)?;
+
+                writeln!(f, "*")?;
+                display_property("footers", format!("{:?}", footers), 2, f)?;
+
+                writeln!(f, "*")?;
+                display_property("title page", format!("{:?}", title_page), 2, f)?;
+
+                writeln!(f, "*")?;
+                display_property("columns", format!("{:?}", columns), 2, f)?;
+
+                writeln!(f, ":( end ):")
+            }
+            DocxNode::Table {
+                properties,
+                grid,
+                rows,
+            } => {
+                writeln!(f, ":( table ):")?;
+
+                writeln!(f, "*")?;
+                display_property("properties", format!("{:?}", properties), 2, f)?;
+
+                writeln!(f, "*")?;
+                display_property("grid", format!("{:?}", grid), 2, f)?;
+
+                writeln!(f, "*")?;
+                writeln!(f, "{}|>- rows -<|", PRE)?;
+                for row in rows {
+                    writeln!(f, "{}+", PRE)?;
+                    display_property("row properties", format!("{:?}", row.properties), 2, f)?;
+                    for cell in &row.cells {
+                        writeln!(f, "{}+", PRE.repeat(2))?;
+                        display_property(
+                            "cell properties",
+                            format!("{:?}", cell.properties),
+                            3,
+                            f,
+                        )?;
+                        for node in &cell.content {
+                            writeln!(f, "{}", node.to_string().with_indent(3))?;
+                        }
+                    }
+                }
+                writeln!(f, "{}|>- end -<|", PRE)?;
+
                 writeln!(f, ":( end ):")
             }
             DocxNode::TodoWordXml(element) => {
@@ -188,6 +245,16 @@ impl std::fmt::Display for TextNode {
         writeln!(f, "*")?;
         display_property("content", &self.content, 1, f)?;
 
+        if let Some(link) = &self.link {
+            writeln!(f, "*")?;
+            display_property("link", format!("{:?}", link), 1, f)?;
+        }
+
+        if let Some(revision) = &self.revision {
+            writeln!(f, "*")?;
+            display_property("revision", format!("{:?}", revision), 1, f)?;
+        }
+
         writeln!(f, ":( end ):")
     }
 }
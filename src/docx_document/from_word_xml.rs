@@ -1,52 +1,262 @@
 use anyhow::{anyhow, Context};
-use word_xml::Node;
+use word_xml::{namespaces::W_NS, Node};
 
 use crate::docx_document::DocxNode;
 
 use super::{
-    Color, DocumentGrid, DocxDocument, FontTable, FormProt, GridType, Justification, LineRule,
-    NumType, PageMargin, PageSize, ParagraphProperties, SpacingProperties, TextDirection, TextSize,
-    TextWeight,
+    AbstractNumbering, Bookmark, Border, BorderStyle, BreakType, CellMargins, CellProperties, Color,
+    Column, Columns, Comment, DocumentGrid, DocxDocument, FontTable, FormProt, GridType,
+    HeaderFooterReferences, HighlightColor, Hyperlink, Indentation, InlineImage, Justification,
+    LevelOverride, LevelSuffix, LineRule, NoteKind, NoteMark, NumType, NumberFormat, Numbering,
+    NumberingInstance, NumberingLevel, NumberingReference, PageMargin, PageSize,
+    ParagraphProperties, Relationship, Relationships, Revision, RowProperties, Settings,
+    SpacingProperties, Style, StyleSheet, StyleType, TabAlignment, TabLeader, TabStop, TableBorders,
+    TableCell, TableProperties, TableRow, TableWidth, TextDirection, TextNode, TextProperties,
+    TextSize, TextWeight, ThemeFont, ThemeFontSet, ThemeFonts, UnderlineStyle, VerticalAlign,
+    VerticalMerge, WidthType,
 };
 
-impl<'a> TryFrom<(&'a word_xml::WordXMLDocument, &'a word_xml::WordXMLDocument)> for DocxDocument {
-    type Error = anyhow::Error;
+impl DocxDocument {
+    /// Build the document straight from `document.xml` events, parsing one
+    /// body element at a time instead of the whole part tree.
+    pub fn from_reader(
+        reader: &mut word_xml::XmlReader<'_>,
+        fonts: &word_xml::WordXMLDocument,
+        styles: StyleSheet,
+        theme_fonts: ThemeFonts,
+    ) -> anyhow::Result<Self> {
+        let root = loop {
+            match reader.next_event()?.context("No root element")? {
+                word_xml::Event::Start(tag) => break tag.to_element(),
+                _ => continue,
+            }
+        };
 
-    fn try_from(
-        (document_xml, fonts): (&'a word_xml::WordXMLDocument, &'a word_xml::WordXMLDocument),
-    ) -> Result<Self, Self::Error> {
-        if document_xml.root.name != "w:document" {
+        if !root.is_ns(reader.namespaces(), W_NS, "document") {
             return Err(anyhow!(
                 "Invalid document root element name: {:?}",
-                document_xml.root.name
+                root.name
             ));
         }
 
         let mut document = DocxDocument {
             fonts: FontTable::try_from(fonts)?,
+            styles,
+            theme_fonts,
             ..Default::default()
         };
 
-        let body = document_xml
-            .root
-            .get_child("w:body")
-            .context("No body element")?;
-
-        for root_element in body.inners.iter().filter_map(Node::get_element) {
-            let curr = Box::new(match root_element.name.as_str() {
-                "w:p" => parse_paragraph(root_element, &mut document),
-                "w:sectPr" => {
-                    parse_sectr_properties(root_element).context(format!("{:#?}", root_element))?
+        let mut ctx = PartContext::default();
+        let (mut body_found, mut in_body) = (false, false);
+        while let Some(event) = reader.next_event()? {
+            match (event, reader.depth()) {
+                (word_xml::Event::Start(tag), 2)
+                    if tag.to_element().is_ns(reader.namespaces(), W_NS, "body") =>
+                {
+                    (body_found, in_body) = (true, true);
+                    ctx.preserve_space = reader.preserves_space();
                 }
-                _ => DocxNode::TodoWordXml(root_element.clone()),
-            });
-            document.content.push(curr);
+                // The body's scope is closed by its end, so it is known by depth.
+                (word_xml::Event::End(_), 1) => in_body = false,
+                (word_xml::Event::Start(tag), 3) if in_body => {
+                    let root_element = reader.read_element(tag)?;
+                    let curr = parse_body_element(&root_element, &mut ctx, &mut document)?;
+                    document.content.push(Box::new(curr));
+                }
+                _ => {}
+            }
+        }
+
+        if !body_found {
+            return Err(anyhow!("No body element"));
         }
 
         Ok(document)
     }
 }
 
+impl DocxDocument {
+    /// Content of a `header*.xml` or `footer*.xml` part, parsed like the body.
+    pub fn parse_header_footer(
+        &mut self,
+        part: &word_xml::WordXMLDocument,
+    ) -> anyhow::Result<Vec<DocxNode>> {
+        let namespaces = &part.namespaces;
+        if !part.root.is_ns(namespaces, W_NS, "hdr") && !part.root.is_ns(namespaces, W_NS, "ftr") {
+            return Err(anyhow!(
+                "Invalid header or footer root element name: {:?}",
+                part.root.name
+            ));
+        }
+
+        let mut ctx = PartContext::default();
+        ctx.scoped(&part.root, |ctx| {
+            part.root
+                .inners
+                .iter()
+                .filter_map(Node::get_element)
+                .map(|root_element| parse_body_element(root_element, ctx, self))
+                .collect()
+        })
+    }
+
+    /// Comments of `comments.xml`, kept as the text of their paragraphs.
+    pub fn parse_comments(&mut self, part: &word_xml::WordXMLDocument) -> anyhow::Result<()> {
+        for comment in part.root.get_children("w:comment") {
+            let paragraphs = comment.get_children("w:p").collect::<Vec<_>>();
+            self.comments.push(Comment {
+                id: comment
+                    .get_attr_parsed::<i64>("w:id")
+                    .context("Comment must have id")?,
+                author: comment.get_attr_parsed::<String>("w:author"),
+                initials: comment.get_attr_parsed::<String>("w:initials"),
+                date: comment.get_attr_parsed::<String>("w:date"),
+                paragraphs: paragraphs
+                    .iter()
+                    .map(|paragraph| {
+                        paragraph
+                            .descendants()
+                            .filter(|element| element.name == "w:t")
+                            .map(word_xml::Element::get_texts)
+                            .collect()
+                    })
+                    .collect(),
+                para_id: paragraphs
+                    .last()
+                    .and_then(|paragraph| paragraph.get_attr_parsed::<String>("w14:paraId")),
+                parent: None,
+                done: false,
+                element: Some(comment.clone()),
+            });
+        }
+        Ok(())
+    }
+
+    /// Replies and resolved state of the comments from `commentsExtended.xml`,
+    /// which refers to them by the `w14:paraId` of their last paragraph.
+    pub fn parse_comments_extended(&mut self, part: &word_xml::WordXMLDocument) {
+        let by_para_id = |comments: &[Comment], para_id: &str| {
+            comments
+                .iter()
+                .position(|comment| comment.para_id.as_deref() == Some(para_id))
+        };
+        for comment_ex in part.root.get_children("w15:commentEx") {
+            let Some(idx) = comment_ex
+                .get_attr("w15:paraId")
+                .and_then(|para_id| by_para_id(&self.comments, para_id))
+            else {
+                continue;
+            };
+            let parent = comment_ex
+                .get_attr("w15:paraIdParent")
+                .and_then(|para_id| by_para_id(&self.comments, para_id))
+                .map(|parent| self.comments[parent].id);
+
+            let comment = &mut self.comments[idx];
+            comment.parent = parent;
+            comment.done = comment_ex.get_attr("w15:done") == Some("1");
+        }
+    }
+
+    /// Notes of `footnotes.xml` or `endnotes.xml`. Separators and other
+    /// notes with a `w:type` are left out.
+    pub fn parse_notes(
+        &mut self,
+        part: &word_xml::WordXMLDocument,
+        kind: NoteKind,
+    ) -> anyhow::Result<()> {
+        let name = match kind {
+            NoteKind::Footnote => "w:footnote",
+            NoteKind::Endnote => "w:endnote",
+        };
+
+        let mut ctx = PartContext {
+            preserve_space: space_preserved(&part.root, false),
+            ..Default::default()
+        };
+        for note in part.root.get_children(name) {
+            if note.get_attr("w:type").is_some() {
+                continue;
+            }
+            let id = note
+                .get_attr_parsed::<i64>("w:id")
+                .context("Note must have id")?;
+
+            let mut content = ctx.scoped(note, |ctx| {
+                note.inners
+                    .iter()
+                    .filter_map(Node::get_element)
+                    .map(|root_element| parse_body_element(root_element, ctx, self))
+                    .collect::<anyhow::Result<Vec<_>>>()
+            })?;
+
+            let number = self
+                .note_number(kind, id)
+                .map(|number| kind.format(number))
+                .unwrap_or_default();
+            let texts = content.iter_mut().flat_map(|node| match node {
+                DocxNode::Paragrapth { texts, .. } => texts.as_mut_slice(),
+                _ => &mut [],
+            });
+            for text in texts.filter(|text| text.note == Some(NoteMark { kind, id: None })) {
+                text.content = number.clone();
+            }
+
+            self.notes.insert((kind, id), content);
+        }
+
+        Ok(())
+    }
+}
+
+/// What the parsing of a part keeps from one of its elements to the next.
+#[derive(Default)]
+struct PartContext {
+    /// Comments whose range is open where the parsing is. Ranges may span
+    /// paragraphs and table cells.
+    open_comments: Vec<i64>,
+    /// Whether `xml:space="preserve"` is in scope of the element parsed.
+    preserve_space: bool,
+}
+
+impl PartContext {
+    /// `f` run with the `xml:space` of `element` in scope.
+    fn scoped<T>(&mut self, element: &word_xml::Element, f: impl FnOnce(&mut Self) -> T) -> T {
+        let outer = self.preserve_space;
+        self.preserve_space = space_preserved(element, outer);
+        let result = f(self);
+        self.preserve_space = outer;
+        result
+    }
+}
+
+/// Whether `element` keeps its whitespace, by its own `xml:space` or else the
+/// one `inherited` from its ancestors.
+fn space_preserved(element: &word_xml::Element, inherited: bool) -> bool {
+    match element.get_attr("xml:space") {
+        Some("preserve") => true,
+        Some("default") => false,
+        _ => inherited,
+    }
+}
+
+fn parse_body_element(
+    root_element: &word_xml::Element,
+    ctx: &mut PartContext,
+    document: &mut DocxDocument,
+) -> anyhow::Result<DocxNode> {
+    ctx.scoped(root_element, |ctx| {
+        Ok(match root_element.name.as_str() {
+            "w:p" => parse_paragraph(root_element, ctx, document),
+            "w:tbl" => parse_table(root_element, ctx, document)?,
+            "w:sectPr" => {
+                parse_sectr_properties(root_element).context(format!("{:#?}", root_element))?
+            }
+            _ => DocxNode::TodoWordXml(root_element.clone()),
+        })
+    })
+}
+
 impl<'a> TryFrom<&'a word_xml::WordXMLDocument> for FontTable {
     type Error = anyhow::Error;
 
@@ -64,42 +274,585 @@ impl<'a> TryFrom<&'a word_xml::WordXMLDocument> for FontTable {
     }
 }
 
+impl<'a> TryFrom<&'a word_xml::WordXMLDocument> for Settings {
+    type Error = anyhow::Error;
+
+    fn try_from(value: &'a word_xml::WordXMLDocument) -> Result<Self, Self::Error> {
+        Ok(Settings {
+            even_and_odd_headers: has_toggle(&value.root, "w:evenAndOddHeaders"),
+            default_tab_stop: value
+                .root
+                .get_childs_attr_parsed::<f32>("w:defaultTabStop", "w:val")
+                .map(|value| value / 10.),
+            do_not_expand_shift_return: value
+                .root
+                .get_child("w:compat")
+                .is_some_and(|compat| has_toggle(compat, "w:doNotExpandShiftReturn")),
+        })
+    }
+}
+
+impl<'a> TryFrom<&'a word_xml::WordXMLDocument> for ThemeFonts {
+    type Error = anyhow::Error;
+
+    fn try_from(value: &'a word_xml::WordXMLDocument) -> Result<Self, Self::Error> {
+        let font_set = |name: &str| {
+            let path = format!("a:themeElements/a:fontScheme/{name}");
+            let Some(font) = value.root.find_path(&path).next() else {
+                return ThemeFontSet::default();
+            };
+            let typeface = |script: &str| {
+                font.get_childs_attr_parsed::<String>(script, "typeface")
+                    .filter(|typeface| !typeface.is_empty())
+            };
+            ThemeFontSet {
+                latin: typeface("a:latin"),
+                east_asia: typeface("a:ea"),
+                complex_script: typeface("a:cs"),
+            }
+        };
+
+        Ok(ThemeFonts {
+            major: font_set("a:majorFont"),
+            minor: font_set("a:minorFont"),
+        })
+    }
+}
+
+impl<'a> TryFrom<&'a word_xml::WordXMLDocument> for Numbering {
+    type Error = anyhow::Error;
+
+    fn try_from(value: &'a word_xml::WordXMLDocument) -> Result<Self, Self::Error> {
+        let mut numbering = Numbering::default();
+
+        for abstract_num in value.root.get_children("w:abstractNum") {
+            let id = abstract_num
+                .get_attr_parsed::<usize>("w:abstractNumId")
+                .context("Abstract numbering must have id")?;
+            let mut levels = abstract_num
+                .get_children("w:lvl")
+                .map(parse_numbering_level)
+                .collect::<anyhow::Result<Vec<_>>>()?;
+            levels.sort_by_key(|level| level.level);
+
+            numbering
+                .abstract_nums
+                .insert(id, AbstractNumbering { levels });
+        }
+
+        for num in value.root.get_children("w:num") {
+            let id = num
+                .get_attr_parsed::<usize>("w:numId")
+                .context("Numbering must have id")?;
+            let abstract_num_id = num
+                .get_childs_attr_parsed::<usize>("w:abstractNumId", "w:val")
+                .context("Numbering must refer to an abstract numbering")?;
+            let overrides = num
+                .get_children("w:lvlOverride")
+                .map(|level_override| {
+                    Ok(LevelOverride {
+                        level: level_override
+                            .get_attr_parsed::<usize>("w:ilvl")
+                            .context("Level override must have level")?,
+                        start: level_override
+                            .get_childs_attr_parsed::<i64>("w:startOverride", "w:val"),
+                        definition: level_override
+                            .get_child("w:lvl")
+                            .map(parse_numbering_level)
+                            .transpose()?,
+                    })
+                })
+                .collect::<anyhow::Result<_>>()?;
+
+            numbering.nums.insert(
+                id,
+                NumberingInstance {
+                    abstract_num_id,
+                    overrides,
+                },
+            );
+        }
+
+        Ok(numbering)
+    }
+}
+
+impl<'a> TryFrom<&'a word_xml::WordXMLDocument> for StyleSheet {
+    type Error = anyhow::Error;
+
+    fn try_from(value: &'a word_xml::WordXMLDocument) -> Result<Self, Self::Error> {
+        let mut styles = StyleSheet::default();
+
+        if let Some(defaults) = value.root.get_child("w:docDefaults") {
+            styles.default_paragraph_properties = defaults
+                .get_child("w:pPrDefault")
+                .and_then(|default| default.get_child("w:pPr"))
+                .cloned();
+            styles.default_run_properties = defaults
+                .get_child("w:rPrDefault")
+                .and_then(|default| default.get_child("w:rPr"))
+                .cloned();
+        }
+
+        for style in value.root.get_children("w:style") {
+            let id = style
+                .get_attr_parsed::<String>("w:styleId")
+                .context("Style must have id")?;
+
+            styles.styles.insert(
+                id.clone(),
+                Style {
+                    id,
+                    name: style.get_childs_attr_parsed::<String>("w:name", "w:val"),
+                    style_type: style
+                        .get_attr_parsed::<StyleType>("w:type")
+                        .unwrap_or_default(),
+                    based_on: style.get_childs_attr_parsed::<String>("w:basedOn", "w:val"),
+                    link: style.get_childs_attr_parsed::<String>("w:link", "w:val"),
+                    is_default: matches!(style.get_attr("w:default"), Some("1" | "true" | "on")),
+                    paragraph_properties: style.get_child("w:pPr").cloned(),
+                    run_properties: style.get_child("w:rPr").cloned(),
+                    table_properties: style.get_child("w:tblPr").cloned(),
+                },
+            );
+        }
+
+        Ok(styles)
+    }
+}
+
+impl<'a> TryFrom<&'a word_xml::WordXMLDocument> for Relationships {
+    type Error = anyhow::Error;
+
+    fn try_from(value: &'a word_xml::WordXMLDocument) -> Result<Self, Self::Error> {
+        let mut relationships = Relationships::default();
+
+        for relationship in value.root.get_children("Relationship") {
+            let id = relationship
+                .get_attr_parsed::<String>("Id")
+                .context("Relationship must have id")?;
+
+            relationships.relationships.insert(
+                id,
+                Relationship {
+                    rel_type: relationship
+                        .get_attr_parsed::<String>("Type")
+                        .unwrap_or_default(),
+                    target: relationship
+                        .get_attr_parsed::<String>("Target")
+                        .context("Relationship must have target")?,
+                    external: relationship.get_attr("TargetMode") == Some("External"),
+                },
+            );
+        }
+
+        Ok(relationships)
+    }
+}
+
+fn parse_numbering_level(lvl: &word_xml::Element) -> anyhow::Result<NumberingLevel> {
+    let ppr = lvl.get_child("w:pPr");
+    Ok(NumberingLevel {
+        level: lvl
+            .get_attr_parsed::<usize>("w:ilvl")
+            .context("Numbering level must have level")?,
+        start: lvl
+            .get_childs_attr_parsed::<i64>("w:start", "w:val")
+            .unwrap_or_default(),
+        format: lvl
+            .get_childs_attr_parsed::<NumberFormat>("w:numFmt", "w:val")
+            .unwrap_or_default(),
+        text: lvl
+            .get_childs_attr_parsed::<String>("w:lvlText", "w:val")
+            .unwrap_or_default(),
+        restart: lvl.get_childs_attr_parsed::<usize>("w:lvlRestart", "w:val"),
+        suffix: lvl
+            .get_childs_attr_parsed::<LevelSuffix>("w:suff", "w:val")
+            .unwrap_or_default(),
+        indent: ppr.and_then(parse_indentation).unwrap_or_default(),
+        tab: ppr
+            .and_then(|ppr| ppr.get_child("w:tabs"))
+            .and_then(|tabs| {
+                tabs.get_children("w:tab")
+                    .filter(|tab| tab.get_attr("w:val") != Some("clear"))
+                    .find_map(|tab| tab.get_attr_parsed::<i64>("w:pos"))
+            })
+            .map(|pos| pos as f32 / 10.),
+        font_name: lvl
+            .get_child("w:rPr")
+            .and_then(|rpr| rpr.get_childs_attr_parsed::<String>("w:rFonts", "w:ascii")),
+    })
+}
+
+fn parse_indentation(ppr: &word_xml::Element) -> Option<Indentation> {
+    let mut indent = Indentation::default();
+    apply_indentation(ppr.get_child("w:ind")?, &mut indent);
+    Some(indent)
+}
+
+/// Apply the attributes set in `ind` over `indent`. `w:firstLine` and
+/// `w:hanging` replace each other.
+fn apply_indentation(ind: &word_xml::Element, indent: &mut Indentation) {
+    let value = |names: &[&str]| {
+        names
+            .iter()
+            .find_map(|name| ind.get_attr_parsed::<i64>(name))
+            .map(|value| value as f32 / 10.)
+    };
+
+    if let Some(left) = value(&["w:left", "w:start"]) {
+        indent.left = left;
+    }
+    if let Some(right) = value(&["w:right", "w:end"]) {
+        indent.right = right;
+    }
+    if let Some(first_line) = value(&["w:firstLine"]) {
+        (indent.first_line, indent.hanging) = (first_line, 0.);
+    }
+    if let Some(hanging) = value(&["w:hanging"]) {
+        (indent.first_line, indent.hanging) = (0., hanging);
+    }
+}
+
+/// Apply the tab stops of `tabs` over the inherited `stops`: a stop replaces
+/// the one at its position, and a `clear` stop removes it.
+fn apply_tab_stops(tabs: &word_xml::Element, stops: &mut Vec<TabStop>) {
+    for tab in tabs.get_children("w:tab") {
+        let (Some(alignment), Some(position)) = (
+            tab.get_attr_parsed::<TabAlignment>("w:val"),
+            tab.get_attr_parsed::<f32>("w:pos"),
+        ) else {
+            continue;
+        };
+        let position = position / 10.;
+
+        stops.retain(|stop| stop.position != position);
+        if alignment != TabAlignment::Clear {
+            stops.push(TabStop {
+                position,
+                alignment,
+                leader: tab.get_attr_parsed::<TabLeader>("w:leader").unwrap_or_default(),
+            });
+        }
+    }
+    stops.sort_by(|a, b| a.position.total_cmp(&b.position));
+}
+
 #[inline]
 fn parse_paragraph(
     root_element: &word_xml::Element,
+    ctx: &mut PartContext,
     document: &mut DocxDocument,
 ) -> super::DocxNode {
     use super::DocxNode::Paragrapth;
+    let mut properties = parse_paragraph_properties(root_element, document);
+    properties.bookmarks = parse_bookmarks(root_element);
+    let texts = get_texts_of_element(root_element, properties.style.as_deref(), ctx, document);
     Paragrapth {
-        properties: parse_paragraph_properties(root_element, document),
+        properties,
         attrs: root_element
             .attrs
             .iter()
             .map(|attr| (attr.name.clone(), attr.value.clone()))
             .collect(),
-        texts: get_texts_of_element(root_element, document),
+        texts,
     }
 }
 
+fn parse_table(
+    root_element: &word_xml::Element,
+    ctx: &mut PartContext,
+    document: &mut DocxDocument,
+) -> anyhow::Result<super::DocxNode> {
+    let grid = root_element
+        .get_child("w:tblGrid")
+        .map(|grid| {
+            grid.get_children("w:gridCol")
+                .map(|col| col.get_attr_parsed::<u64>("w:w").unwrap_or_default() as f32 / 10.)
+                .collect()
+        })
+        .unwrap_or_default();
+
+    let tbl_pr = root_element.get_child("w:tblPr");
+    let style =
+        tbl_pr.and_then(|tbl_pr| tbl_pr.get_childs_attr_parsed::<String>("w:tblStyle", "w:val"));
+    let base = document.styles.table_properties(style.as_deref());
+    let properties = match tbl_pr {
+        Some(tbl_pr) => parse_table_properties(tbl_pr, base),
+        None => base,
+    };
+
+    let rows = root_element
+        .get_children("w:tr")
+        .map(|tr| ctx.scoped(tr, |ctx| parse_table_row(tr, &properties, ctx, document)))
+        .collect::<anyhow::Result<_>>()?;
+
+    Ok(super::DocxNode::Table {
+        properties,
+        grid,
+        rows,
+    })
+}
+
+/// `tbl_pr` applied over `base`, the properties of the table style.
+pub fn parse_table_properties(
+    tbl_pr: &word_xml::Element,
+    base: TableProperties,
+) -> TableProperties {
+    TableProperties {
+        style: tbl_pr
+            .get_childs_attr_parsed::<String>("w:tblStyle", "w:val")
+            .or(base.style),
+        width: tbl_pr
+            .get_child("w:tblW")
+            .and_then(parse_table_width)
+            .or(base.width),
+        justify: tbl_pr
+            .get_childs_attr_parsed::<Justification>("w:jc", "w:val")
+            .or(base.justify),
+        borders: match tbl_pr.get_child("w:tblBorders") {
+            Some(borders) => parse_table_borders(borders, base.borders),
+            None => base.borders,
+        },
+        cell_margin: tbl_pr
+            .get_child("w:tblCellMar")
+            .map(|margin| parse_cell_margins(margin, base.cell_margin))
+            .unwrap_or(base.cell_margin),
+    }
+}
+
+fn parse_table_row(
+    tr: &word_xml::Element,
+    table_properties: &TableProperties,
+    ctx: &mut PartContext,
+    document: &mut DocxDocument,
+) -> anyhow::Result<TableRow> {
+    let properties = tr
+        .get_child("w:trPr")
+        .map(|tr_pr| RowProperties {
+            height: tr_pr
+                .get_childs_attr_parsed::<u64>("w:trHeight", "w:val")
+                .map(|height| height as f32 / 10.),
+            header: has_toggle(tr_pr, "w:tblHeader"),
+            cant_split: has_toggle(tr_pr, "w:cantSplit"),
+        })
+        .unwrap_or_default();
+
+    let cells = tr
+        .get_children("w:tc")
+        .map(|tc| ctx.scoped(tc, |ctx| parse_table_cell(tc, table_properties, ctx, document)))
+        .collect::<anyhow::Result<_>>()?;
+
+    Ok(TableRow { properties, cells })
+}
+
+fn parse_table_cell(
+    tc: &word_xml::Element,
+    table_properties: &TableProperties,
+    ctx: &mut PartContext,
+    document: &mut DocxDocument,
+) -> anyhow::Result<TableCell> {
+    let properties = tc
+        .get_child("w:tcPr")
+        .map(|tc_pr| CellProperties {
+            width: tc_pr.get_child("w:tcW").and_then(parse_table_width),
+            grid_span: tc_pr
+                .get_childs_attr_parsed::<usize>("w:gridSpan", "w:val")
+                .unwrap_or(1)
+                .max(1),
+            v_merge: tc_pr.get_child("w:vMerge").map(|v_merge| {
+                v_merge
+                    .get_attr_parsed::<VerticalMerge>("w:val")
+                    .unwrap_or(VerticalMerge::Continue)
+            }),
+            borders: tc_pr
+                .get_child("w:tcBorders")
+                .map(|borders| parse_table_borders(borders, TableBorders::default()))
+                .unwrap_or_default(),
+            margin: tc_pr
+                .get_child("w:tcMar")
+                .map(|margin| parse_cell_margins(margin, table_properties.cell_margin)),
+            shading: tc_pr.get_childs_attr_parsed::<Color>("w:shd", "w:fill"),
+        })
+        .unwrap_or_default();
+
+    let content = tc
+        .inners
+        .iter()
+        .filter_map(Node::get_element)
+        .filter(|element| element.name != "w:tcPr")
+        .map(|element| parse_body_element(element, ctx, document))
+        .collect::<anyhow::Result<_>>()?;
+
+    Ok(TableCell {
+        properties,
+        content,
+    })
+}
+
+/// Widths are stored in document units for `dxa` and in fiftieths of a
+/// percent for `pct`, the same as `w:w` itself.
+fn parse_table_width(width: &word_xml::Element) -> Option<TableWidth> {
+    let width_type = width
+        .get_attr_parsed::<WidthType>("w:type")
+        .unwrap_or_default();
+    let value = width.get_attr("w:w")?;
+    let value = match value.strip_suffix('%') {
+        Some(percent) => percent.parse::<f32>().ok()? * 50.,
+        None => value.parse::<f32>().ok()?,
+    };
+
+    Some(TableWidth {
+        value: if width_type == WidthType::Dxa {
+            value / 10.
+        } else {
+            value
+        },
+        width_type,
+    })
+}
+
+fn parse_table_borders(borders: &word_xml::Element, default: TableBorders) -> TableBorders {
+    let border = |names: &[&str]| {
+        names
+            .iter()
+            .find_map(|name| borders.get_child(name))
+            .map(|border| Border {
+                style: border
+                    .get_attr_parsed::<BorderStyle>("w:val")
+                    .unwrap_or_default(),
+                size: border.get_attr_parsed::<u32>("w:sz").unwrap_or(4),
+                color: border.get_attr_parsed::<Color>("w:color"),
+            })
+    };
+
+    TableBorders {
+        top: border(&["w:top"]).or(default.top),
+        left: border(&["w:left", "w:start"]).or(default.left),
+        bottom: border(&["w:bottom"]).or(default.bottom),
+        right: border(&["w:right", "w:end"]).or(default.right),
+        inside_h: border(&["w:insideH"]).or(default.inside_h),
+        inside_v: border(&["w:insideV"]).or(default.inside_v),
+    }
+}
+
+fn parse_cell_margins(margins: &word_xml::Element, default: CellMargins) -> CellMargins {
+    let margin = |names: &[&str], default: f32| {
+        names
+            .iter()
+            .find_map(|name| margins.get_childs_attr_parsed::<u64>(name, "w:w"))
+            .map(|value| value as f32 / 10.)
+            .unwrap_or(default)
+    };
+
+    CellMargins {
+        top: margin(&["w:top"], default.top),
+        left: margin(&["w:left", "w:start"], default.left),
+        bottom: margin(&["w:bottom"], default.bottom),
+        right: margin(&["w:right", "w:end"], default.right),
+    }
+}
+
+/// On/off properties are set by presence unless `w:val` turns them off.
+fn has_toggle(parent_tag: &word_xml::Element, name: &str) -> bool {
+    get_toggle(parent_tag, name).unwrap_or_default()
+}
+
+/// Like [`has_toggle`], but `None` when the property isn't there at all.
+fn get_toggle(parent_tag: &word_xml::Element, name: &str) -> Option<bool> {
+    parent_tag
+        .get_child(name)
+        .map(|tag| !matches!(tag.get_attr("w:val"), Some("0" | "false" | "off")))
+}
+
 #[inline]
 fn parse_paragraph_properties(
     root_element: &word_xml::Element,
     document: &mut DocxDocument,
 ) -> ParagraphProperties {
-    let Some(ppr) = root_element.get_child("w:pPr") else {
-        return ParagraphProperties::default();
+    let ppr = root_element.get_child("w:pPr");
+    let style = ppr.and_then(|ppr| ppr.get_childs_attr_parsed::<String>("w:pStyle", "w:val"));
+
+    let mut properties = document.styles.paragraph_properties(style.as_deref());
+    if let Some(ppr) = ppr {
+        apply_paragraph_properties(ppr, &mut properties);
+    }
+    properties.text_properties = Some(parse_text_properties(ppr, style.as_deref(), document, ""));
+    properties.style = style;
+    properties.section = ppr
+        .and_then(|ppr| ppr.get_child("w:sectPr"))
+        .and_then(|sect_pr| match parse_sectr_properties(sect_pr) {
+            Ok(section) => section.get_sectr_properties(),
+            Err(err) => {
+                log::warn!("skipping section break: {:#}", err);
+                None
+            }
+        });
+    properties
+}
+
+/// Apply the properties set in `ppr` over `properties`.
+pub fn apply_paragraph_properties(ppr: &word_xml::Element, properties: &mut ParagraphProperties) {
+    let spacing = parce_spacing(ppr);
+    properties.spacing = SpacingProperties {
+        line: spacing.line.or(properties.spacing.line),
+        line_rule: spacing.line_rule.or(properties.spacing.line_rule.take()),
+        after: spacing.after.or(properties.spacing.after),
+        before: spacing.before.or(properties.spacing.before),
     };
-    ParagraphProperties {
-        justify: ppr.get_childs_attr_parsed::<Justification>("w:jc", "w:val"),
-        text_properties: parse_text_properties(ppr, document, &Default::default()),
-        spacing: parce_spacing(ppr),
+
+    if let Some(justify) = ppr.get_childs_attr_parsed::<Justification>("w:jc", "w:val") {
+        properties.justify = Some(justify);
+    }
+
+    if let Some(num_pr) = ppr.get_child("w:numPr") {
+        properties.numbering = parse_numbering_reference(num_pr, properties.numbering);
+    }
+
+    if let Some(ind) = ppr.get_child("w:ind") {
+        apply_indentation(ind, properties.indent.get_or_insert_with(Default::default));
+    }
+
+    if let Some(tabs) = ppr.get_child("w:tabs") {
+        apply_tab_stops(tabs, &mut properties.tabs);
+    }
+
+    for (name, toggle) in [
+        ("w:pageBreakBefore", &mut properties.page_break_before),
+        ("w:keepNext", &mut properties.keep_next),
+        ("w:keepLines", &mut properties.keep_lines),
+        ("w:widowControl", &mut properties.widow_control),
+    ] {
+        if let Some(value) = get_toggle(ppr, name) {
+            *toggle = value;
+        }
     }
 }
 
+/// A paragraph may set only `w:ilvl` and take `w:numId` from its style.
+fn parse_numbering_reference(
+    num_pr: &word_xml::Element,
+    inherited: Option<NumberingReference>,
+) -> Option<NumberingReference> {
+    // `w:numId` 0 removes numbering inherited from a style.
+    let num_id = num_pr
+        .get_childs_attr_parsed::<usize>("w:numId", "w:val")
+        .or(inherited.map(|reference| reference.num_id))
+        .filter(|num_id| *num_id != 0)?;
+
+    Some(NumberingReference {
+        num_id,
+        level: num_pr
+            .get_childs_attr_parsed::<usize>("w:ilvl", "w:val")
+            .or(inherited.map(|reference| reference.level))
+            .unwrap_or_default(),
+    })
+}
+
 fn parce_spacing(ppr: &word_xml::Element) -> SpacingProperties {
     SpacingProperties {
         line: parse_float_as_some(ppr, "w:spacing", "w:line"),
-        line_rule: ppr.get_childs_attr_parsed::<LineRule>("spacing", "w:lineRule"),
+        line_rule: ppr.get_childs_attr_parsed::<LineRule>("w:spacing", "w:lineRule"),
         after: parse_float_as_some(ppr, "w:spacing", "w:after"),
         before: parse_float_as_some(ppr, "w:spacing", "w:before"),
     }
@@ -121,9 +874,68 @@ fn parse_sectr_properties(root_element: &word_xml::Element) -> anyhow::Result<su
         form_prot: parse_form_prot(root_element),
         text_direction: parse_text_direction(root_element),
         document_grid: parse_document_grid(root_element),
+        headers: parse_header_footer_references(root_element, "w:headerReference"),
+        footers: parse_header_footer_references(root_element, "w:footerReference"),
+        title_page: has_toggle(root_element, "w:titlePg"),
+        columns: parse_columns(root_element),
     })
 }
 
+/// `w:cols` of more than one column. Equal width columns are `w:space` apart
+/// by default, unequal ones are listed as `w:col`.
+fn parse_columns(root_element: &word_xml::Element) -> Option<Columns> {
+    let cols = root_element.get_child("w:cols")?;
+    let twips = |element: &word_xml::Element, name: &str| {
+        element.get_attr_parsed::<f32>(name).map(|value| value / 10.)
+    };
+
+    let columns = cols
+        .get_children("w:col")
+        .map(|col| Column {
+            width: twips(col, "w:w").unwrap_or_default(),
+            space: twips(col, "w:space").unwrap_or_default(),
+        })
+        .collect::<Vec<_>>();
+    let equal_width = match cols.get_attr("w:equalWidth") {
+        Some(value) => !matches!(value, "0" | "false" | "off"),
+        None => columns.is_empty(),
+    };
+    let count = if equal_width {
+        cols.get_attr_parsed::<usize>("w:num").unwrap_or(1)
+    } else {
+        columns.len()
+    };
+    if count < 2 {
+        return None;
+    }
+
+    Some(Columns {
+        count,
+        space: twips(cols, "w:space").unwrap_or(72.),
+        equal_width,
+        separator: matches!(cols.get_attr("w:sep"), Some("1" | "true" | "on")),
+        columns,
+    })
+}
+
+fn parse_header_footer_references(
+    root_element: &word_xml::Element,
+    name: &str,
+) -> HeaderFooterReferences {
+    let mut references = HeaderFooterReferences::default();
+    for reference in root_element.get_children(name) {
+        let Some(id) = reference.get_attr_parsed::<String>("r:id") else {
+            continue;
+        };
+        match reference.get_attr("w:type").unwrap_or("default") {
+            "first" => references.first = Some(id),
+            "even" => references.even = Some(id),
+            _ => references.default = Some(id),
+        }
+    }
+    references
+}
+
 fn parse_document_grid(root_element: &word_xml::Element) -> Option<DocumentGrid> {
     Some(DocumentGrid {
         char_space: root_element.get_childs_attr_parsed::<u64>("w:docGrid", "w:charSpace")?,
@@ -177,6 +989,9 @@ fn parse_page_size(root_element: &word_xml::Element) -> Option<PageSize> {
     Some(PageSize {
         width: get_float(root_element, "w:pgSz", "w:w").ok()?,
         height: get_float(root_element, "w:pgSz", "w:h").ok()?,
+        orientation: root_element
+            .get_childs_attr_parsed("w:pgSz", "w:orient")
+            .unwrap_or_default(),
     })
 }
 
@@ -195,68 +1010,403 @@ fn get_float(
 #[inline]
 fn get_texts_of_element(
     root_element: &word_xml::Element,
+    paragraph_style: Option<&str>,
+    ctx: &mut PartContext,
     document: &mut DocxDocument,
 ) -> Vec<super::TextNode> {
+    let mut texts = Vec::new();
+    push_runs(root_element, None, None, paragraph_style, ctx, document, &mut texts);
+    texts
+}
+
+/// Runs of `root_element` and of the `w:hyperlink`, `w:ins` and `w:del`
+/// elements in it, which wrap runs in either order. Comment ranges may span
+/// paragraphs, so the open ones are kept in `ctx`.
+fn push_runs(
+    root_element: &word_xml::Element,
+    link: Option<&Hyperlink>,
+    revision: Option<&Revision>,
+    paragraph_style: Option<&str>,
+    ctx: &mut PartContext,
+    document: &mut DocxDocument,
+    texts: &mut Vec<super::TextNode>,
+) {
+    for element in root_element.inners.iter().filter_map(word_xml::Node::get_element) {
+        match element.name.as_str() {
+            "w:r" => {
+                let preserve_space = space_preserved(element, ctx.preserve_space);
+                let runs = parse_run(element, paragraph_style, preserve_space, document);
+                texts.extend(runs.into_iter().map(|text| TextNode {
+                    link: link.cloned(),
+                    revision: revision.cloned(),
+                    comments: ctx.open_comments.clone(),
+                    ..text
+                }));
+
+                // A comment without a range is anchored to the text before
+                // its reference.
+                for reference in element.get_children("w:commentReference") {
+                    if let Some(id) = reference.get_attr_parsed::<i64>("w:id")
+                        && let Some(last) = texts.last_mut()
+                        && !last.comments.contains(&id)
+                    {
+                        last.comments.push(id);
+                    }
+                }
+            }
+            "w:commentRangeStart" => {
+                if let Some(id) = element.get_attr_parsed::<i64>("w:id") {
+                    ctx.open_comments.push(id);
+                }
+            }
+            "w:commentRangeEnd" => {
+                if let Some(id) = element.get_attr_parsed::<i64>("w:id") {
+                    ctx.open_comments.retain(|open| *open != id);
+                }
+            }
+            "w:hyperlink" => {
+                let link = Hyperlink {
+                    relationship_id: element.get_attr_parsed::<String>("r:id"),
+                    anchor: element.get_attr_parsed::<String>("w:anchor"),
+                };
+                let link = Some(&link);
+                ctx.scoped(element, |ctx| {
+                    push_runs(element, link, revision, paragraph_style, ctx, document, texts)
+                });
+            }
+            "w:ins" | "w:del" => {
+                let revision = Revision::from_element(element);
+                let revision = revision.as_ref();
+                ctx.scoped(element, |ctx| {
+                    push_runs(element, link, revision, paragraph_style, ctx, document, texts)
+                });
+            }
+            _ => {}
+        }
+    }
+}
+
+/// Bookmarks starting in a paragraph, also the ones inside its hyperlinks.
+fn parse_bookmarks(root_element: &word_xml::Element) -> Vec<Bookmark> {
     root_element
         .inners
         .iter()
         .filter_map(word_xml::Node::get_element)
-        .filter(|tag| tag.name.as_str() == "w:r")
-        .filter_map(|r_tag| {
-            let content = r_tag.get_child("w:t")?.get_texts();
-
-            let properties = parse_text_properties(r_tag, document, &content)?;
-
-            Some(super::TextNode {
-                properties,
-                content,
+        .flat_map(|element| match element.name.as_str() {
+            "w:hyperlink" => element.get_children("w:bookmarkStart").collect::<Vec<_>>(),
+            "w:bookmarkStart" => vec![element],
+            _ => Vec::new(),
+        })
+        .filter_map(|bookmark| {
+            Some(Bookmark {
+                id: bookmark.get_attr_parsed::<i64>("w:id")?,
+                name: bookmark.get_attr_parsed::<String>("w:name")?,
             })
         })
         .collect()
 }
 
-#[inline]
-fn parse_text_properties(
-    parent_tag: &word_xml::Element,
+/// Texts of the run `r_tag`, keeping their whitespace where `xml:space` of
+/// the `w:t` or else `preserve_space` of the run says so.
+fn parse_run(
+    r_tag: &word_xml::Element,
+    paragraph_style: Option<&str>,
+    preserve_space: bool,
     document: &mut DocxDocument,
-    content: &String,
-) -> Option<super::TextProperties> {
-    let rpr = parent_tag.get_child("w:rPr")?;
+) -> Vec<super::TextNode> {
+    // Text in document order, with every `w:tab` and `w:br` a content of its
+    // own. `w:lastRenderedPageBreak` only tells where Word broke the page the
+    // last time, so it is left out.
+    let mut contents: Vec<(String, Option<BreakType>)> = Vec::new();
+    for element in r_tag.inners.iter().filter_map(word_xml::Node::get_element) {
+        match element.name.as_str() {
+            "w:t" | "w:delText" => {
+                let text = if space_preserved(element, preserve_space) {
+                    element.get_texts()
+                } else {
+                    element.get_texts().trim().to_string()
+                };
+                match contents.last_mut() {
+                    Some((last, None)) if last != TextNode::TAB => last.push_str(&text),
+                    _ => contents.push((text, None)),
+                }
+            }
+            "w:tab" => contents.push((TextNode::TAB.to_string(), None)),
+            "w:br" | "w:cr" => {
+                let br = element.get_attr_parsed::<BreakType>("w:type");
+                contents.push((String::new(), Some(br.unwrap_or_default())));
+            }
+            _ => {}
+        }
+    }
+    let images = r_tag
+        .get_children("w:drawing")
+        .filter_map(parse_inline_image)
+        .collect::<Vec<_>>();
+    let notes = parse_note_marks(r_tag, document);
+    if contents.is_empty() && images.is_empty() && notes.is_empty() {
+        return Vec::new();
+    }
 
-    let size = rpr
-        .get_childs_attr_parsed::<i32>("w:sz", "w:val")
-        .map(TextSize::from);
+    let properties = parse_text_properties(
+        Some(r_tag),
+        paragraph_style,
+        document,
+        &contents
+            .iter()
+            .map(|(content, _)| content.as_str())
+            .collect::<String>(),
+    );
 
-    let size_cs = rpr
-        .get_childs_attr_parsed::<i32>("w:szCs", "w:val")
-        .map(TextSize::from);
+    let mut texts = images
+        .into_iter()
+        .map(|image| super::TextNode {
+            properties: properties.clone(),
+            content: String::new(),
+            image: Some(image),
+            note: None,
+            br: None,
+            link: None,
+            revision: None,
+            comments: Vec::new(),
+        })
+        .collect::<Vec<_>>();
+    texts.extend(notes.into_iter().map(|(note, content)| super::TextNode {
+        properties: properties.clone(),
+        content,
+        image: None,
+        note: Some(note),
+        br: None,
+        link: None,
+        revision: None,
+        comments: Vec::new(),
+    }));
+    texts.extend(contents.into_iter().map(|(content, br)| TextNode {
+        properties: properties.clone(),
+        content,
+        image: None,
+        note: None,
+        br,
+        link: None,
+        revision: None,
+        comments: Vec::new(),
+    }));
+    texts
+}
+
+/// Note marks of a run with their numbers. References are numbered in the
+/// order they are met, the marks starting notes once the note is parsed.
+fn parse_note_marks(
+    r_tag: &word_xml::Element,
+    document: &mut DocxDocument,
+) -> Vec<(NoteMark, String)> {
+    let mut marks = Vec::new();
+    for kind in [NoteKind::Footnote, NoteKind::Endnote] {
+        for reference in r_tag.get_children(kind.reference_name()) {
+            let Some(id) = reference.get_attr_parsed::<i64>("w:id") else {
+                continue;
+            };
+            if document.note_number(kind, id).is_none() {
+                document.note_references.push((kind, id));
+            }
+            let number = document.note_number(kind, id).unwrap_or_default();
+            marks.push((NoteMark { kind, id: Some(id) }, kind.format(number)));
+        }
 
-    let font_name = rpr.get_childs_attr_parsed::<String>("w:rFonts", "w:ascii");
-    let font_handle = if let Some(font_name) = font_name.clone() {
-        document.init_or_push_to_font(font_name, content.clone())
+        if r_tag.get_child(kind.mark_name()).is_some() {
+            marks.push((NoteMark { kind, id: None }, String::new()));
+        }
+    }
+    marks
+}
+
+/// Pictures placed inline. Floating `wp:anchor` drawings are left out.
+fn parse_inline_image(drawing: &word_xml::Element) -> Option<InlineImage> {
+    let inline = drawing.get_child("wp:inline")?;
+    let relationship_id = inline
+        .descendants()
+        .find(|element| element.name == "a:blip")?
+        .get_attr_parsed::<String>("r:embed")?;
+
+    // 914400 EMUs and 1440 twips to the inch.
+    let extent = inline.get_child("wp:extent")?;
+    let emu = |name: &str| {
+        extent
+            .get_attr_parsed::<u64>(name)
+            .map(|emu| emu as f32 / 6350.)
+    };
+
+    Some(InlineImage {
+        relationship_id,
+        width: emu("cx")?,
+        height: emu("cy")?,
+        description: inline.get_child("wp:docPr").and_then(|doc_pr| {
+            doc_pr
+                .get_attr_parsed::<String>("descr")
+                .or(doc_pr.get_attr_parsed::<String>("name"))
+        }),
+        drawing: drawing.clone(),
+    })
+}
+
+/// Run properties of `parent_tag`'s `w:rPr` over those of the styles, with
+/// `content` registered in the resolved font.
+fn parse_text_properties(
+    parent_tag: Option<&word_xml::Element>,
+    paragraph_style: Option<&str>,
+    document: &mut DocxDocument,
+    content: &str,
+) -> TextProperties {
+    let rpr = parent_tag.and_then(|tag| tag.get_child("w:rPr"));
+    let style = rpr.and_then(|rpr| rpr.get_childs_attr_parsed::<String>("w:rStyle", "w:val"));
+
+    let mut properties = document
+        .styles
+        .text_properties(paragraph_style, style.as_deref());
+    if let Some(rpr) = rpr {
+        apply_run_properties(rpr, &mut properties);
+    }
+    properties.style = style;
+    properties.resolve_theme_fonts(&document.theme_fonts);
+
+    properties.font_handle = if let Some(font_name) = properties.font_name.clone() {
+        document.init_or_push_to_font(font_name, content.to_string())
     } else {
-        document.push_to_default_font(content.clone())
+        document.push_to_default_font(content.to_string())
     };
 
-    let color = rpr.get_childs_attr_parsed::<Color>("w:color", "w:val");
+    properties
+}
 
-    let width = rpr
-        .has_child("w:b")
-        .then_some(TextWeight::Bold)
-        .unwrap_or_default();
+/// Apply the properties set in `rpr` over `properties`.
+pub fn apply_run_properties(rpr: &word_xml::Element, properties: &mut TextProperties) {
+    if let Some(size) = rpr.get_childs_attr_parsed::<i32>("w:sz", "w:val") {
+        properties.size = Some(TextSize::from(size));
+    }
 
-    let italic = rpr.has_child("w:i");
+    if let Some(size_cs) = rpr.get_childs_attr_parsed::<i32>("w:szCs", "w:val") {
+        properties.size_cs = Some(TextSize::from(size_cs));
+    }
 
-    let underline = rpr.has_child("w:b");
+    if let Some(r_fonts) = rpr.get_child("w:rFonts") {
+        apply_run_fonts(r_fonts, properties);
+    }
 
-    Some(super::TextProperties {
-        font_handle,
-        font_name,
-        size,
-        size_cs,
-        weight: width,
-        color,
-        italic,
-        underline,
-    })
+    if let Some(color) = rpr.get_childs_attr_parsed::<Color>("w:color", "w:val") {
+        properties.color = Some(color);
+    }
+
+    let weight = |bold| match bold {
+        true => TextWeight::Bold,
+        false => TextWeight::Regular,
+    };
+    if let Some(bold) = get_toggle(rpr, "w:b") {
+        properties.weight = weight(bold);
+    }
+    if let Some(bold) = get_toggle(rpr, "w:bCs") {
+        properties.weight_cs = weight(bold);
+    }
+
+    if let Some(italic) = get_toggle(rpr, "w:i") {
+        properties.italic = italic;
+    }
+    if let Some(italic) = get_toggle(rpr, "w:iCs") {
+        properties.italic_cs = italic;
+    }
+
+    if let Some(underline) = rpr.get_child("w:u") {
+        properties.underline = underline.get_attr_parsed::<UnderlineStyle>("w:val");
+    }
+
+    let toggles = [
+        ("w:strike", &mut properties.strike),
+        ("w:dstrike", &mut properties.double_strike),
+        ("w:caps", &mut properties.caps),
+        ("w:smallCaps", &mut properties.small_caps),
+        ("w:vanish", &mut properties.vanish),
+    ];
+    for (name, toggle) in toggles {
+        if let Some(value) = get_toggle(rpr, name) {
+            *toggle = value;
+        }
+    }
+
+    if let Some(vertical_align) =
+        rpr.get_childs_attr_parsed::<VerticalAlign>("w:vertAlign", "w:val")
+    {
+        properties.vertical_align = Some(vertical_align);
+    }
+
+    if let Some(highlight) = rpr.get_child("w:highlight") {
+        properties.highlight = highlight.get_attr_parsed::<HighlightColor>("w:val");
+    }
+
+    if let Some(shading) = rpr.get_child("w:shd") {
+        properties.shading = shading.get_attr_parsed::<Color>("w:fill");
+    }
+
+    if let Some(spacing) = rpr.get_childs_attr_parsed::<f32>("w:spacing", "w:val") {
+        properties.spacing = Some(spacing / 10.);
+    }
+
+    if let Some(stretch) = rpr.get_childs_attr_parsed::<String>("w:w", "w:val") {
+        properties.stretch = stretch.trim_end_matches('%').parse::<f32>().ok();
+    }
+
+    if let Some(position) = rpr.get_childs_attr_parsed::<f32>("w:position", "w:val") {
+        properties.position = Some(position);
+    }
+
+    if let Some(lang) = rpr.get_child("w:lang") {
+        let apply = |attr: &str, value: &mut Option<String>| {
+            if let Some(lang) = lang.get_attr_parsed::<String>(attr) {
+                *value = Some(lang);
+            }
+        };
+        apply("w:val", &mut properties.lang.latin);
+        apply("w:eastAsia", &mut properties.lang.east_asia);
+        apply("w:bidi", &mut properties.lang.bidi);
+    }
+}
+
+/// An explicit font of a slot overrides the theme font inherited from the
+/// styles, while a theme font set next to it wins over the name.
+fn apply_run_fonts(r_fonts: &word_xml::Element, properties: &mut TextProperties) {
+    let fonts = &mut properties.fonts;
+    let slots = [
+        (
+            "w:ascii",
+            "w:asciiTheme",
+            &mut properties.font_name,
+            &mut fonts.ascii_theme,
+        ),
+        (
+            "w:hAnsi",
+            "w:hAnsiTheme",
+            &mut fonts.h_ansi,
+            &mut fonts.h_ansi_theme,
+        ),
+        (
+            "w:eastAsia",
+            "w:eastAsiaTheme",
+            &mut fonts.east_asia,
+            &mut fonts.east_asia_theme,
+        ),
+        (
+            "w:cs",
+            "w:cstheme",
+            &mut fonts.complex_script,
+            &mut fonts.complex_script_theme,
+        ),
+    ];
+    for (attr, theme_attr, name, theme) in slots {
+        let font_name = r_fonts.get_attr_parsed::<String>(attr);
+        let theme_font = r_fonts.get_attr_parsed::<ThemeFont>(theme_attr);
+        if font_name.is_some() || theme_font.is_some() {
+            *theme = theme_font;
+        }
+        if font_name.is_some() {
+            *name = font_name;
+        }
+    }
 }
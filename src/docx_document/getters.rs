@@ -2,8 +2,8 @@
 use crate::document_draw::PageProperties;
 
 use super::{
-    DocumentGrid, DocxDocument, DocxNode, FormProt, NumType, PageMargin, PageSize, PageType,
-    TextDirection,
+    Columns, DocumentGrid, DocxDocument, DocxNode, FormProt, HeaderFooterReferences, NumType,
+    Orientation, PageMargin, PageSize, PageType, TextDirection,
 };
 
 #[derive(Clone, Debug)]
@@ -15,11 +15,24 @@ pub struct SectrOfProperties {
     pub form_prot: Option<FormProt>,
     pub text_direction: TextDirection,
     pub document_grid: Option<DocumentGrid>,
+    pub headers: HeaderFooterReferences,
+    pub footers: HeaderFooterReferences,
+    pub title_page: bool,
+    pub columns: Option<Columns>,
 }
 
 impl SectrOfProperties {
+    /// Page width and height, swapped when they don't match `w:orient`.
     pub fn get_size(&self) -> (f32, f32) {
-        (self.page_size.width, self.page_size.height)
+        let PageSize {
+            width,
+            height,
+            orientation,
+        } = self.page_size;
+        match orientation {
+            Orientation::Landscape if width < height => (height, width),
+            _ => (width, height),
+        }
     }
 
     pub fn get_margins(&self) -> (f32, f32, f32, f32) {
@@ -37,9 +50,21 @@ impl DocxDocument {
         let Some(nodes) = &self.content.nodes else {
             return None;
         };
-        let properties = nodes.iter().rev().find(|v| v.is_sector_of_properties())?;
+        nodes
+            .iter()
+            .rev()
+            .find(|v| v.is_sector_of_properties())?
+            .get_sectr_properties()
+    }
+}
 
-        match properties {
+impl DocxNode {
+    pub fn is_sector_of_properties(&self) -> bool {
+        matches!(self, Self::SectrOfProperties { .. })
+    }
+
+    pub fn get_sectr_properties(&self) -> Option<SectrOfProperties> {
+        match self {
             DocxNode::SectrOfProperties {
                 page_type,
                 page_margin,
@@ -48,6 +73,10 @@ impl DocxDocument {
                 form_prot,
                 text_direction,
                 document_grid,
+                headers,
+                footers,
+                title_page,
+                columns,
             } => Some(SectrOfProperties {
                 page_type: page_type.clone(),
                 page_size: page_size.clone(),
@@ -56,15 +85,51 @@ impl DocxDocument {
                 form_prot: form_prot.clone(),
                 text_direction: text_direction.clone(),
                 document_grid: document_grid.clone(),
+                headers: headers.clone(),
+                footers: footers.clone(),
+                title_page: *title_page,
+                columns: columns.clone(),
             }),
             _ => None,
         }
     }
 }
 
-impl DocxNode {
-    pub fn is_sector_of_properties(&self) -> bool {
-        matches!(self, Self::SectrOfProperties { .. })
+impl HeaderFooterReferences {
+    /// Part shown on a page: `first` for the first page of a section with
+    /// `w:titlePg`, `even` for even pages with `w:evenAndOddHeaders`.
+    pub fn get(&self, first: bool, even: bool) -> Option<&str> {
+        if first {
+            self.first.as_deref()
+        } else if even {
+            self.even.as_deref()
+        } else {
+            self.default.as_deref()
+        }
+    }
+}
+
+impl Columns {
+    /// Start and width of every column in a `width` wide text column.
+    pub fn get_layout(&self, width: f32) -> Vec<(f32, f32)> {
+        if !self.equal_width && !self.columns.is_empty() {
+            let mut start = 0.;
+            return self
+                .columns
+                .iter()
+                .map(|column| {
+                    let layout = (start, column.width);
+                    start += column.width + column.space;
+                    layout
+                })
+                .collect();
+        }
+
+        let count = self.count.max(1);
+        let column_width = ((width - self.space * (count - 1) as f32) / count as f32).max(0.);
+        (0..count)
+            .map(|idx| (idx as f32 * (column_width + self.space), column_width))
+            .collect()
     }
 }
 
@@ -74,6 +139,7 @@ impl From<PageProperties> for SectrOfProperties {
             page_size: PageSize {
                 width: value.size.width,
                 height: value.size.height,
+                orientation: Orientation::Portrait,
             },
             page_margin: PageMargin {
                 footer: 0.,
@@ -96,6 +162,7 @@ impl Default for SectrOfProperties {
             page_size: PageSize {
                 width: 1000.,
                 height: 1000.,
+                orientation: Orientation::Portrait,
             },
             page_margin: PageMargin {
                 footer: 100.,
@@ -114,6 +181,10 @@ impl Default for SectrOfProperties {
                 line_pitch: 20,
                 grid_type: super::GridType::Default,
             }),
+            headers: HeaderFooterReferences::default(),
+            footers: HeaderFooterReferences::default(),
+            title_page: false,
+            columns: None,
         }
     }
 }
@@ -134,6 +205,7 @@ impl crate::traits::Scale for PageSize {
         Self {
             width: self.width * v,
             height: self.height * v,
+            ..self
         }
     }
 }
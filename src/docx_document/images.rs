@@ -0,0 +1,25 @@
+use anyhow::Context;
+
+use super::Image;
+
+impl Image {
+    /// Decode a PNG, JPEG, GIF or BMP picture. Only the first frame of an
+    /// animated GIF is kept.
+    pub fn decode(bytes: &[u8]) -> anyhow::Result<Self> {
+        let image = image::load_from_memory(bytes)
+            .context("unsupported or malformed image")?
+            .into_rgba8();
+
+        Ok(Self {
+            width: image.width(),
+            height: image.height(),
+            pixels: image.into_raw(),
+        })
+    }
+}
+
+impl std::fmt::Debug for Image {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "Image ({}x{})", self.width, self.height)
+    }
+}
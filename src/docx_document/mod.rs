@@ -1,21 +1,105 @@
-use std::{collections::HashSet, str::FromStr};
+use std::{
+    collections::{HashMap, HashSet},
+    str::FromStr,
+    sync::Arc,
+};
 
 
 pub mod add_font;
+pub mod comments;
 pub mod content_tree;
 pub mod display;
 pub mod from_word_xml;
 pub mod getters;
+pub mod images;
+pub mod notes;
+pub mod numbering;
 pub mod parse_fonts;
+pub mod relationships;
+pub mod revisions;
+pub mod styles;
+pub mod theme;
 
 pub use getters::SectrOfProperties;
 
 #[derive(Default, Debug)]
 pub struct DocxDocument {
     pub fonts: FontTable,
+    pub numbering: Numbering,
+    pub styles: StyleSheet,
+    pub relationships: Relationships,
+    /// Decoded pictures by the relationship id `r:embed` refers to.
+    pub images: HashMap<String, Arc<Image>>,
+    /// Body of each `header*.xml`/`footer*.xml` part by relationship id.
+    pub headers_footers: HashMap<String, Vec<DocxNode>>,
+    /// Bodies of `footnotes.xml` and `endnotes.xml` by kind and `w:id`.
+    pub notes: HashMap<(NoteKind, i64), Vec<DocxNode>>,
+    /// Notes in the order the document refers to them, which numbers them.
+    pub note_references: Vec<(NoteKind, i64)>,
+    /// Comments of `comments.xml` in the order of the part.
+    pub comments: Vec<Comment>,
+    pub settings: Settings,
+    pub theme_fonts: ThemeFonts,
     pub content: ContentTree,
 }
 
+/// Document wide options of `settings.xml`.
+#[derive(Default, Debug, Clone)]
+pub struct Settings {
+    pub even_and_odd_headers: bool,
+    pub default_tab_stop: Option<f32>,
+    /// Compatibility setting to leave lines ended by a text wrapping break
+    /// ragged in justified paragraphs.
+    pub do_not_expand_shift_return: bool,
+}
+
+/// Relationships of `document.xml` by `Id`.
+#[derive(Default, Debug, Clone)]
+pub struct Relationships {
+    pub relationships: HashMap<String, Relationship>,
+}
+
+#[derive(Debug, Clone)]
+pub struct Relationship {
+    pub rel_type: String,
+    pub target: String,
+    pub external: bool,
+}
+
+/// Picture decoded to RGBA8, rows from the top.
+#[derive(Clone)]
+pub struct Image {
+    pub width: u32,
+    pub height: u32,
+    pub pixels: Vec<u8>,
+}
+
+/// Picture placed inline with the text by a `wp:inline` drawing.
+#[derive(Debug, Clone)]
+pub struct InlineImage {
+    pub relationship_id: String,
+    /// `wp:extent` in document units.
+    pub width: f32,
+    pub height: f32,
+    pub description: Option<String>,
+    /// The whole `w:drawing`, written back as is.
+    pub drawing: word_xml::Element,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum NoteKind {
+    Footnote,
+    Endnote,
+}
+
+/// `w:footnoteReference`/`w:endnoteReference` mark in the text, or without
+/// `id` the `w:footnoteRef`/`w:endnoteRef` mark starting the note itself.
+#[derive(Debug, Clone, PartialEq)]
+pub struct NoteMark {
+    pub kind: NoteKind,
+    pub id: Option<i64>,
+}
+
 #[derive(Default, Debug)]
 pub struct FontTable {
     pub fonts: Vec<FontProperties>,
@@ -42,6 +126,7 @@ pub struct ContentTree {
 }
 
 #[derive(Debug, Clone)]
+#[allow(clippy::large_enum_variant)]
 pub enum DocxNode {
     Paragrapth {
         properties: ParagraphProperties,
@@ -56,6 +141,15 @@ pub enum DocxNode {
         form_prot: Option<FormProt>,
         text_direction: TextDirection,
         document_grid: Option<DocumentGrid>,
+        headers: HeaderFooterReferences,
+        footers: HeaderFooterReferences,
+        title_page: bool,
+        columns: Option<Columns>,
+    },
+    Table {
+        properties: TableProperties,
+        grid: Vec<f32>,
+        rows: Vec<TableRow>,
     },
     TodoWordXml(word_xml::Element)
 }
@@ -64,6 +158,35 @@ pub enum DocxNode {
 pub struct PageSize {
     pub width: f32,
     pub height: f32,
+    pub orientation: Orientation,
+}
+
+#[derive(Default, Debug, Clone, Copy, PartialEq)]
+pub enum Orientation {
+    #[default]
+    Portrait,
+    Landscape,
+}
+
+impl std::fmt::Display for Orientation {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Portrait => write!(f, "portrait"),
+            Self::Landscape => write!(f, "landscape"),
+        }
+    }
+}
+
+impl FromStr for Orientation {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "portrait" => Ok(Self::Portrait),
+            "landscape" => Ok(Self::Landscape),
+            _ => Err(anyhow::Error::msg(format!("Invalid orientation: {:?}", s))),
+        }
+    }
 }
 
 #[derive(Debug, Clone)]
@@ -174,6 +297,14 @@ impl FromStr for NumType {
     }
 }
 
+/// Relationship ids of the headers or footers of a section by `w:type`.
+#[derive(Default, Debug, Clone)]
+pub struct HeaderFooterReferences {
+    pub default: Option<String>,
+    pub first: Option<String>,
+    pub even: Option<String>,
+}
+
 #[derive(Debug, Clone)]
 pub struct PageMargin {
     pub footer: f32,
@@ -185,15 +316,42 @@ pub struct PageMargin {
     pub top: f32,
 }
 
+/// Text columns of a section, from `w:cols`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Columns {
+    pub count: usize,
+    /// Space between equal width columns.
+    pub space: f32,
+    pub equal_width: bool,
+    /// Draw a line between columns.
+    pub separator: bool,
+    /// Width and space after each column when they aren't of equal width.
+    pub columns: Vec<Column>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Column {
+    pub width: f32,
+    pub space: f32,
+}
+
 #[derive(Debug, Clone)]
 pub enum PageType {
     NextPage,
+    NextColumn,
+    Continuous,
+    EvenPage,
+    OddPage,
 }
 
 impl std::fmt::Display for PageType {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         match self {
             Self::NextPage => write!(f, "nextPage"),
+            Self::NextColumn => write!(f, "nextColumn"),
+            Self::Continuous => write!(f, "continuous"),
+            Self::EvenPage => write!(f, "evenPage"),
+            Self::OddPage => write!(f, "oddPage"),
         }
     }
 }
@@ -204,6 +362,10 @@ impl FromStr for PageType {
     fn from_str(s: &str) -> Result<Self, Self::Err> {
         match s {
             "nextPage" => Ok(Self::NextPage),
+            "nextColumn" => Ok(Self::NextColumn),
+            "continuous" => Ok(Self::Continuous),
+            "evenPage" => Ok(Self::EvenPage),
+            "oddPage" => Ok(Self::OddPage),
             _ => Err(anyhow::Error::msg(format!("Invalid page type: {:?}", s))),
         }
     }
@@ -212,14 +374,389 @@ impl FromStr for PageType {
 #[derive(Debug, Clone)]
 pub struct TextNode {
     pub properties: TextProperties,
+    /// [`TextNode::TAB`] for a `w:tab`, which is a node of its own.
     pub content: String,
+    pub image: Option<InlineImage>,
+    /// Set for a note mark, whose number is `content`.
+    pub note: Option<NoteMark>,
+    /// Set for a `w:br`, which has no content.
+    pub br: Option<BreakType>,
+    /// `w:hyperlink` the run is in.
+    pub link: Option<Hyperlink>,
+    /// `w:ins` or `w:del` the run is in.
+    pub revision: Option<Revision>,
+    /// Comments whose range the run is in.
+    pub comments: Vec<i64>,
+}
+
+/// `w:comment` of `comments.xml` with its thread and state from
+/// `commentsExtended.xml`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Comment {
+    pub id: i64,
+    pub author: Option<String>,
+    pub initials: Option<String>,
+    pub date: Option<String>,
+    /// Text of every paragraph of the comment.
+    pub paragraphs: Vec<String>,
+    /// `w14:paraId` of the last paragraph, which `commentsExtended.xml`
+    /// refers to the comment by.
+    pub para_id: Option<String>,
+    /// Comment this one is a reply to.
+    pub parent: Option<i64>,
+    pub done: bool,
+    /// `w:comment` as read, saved again as it is until the comment is
+    /// edited. New and edited comments are written from their text.
+    pub element: Option<word_xml::Element>,
+}
+
+/// Tracked change of the runs in a `w:ins` or `w:del`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Revision {
+    pub kind: RevisionKind,
+    pub id: Option<i64>,
+    pub author: Option<String>,
+    /// `w:date` as written, like `2024-05-01T10:00:00Z`.
+    pub date: Option<String>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum RevisionKind {
+    Insertion,
+    Deletion,
+}
+
+/// `w:hyperlink` to an external target by relationship id or to a bookmark of
+/// the document by its name in `anchor`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Hyperlink {
+    pub relationship_id: Option<String>,
+    pub anchor: Option<String>,
+}
+
+/// `w:bookmarkStart`, which internal hyperlinks go to by `name`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Bookmark {
+    pub id: i64,
+    pub name: String,
+}
+
+impl Bookmark {
+    /// Word's own bookmarks, like `_GoBack` and the `_Toc` targets of a table
+    /// of contents, start with an underscore and are not shown to the user.
+    pub fn is_hidden(&self) -> bool {
+        self.name.starts_with('_')
+    }
+}
+
+/// `w:type` of a `w:br`.
+#[derive(Default, Debug, Clone, Copy, PartialEq)]
+pub enum BreakType {
+    Page,
+    Column,
+    #[default]
+    TextWrapping,
+}
+
+impl std::fmt::Display for BreakType {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Page => write!(f, "page"),
+            Self::Column => write!(f, "column"),
+            Self::TextWrapping => write!(f, "textWrapping"),
+        }
+    }
+}
+
+impl FromStr for BreakType {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "page" => Ok(Self::Page),
+            "column" => Ok(Self::Column),
+            "textWrapping" => Ok(Self::TextWrapping),
+            _ => Err(anyhow::Error::msg(format!("Invalid break type: {:?}", s))),
+        }
+    }
 }
 
 #[derive(Default, Debug, Clone)]
 pub struct ParagraphProperties {
+    pub style: Option<String>,
     pub justify: Option<Justification>,
     pub text_properties: Option<TextProperties>,
     pub spacing: SpacingProperties,
+    pub indent: Option<Indentation>,
+    /// Custom tab stops sorted by position.
+    pub tabs: Vec<TabStop>,
+    pub numbering: Option<NumberingReference>,
+    /// `w:sectPr` of the last paragraph of a section other than the last.
+    pub section: Option<SectrOfProperties>,
+    pub page_break_before: bool,
+    /// Keep the last line on the page of the next paragraph.
+    pub keep_next: bool,
+    /// Keep all lines on one page.
+    pub keep_lines: bool,
+    /// Leave no single first or last line of the paragraph alone on a page.
+    pub widow_control: bool,
+    /// Bookmarks starting in the paragraph. They aren't part of `w:pPr` and
+    /// are saved around the runs.
+    pub bookmarks: Vec<Bookmark>,
+}
+
+/// Styles from `styles.xml` by `w:styleId`. Properties are kept as the raw
+/// `w:pPr`/`w:rPr`/`w:tblPr` elements and applied over the base style's when
+/// resolved, so only what a style sets overrides what it inherits.
+#[derive(Default, Debug, Clone)]
+pub struct StyleSheet {
+    pub default_paragraph_properties: Option<word_xml::Element>,
+    pub default_run_properties: Option<word_xml::Element>,
+    pub styles: HashMap<String, Style>,
+}
+
+#[derive(Debug, Clone)]
+pub struct Style {
+    pub id: String,
+    pub name: Option<String>,
+    pub style_type: StyleType,
+    pub based_on: Option<String>,
+    /// Paragraph style of a character style or the other way around.
+    pub link: Option<String>,
+    pub is_default: bool,
+    pub paragraph_properties: Option<word_xml::Element>,
+    pub run_properties: Option<word_xml::Element>,
+    pub table_properties: Option<word_xml::Element>,
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum StyleType {
+    #[default]
+    Paragraph,
+    Character,
+    Table,
+    Numbering,
+}
+
+impl std::fmt::Display for StyleType {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            StyleType::Paragraph => write!(f, "paragraph"),
+            StyleType::Character => write!(f, "character"),
+            StyleType::Table => write!(f, "table"),
+            StyleType::Numbering => write!(f, "numbering"),
+        }
+    }
+}
+
+impl FromStr for StyleType {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "paragraph" => Ok(StyleType::Paragraph),
+            "character" => Ok(StyleType::Character),
+            "table" => Ok(StyleType::Table),
+            "numbering" => Ok(StyleType::Numbering),
+            _ => Err(anyhow::Error::msg("invalid style type")),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct NumberingReference {
+    pub num_id: usize,
+    pub level: usize,
+}
+
+/// Definitions from `numbering.xml`: `abstract_nums` by `w:abstractNumId`
+/// and the `nums` paragraphs refer to by `w:numId`.
+#[derive(Default, Debug, Clone)]
+pub struct Numbering {
+    pub abstract_nums: HashMap<usize, AbstractNumbering>,
+    pub nums: HashMap<usize, NumberingInstance>,
+}
+
+#[derive(Default, Debug, Clone)]
+pub struct AbstractNumbering {
+    pub levels: Vec<NumberingLevel>,
+}
+
+#[derive(Debug, Clone)]
+pub struct NumberingInstance {
+    pub abstract_num_id: usize,
+    pub overrides: Vec<LevelOverride>,
+}
+
+#[derive(Debug, Clone)]
+pub struct LevelOverride {
+    pub level: usize,
+    pub start: Option<i64>,
+    pub definition: Option<NumberingLevel>,
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct NumberingLevel {
+    pub level: usize,
+    pub start: i64,
+    pub format: NumberFormat,
+    pub text: String,
+    /// One-based level whose use restarts this one; `Some(0)` never restarts.
+    pub restart: Option<usize>,
+    pub suffix: LevelSuffix,
+    pub indent: Indentation,
+    pub tab: Option<f32>,
+    pub font_name: Option<String>,
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct Indentation {
+    pub left: f32,
+    pub right: f32,
+    pub first_line: f32,
+    pub hanging: f32,
+}
+
+/// Tab stop at `position` from the start of the text column.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct TabStop {
+    pub position: f32,
+    pub alignment: TabAlignment,
+    pub leader: TabLeader,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum TabAlignment {
+    Clear,
+    Start,
+    Center,
+    End,
+    Decimal,
+    Bar,
+    Num,
+}
+
+impl std::fmt::Display for TabAlignment {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Clear => write!(f, "clear"),
+            Self::Start => write!(f, "start"),
+            Self::Center => write!(f, "center"),
+            Self::End => write!(f, "end"),
+            Self::Decimal => write!(f, "decimal"),
+            Self::Bar => write!(f, "bar"),
+            Self::Num => write!(f, "num"),
+        }
+    }
+}
+
+impl FromStr for TabAlignment {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "clear" => Ok(Self::Clear),
+            "start" | "left" => Ok(Self::Start),
+            "center" => Ok(Self::Center),
+            "end" | "right" => Ok(Self::End),
+            "decimal" => Ok(Self::Decimal),
+            "bar" => Ok(Self::Bar),
+            "num" => Ok(Self::Num),
+            _ => Err(anyhow::Error::msg(format!("Invalid tab stop: {:?}", s))),
+        }
+    }
+}
+
+#[derive(Default, Debug, Clone, Copy, PartialEq)]
+pub enum TabLeader {
+    #[default]
+    None,
+    Dot,
+    Hyphen,
+    Underscore,
+    Heavy,
+    MiddleDot,
+}
+
+impl std::fmt::Display for TabLeader {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::None => write!(f, "none"),
+            Self::Dot => write!(f, "dot"),
+            Self::Hyphen => write!(f, "hyphen"),
+            Self::Underscore => write!(f, "underscore"),
+            Self::Heavy => write!(f, "heavy"),
+            Self::MiddleDot => write!(f, "middleDot"),
+        }
+    }
+}
+
+impl FromStr for TabLeader {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "none" => Ok(Self::None),
+            "dot" => Ok(Self::Dot),
+            "hyphen" => Ok(Self::Hyphen),
+            "underscore" => Ok(Self::Underscore),
+            "heavy" => Ok(Self::Heavy),
+            "middleDot" => Ok(Self::MiddleDot),
+            _ => Err(anyhow::Error::msg(format!("Invalid tab leader: {:?}", s))),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Default, PartialEq)]
+pub enum NumberFormat {
+    #[default]
+    Decimal,
+    DecimalZero,
+    LowerLetter,
+    UpperLetter,
+    LowerRoman,
+    UpperRoman,
+    Bullet,
+    None,
+}
+
+impl FromStr for NumberFormat {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "decimal" => Ok(Self::Decimal),
+            "decimalZero" => Ok(Self::DecimalZero),
+            "lowerLetter" => Ok(Self::LowerLetter),
+            "upperLetter" => Ok(Self::UpperLetter),
+            "lowerRoman" => Ok(Self::LowerRoman),
+            "upperRoman" => Ok(Self::UpperRoman),
+            "bullet" => Ok(Self::Bullet),
+            "none" => Ok(Self::None),
+            _ => Err(anyhow::Error::msg(format!("Unsupported number format: {:?}", s))),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub enum LevelSuffix {
+    #[default]
+    Tab,
+    Space,
+    Nothing,
+}
+
+impl FromStr for LevelSuffix {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "tab" => Ok(Self::Tab),
+            "space" => Ok(Self::Space),
+            "nothing" => Ok(Self::Nothing),
+            _ => Err(anyhow::Error::msg("invalid level suffix")),
+        }
+    }
 }
 
 #[derive(Debug, Default, Clone)]
@@ -230,7 +767,7 @@ pub struct SpacingProperties {
     pub before: Option<f32>,
 }
 
-#[derive(Debug, Clone, Default)]
+#[derive(Debug, Clone, Default, PartialEq)]
 pub enum LineRule {
     #[default]
     Auto,
@@ -253,13 +790,27 @@ impl std::fmt::Display for LineRule {
     }
 }
 
-#[derive(Default, Debug, Clone)]
+#[derive(Default, Debug, Clone, PartialEq)]
 pub enum Justification {
     #[default]
     Start,
     End,
     Center,
     Width,
+    /// Lines stretched to both margins, except the last one.
+    Both,
+    /// Every line, the last one included, stretched to both margins with
+    /// room added between characters too.
+    Distribute,
+    Left,
+    Right,
+}
+
+impl Justification {
+    /// Whether inter-word spacing is stretched to fill the line.
+    pub fn is_justified(&self) -> bool {
+        matches!(self, Self::Width | Self::Both | Self::Distribute)
+    }
 }
 
 impl std::fmt::Display for Justification {
@@ -269,6 +820,10 @@ impl std::fmt::Display for Justification {
             Justification::End => write!(f, "end"),
             Justification::Center => write!(f, "center"),
             Justification::Width => write!(f, "width"),
+            Justification::Both => write!(f, "both"),
+            Justification::Distribute => write!(f, "distribute"),
+            Justification::Left => write!(f, "left"),
+            Justification::Right => write!(f, "right"),
         }
     }
 }
@@ -282,21 +837,524 @@ impl FromStr for Justification {
             "end" => Ok(Justification::End),
             "width" => Ok(Justification::Width),
             "center" => Ok(Justification::Center),
+            "both" | "lowKashida" | "mediumKashida" | "highKashida" => Ok(Justification::Both),
+            "distribute" | "thaiDistribute" => Ok(Justification::Distribute),
+            "left" => Ok(Justification::Left),
+            "right" => Ok(Justification::Right),
             _ => Err(anyhow::Error::msg("invalid justification")),
         }
     }
 }
 
+#[derive(Default, Debug, Clone)]
+pub struct TableProperties {
+    pub style: Option<String>,
+    pub width: Option<TableWidth>,
+    pub justify: Option<Justification>,
+    pub borders: TableBorders,
+    pub cell_margin: CellMargins,
+}
+
+#[derive(Default, Debug, Clone)]
+pub struct TableRow {
+    pub properties: RowProperties,
+    pub cells: Vec<TableCell>,
+}
+
+#[derive(Default, Debug, Clone)]
+pub struct RowProperties {
+    pub height: Option<f32>,
+    pub header: bool,
+    pub cant_split: bool,
+}
+
+#[derive(Default, Debug, Clone)]
+pub struct TableCell {
+    pub properties: CellProperties,
+    pub content: Vec<DocxNode>,
+}
+
+#[derive(Debug, Clone)]
+pub struct CellProperties {
+    pub width: Option<TableWidth>,
+    pub grid_span: usize,
+    pub v_merge: Option<VerticalMerge>,
+    pub borders: TableBorders,
+    pub margin: Option<CellMargins>,
+    pub shading: Option<Color>,
+}
+
+impl Default for CellProperties {
+    fn default() -> Self {
+        Self {
+            width: None,
+            grid_span: 1,
+            v_merge: None,
+            borders: TableBorders::default(),
+            margin: None,
+            shading: None,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum VerticalMerge {
+    Restart,
+    Continue,
+}
+
+impl std::fmt::Display for VerticalMerge {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            VerticalMerge::Restart => write!(f, "restart"),
+            VerticalMerge::Continue => write!(f, "continue"),
+        }
+    }
+}
+
+impl FromStr for VerticalMerge {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "restart" => Ok(VerticalMerge::Restart),
+            "continue" => Ok(VerticalMerge::Continue),
+            _ => Err(anyhow::Error::msg("invalid vertical merge")),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy)]
+pub struct TableWidth {
+    pub value: f32,
+    pub width_type: WidthType,
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub enum WidthType {
+    #[default]
+    Dxa,
+    Pct,
+    Auto,
+    Nil,
+}
+
+impl std::fmt::Display for WidthType {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            WidthType::Dxa => write!(f, "dxa"),
+            WidthType::Pct => write!(f, "pct"),
+            WidthType::Auto => write!(f, "auto"),
+            WidthType::Nil => write!(f, "nil"),
+        }
+    }
+}
+
+impl FromStr for WidthType {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "dxa" => Ok(WidthType::Dxa),
+            "pct" => Ok(WidthType::Pct),
+            "auto" => Ok(WidthType::Auto),
+            "nil" => Ok(WidthType::Nil),
+            _ => Err(anyhow::Error::msg("invalid width type")),
+        }
+    }
+}
+
+#[derive(Default, Debug, Clone)]
+pub struct TableBorders {
+    pub top: Option<Border>,
+    pub left: Option<Border>,
+    pub bottom: Option<Border>,
+    pub right: Option<Border>,
+    pub inside_h: Option<Border>,
+    pub inside_v: Option<Border>,
+}
+
+#[derive(Debug, Clone)]
+pub struct Border {
+    pub style: BorderStyle,
+    /// Eighths of a point.
+    pub size: u32,
+    pub color: Option<Color>,
+}
+
+impl Border {
+    pub fn is_visible(&self) -> bool {
+        !matches!(self.style, BorderStyle::Nil | BorderStyle::None)
+    }
+}
+
+#[derive(Debug, Clone, Default, PartialEq)]
+pub enum BorderStyle {
+    Nil,
+    None,
+    #[default]
+    Single,
+    Thick,
+    Double,
+    Dotted,
+    Dashed,
+}
+
+impl std::fmt::Display for BorderStyle {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            BorderStyle::Nil => write!(f, "nil"),
+            BorderStyle::None => write!(f, "none"),
+            BorderStyle::Single => write!(f, "single"),
+            BorderStyle::Thick => write!(f, "thick"),
+            BorderStyle::Double => write!(f, "double"),
+            BorderStyle::Dotted => write!(f, "dotted"),
+            BorderStyle::Dashed => write!(f, "dashed"),
+        }
+    }
+}
+
+impl FromStr for BorderStyle {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "nil" => Ok(BorderStyle::Nil),
+            "none" => Ok(BorderStyle::None),
+            "single" => Ok(BorderStyle::Single),
+            "thick" => Ok(BorderStyle::Thick),
+            "double" => Ok(BorderStyle::Double),
+            "dotted" => Ok(BorderStyle::Dotted),
+            "dashed" => Ok(BorderStyle::Dashed),
+            _ => Err(anyhow::Error::msg("invalid border style")),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy)]
+pub struct CellMargins {
+    pub top: f32,
+    pub left: f32,
+    pub bottom: f32,
+    pub right: f32,
+}
+
+impl Default for CellMargins {
+    /// Word's defaults: 0.08" on the sides, nothing above and below.
+    fn default() -> Self {
+        Self {
+            top: 0.,
+            left: 10.8,
+            bottom: 0.,
+            right: 10.8,
+        }
+    }
+}
+
 #[derive(Default, Debug, Clone, PartialEq)]
 pub struct TextProperties {
+    pub style: Option<String>,
     pub font_handle: FontHandle,
     pub font_name: Option<String>,
     pub size: Option<TextSize>,
     pub size_cs: Option<TextSize>,
     pub weight: TextWeight,
+    pub weight_cs: TextWeight,
     pub color: Option<Color>,
-    pub underline: bool,
+    pub underline: Option<UnderlineStyle>,
     pub italic: bool,
+    pub italic_cs: bool,
+    pub strike: bool,
+    pub double_strike: bool,
+    pub vertical_align: Option<VerticalAlign>,
+    pub caps: bool,
+    pub small_caps: bool,
+    pub highlight: Option<HighlightColor>,
+    pub shading: Option<Color>,
+    /// Extra space after every character.
+    pub spacing: Option<f32>,
+    /// Horizontal scale of the glyphs in percent.
+    pub stretch: Option<f32>,
+    /// Raises the text above the baseline, or lowers it when negative.
+    pub position: Option<f32>,
+    pub fonts: RunFonts,
+    pub lang: Language,
+    pub vanish: bool,
+}
+
+/// Fonts of the `w:rFonts` slots other than ascii, which is `font_name`.
+#[derive(Default, Debug, Clone, PartialEq)]
+pub struct RunFonts {
+    pub h_ansi: Option<String>,
+    pub east_asia: Option<String>,
+    pub complex_script: Option<String>,
+    pub ascii_theme: Option<ThemeFont>,
+    pub h_ansi_theme: Option<ThemeFont>,
+    pub east_asia_theme: Option<ThemeFont>,
+    pub complex_script_theme: Option<ThemeFont>,
+}
+
+#[derive(Default, Debug, Clone, PartialEq)]
+pub struct Language {
+    pub latin: Option<String>,
+    pub east_asia: Option<String>,
+    pub bidi: Option<String>,
+}
+
+/// Typefaces of the theme's major (headings) and minor (body) fonts.
+#[derive(Default, Debug, Clone)]
+pub struct ThemeFonts {
+    pub major: ThemeFontSet,
+    pub minor: ThemeFontSet,
+}
+
+#[derive(Default, Debug, Clone)]
+pub struct ThemeFontSet {
+    pub latin: Option<String>,
+    pub east_asia: Option<String>,
+    pub complex_script: Option<String>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ThemeFont {
+    MajorAscii,
+    MajorHAnsi,
+    MajorEastAsia,
+    MajorBidi,
+    MinorAscii,
+    MinorHAnsi,
+    MinorEastAsia,
+    MinorBidi,
+}
+
+impl std::fmt::Display for ThemeFont {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::MajorAscii => write!(f, "majorAscii"),
+            Self::MajorHAnsi => write!(f, "majorHAnsi"),
+            Self::MajorEastAsia => write!(f, "majorEastAsia"),
+            Self::MajorBidi => write!(f, "majorBidi"),
+            Self::MinorAscii => write!(f, "minorAscii"),
+            Self::MinorHAnsi => write!(f, "minorHAnsi"),
+            Self::MinorEastAsia => write!(f, "minorEastAsia"),
+            Self::MinorBidi => write!(f, "minorBidi"),
+        }
+    }
+}
+
+impl FromStr for ThemeFont {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "majorAscii" => Ok(Self::MajorAscii),
+            "majorHAnsi" => Ok(Self::MajorHAnsi),
+            "majorEastAsia" => Ok(Self::MajorEastAsia),
+            "majorBidi" => Ok(Self::MajorBidi),
+            "minorAscii" => Ok(Self::MinorAscii),
+            "minorHAnsi" => Ok(Self::MinorHAnsi),
+            "minorEastAsia" => Ok(Self::MinorEastAsia),
+            "minorBidi" => Ok(Self::MinorBidi),
+            _ => Err(anyhow::Error::msg(format!("Invalid theme font: {:?}", s))),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum UnderlineStyle {
+    Single,
+    Words,
+    Double,
+    Thick,
+    Dotted,
+    DottedHeavy,
+    Dash,
+    DashedHeavy,
+    DashLong,
+    DashLongHeavy,
+    DotDash,
+    DashDotHeavy,
+    DotDotDash,
+    DashDotDotHeavy,
+    Wave,
+    WavyHeavy,
+    WavyDouble,
+}
+
+impl std::fmt::Display for UnderlineStyle {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Single => write!(f, "single"),
+            Self::Words => write!(f, "words"),
+            Self::Double => write!(f, "double"),
+            Self::Thick => write!(f, "thick"),
+            Self::Dotted => write!(f, "dotted"),
+            Self::DottedHeavy => write!(f, "dottedHeavy"),
+            Self::Dash => write!(f, "dash"),
+            Self::DashedHeavy => write!(f, "dashedHeavy"),
+            Self::DashLong => write!(f, "dashLong"),
+            Self::DashLongHeavy => write!(f, "dashLongHeavy"),
+            Self::DotDash => write!(f, "dotDash"),
+            Self::DashDotHeavy => write!(f, "dashDotHeavy"),
+            Self::DotDotDash => write!(f, "dotDotDash"),
+            Self::DashDotDotHeavy => write!(f, "dashDotDotHeavy"),
+            Self::Wave => write!(f, "wave"),
+            Self::WavyHeavy => write!(f, "wavyHeavy"),
+            Self::WavyDouble => write!(f, "wavyDouble"),
+        }
+    }
+}
+
+impl FromStr for UnderlineStyle {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "single" => Ok(Self::Single),
+            "words" => Ok(Self::Words),
+            "double" => Ok(Self::Double),
+            "thick" => Ok(Self::Thick),
+            "dotted" => Ok(Self::Dotted),
+            "dottedHeavy" => Ok(Self::DottedHeavy),
+            "dash" => Ok(Self::Dash),
+            "dashedHeavy" => Ok(Self::DashedHeavy),
+            "dashLong" => Ok(Self::DashLong),
+            "dashLongHeavy" => Ok(Self::DashLongHeavy),
+            "dotDash" => Ok(Self::DotDash),
+            "dashDotHeavy" => Ok(Self::DashDotHeavy),
+            "dotDotDash" => Ok(Self::DotDotDash),
+            "dashDotDotHeavy" => Ok(Self::DashDotDotHeavy),
+            "wave" => Ok(Self::Wave),
+            "wavyHeavy" => Ok(Self::WavyHeavy),
+            "wavyDouble" => Ok(Self::WavyDouble),
+            _ => Err(anyhow::Error::msg(format!("Invalid underline: {:?}", s))),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum VerticalAlign {
+    Baseline,
+    Superscript,
+    Subscript,
+}
+
+impl std::fmt::Display for VerticalAlign {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Baseline => write!(f, "baseline"),
+            Self::Superscript => write!(f, "superscript"),
+            Self::Subscript => write!(f, "subscript"),
+        }
+    }
+}
+
+impl FromStr for VerticalAlign {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "baseline" => Ok(Self::Baseline),
+            "superscript" => Ok(Self::Superscript),
+            "subscript" => Ok(Self::Subscript),
+            _ => Err(anyhow::Error::msg(format!(
+                "Invalid vertical align: {:?}",
+                s
+            ))),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum HighlightColor {
+    Black,
+    Blue,
+    Cyan,
+    Green,
+    Magenta,
+    Red,
+    Yellow,
+    White,
+    DarkBlue,
+    DarkCyan,
+    DarkGreen,
+    DarkMagenta,
+    DarkRed,
+    DarkYellow,
+    DarkGray,
+    LightGray,
+}
+
+impl std::fmt::Display for HighlightColor {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Black => write!(f, "black"),
+            Self::Blue => write!(f, "blue"),
+            Self::Cyan => write!(f, "cyan"),
+            Self::Green => write!(f, "green"),
+            Self::Magenta => write!(f, "magenta"),
+            Self::Red => write!(f, "red"),
+            Self::Yellow => write!(f, "yellow"),
+            Self::White => write!(f, "white"),
+            Self::DarkBlue => write!(f, "darkBlue"),
+            Self::DarkCyan => write!(f, "darkCyan"),
+            Self::DarkGreen => write!(f, "darkGreen"),
+            Self::DarkMagenta => write!(f, "darkMagenta"),
+            Self::DarkRed => write!(f, "darkRed"),
+            Self::DarkYellow => write!(f, "darkYellow"),
+            Self::DarkGray => write!(f, "darkGray"),
+            Self::LightGray => write!(f, "lightGray"),
+        }
+    }
+}
+
+impl FromStr for HighlightColor {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "black" => Ok(Self::Black),
+            "blue" => Ok(Self::Blue),
+            "cyan" => Ok(Self::Cyan),
+            "green" => Ok(Self::Green),
+            "magenta" => Ok(Self::Magenta),
+            "red" => Ok(Self::Red),
+            "yellow" => Ok(Self::Yellow),
+            "white" => Ok(Self::White),
+            "darkBlue" => Ok(Self::DarkBlue),
+            "darkCyan" => Ok(Self::DarkCyan),
+            "darkGreen" => Ok(Self::DarkGreen),
+            "darkMagenta" => Ok(Self::DarkMagenta),
+            "darkRed" => Ok(Self::DarkRed),
+            "darkYellow" => Ok(Self::DarkYellow),
+            "darkGray" => Ok(Self::DarkGray),
+            "lightGray" => Ok(Self::LightGray),
+            _ => Err(anyhow::Error::msg(format!("Invalid highlight: {:?}", s))),
+        }
+    }
+}
+
+impl From<HighlightColor> for Color {
+    fn from(highlight: HighlightColor) -> Self {
+        Color::from(match highlight {
+            HighlightColor::Black => 0x000000FF,
+            HighlightColor::Blue => 0x0000FFFF,
+            HighlightColor::Cyan => 0x00FFFFFF,
+            HighlightColor::Green => 0x00FF00FF,
+            HighlightColor::Magenta => 0xFF00FFFF,
+            HighlightColor::Red => 0xFF0000FF,
+            HighlightColor::Yellow => 0xFFFF00FF,
+            HighlightColor::White => 0xFFFFFFFF,
+            HighlightColor::DarkBlue => 0x000080FF,
+            HighlightColor::DarkCyan => 0x008080FF,
+            HighlightColor::DarkGreen => 0x008000FF,
+            HighlightColor::DarkMagenta => 0x800080FF,
+            HighlightColor::DarkRed => 0x800000FF,
+            HighlightColor::DarkYellow => 0x808000FF,
+            HighlightColor::DarkGray => 0x808080FF,
+            HighlightColor::LightGray => 0xC0C0C0FF,
+        })
+    }
 }
 
 #[derive(Debug, Clone, Copy, PartialEq)]
@@ -336,7 +1394,7 @@ impl Color {
     }
 
     #[inline]
-    pub fn rgb(r: f32, g: f32, b: f32) -> Self {
+    pub const fn rgb(r: f32, g: f32, b: f32) -> Self {
         Self { r, g, b, a: 1.0 }
     }
 
@@ -407,13 +1465,81 @@ impl From<i32> for TextSize {
     }
 }
 
+impl TextNode {
+    pub const TAB: &'static str = "\t";
+
+    pub fn is_tab(&self) -> bool {
+        self.content == Self::TAB && self.image.is_none() && self.note.is_none()
+    }
+}
+
 impl DocxNode {
     pub fn is_paragraph(&self) -> bool {
         matches!(self, DocxNode::Paragrapth { .. })
     }
 }
 
+/// Scripts with their own fonts in `w:rFonts`.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Script {
+    Ascii,
+    HighAnsi,
+    EastAsia,
+    ComplexScript,
+}
+
+impl Script {
+    pub fn of(ch: char) -> Self {
+        match ch as u32 {
+            0x0000..=0x007F => Self::Ascii,
+            0x0590..=0x08FF
+            | 0x0900..=0x0DFF
+            | 0x0E00..=0x0EFF
+            | 0xFB1D..=0xFDFF
+            | 0xFE70..=0xFEFF => Self::ComplexScript,
+            0x1100..=0x11FF
+            | 0x2E80..=0x9FFF
+            | 0xA960..=0xA97F
+            | 0xAC00..=0xD7FF
+            | 0xF900..=0xFAFF
+            | 0xFF00..=0xFFEF
+            | 0x20000..=0x3FFFF => Self::EastAsia,
+            _ => Self::HighAnsi,
+        }
+    }
+
+    /// Script of the first letter of `content`.
+    pub fn of_text(content: &str) -> Self {
+        content
+            .chars()
+            .find(|ch| ch.is_alphabetic())
+            .map(Self::of)
+            .unwrap_or(Self::Ascii)
+    }
+}
+
 impl TextProperties {
+    /// Properties `content` is drawn with: east asian and complex scripts
+    /// have their own fonts, and the latter their own size and toggles.
+    pub fn for_script(&self, content: &str) -> TextProperties {
+        let mut properties = self.clone();
+        let font = match Script::of_text(content) {
+            Script::Ascii => None,
+            Script::HighAnsi => self.fonts.h_ansi.clone(),
+            Script::EastAsia => self.fonts.east_asia.clone(),
+            Script::ComplexScript => {
+                properties.size = self.size_cs.clone().or(properties.size);
+                properties.weight = self.weight_cs.clone();
+                properties.italic = self.italic_cs;
+                self.fonts.complex_script.clone()
+            }
+        };
+        if font.is_some() {
+            properties.font_name = font;
+        }
+        properties
+    }
+
     pub fn get_font_idx(&self) -> (String, String) {
         let mode = match (self.weight.clone(), self.italic) {
             (TextWeight::Bold, false) => String::from("Bold"),
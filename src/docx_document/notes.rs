@@ -0,0 +1,38 @@
+use super::{DocxDocument, NoteKind, NumberFormat};
+
+impl NoteKind {
+    /// Footnotes are numbered 1, 2, 3 and endnotes i, ii, iii, the defaults
+    /// of `w:footnotePr`/`w:endnotePr`.
+    pub fn format(&self, number: usize) -> String {
+        let format = match self {
+            Self::Footnote => NumberFormat::Decimal,
+            Self::Endnote => NumberFormat::LowerRoman,
+        };
+        format.format(number as i64)
+    }
+
+    pub fn reference_name(&self) -> &'static str {
+        match self {
+            Self::Footnote => "w:footnoteReference",
+            Self::Endnote => "w:endnoteReference",
+        }
+    }
+
+    pub fn mark_name(&self) -> &'static str {
+        match self {
+            Self::Footnote => "w:footnoteRef",
+            Self::Endnote => "w:endnoteRef",
+        }
+    }
+}
+
+impl DocxDocument {
+    /// Number of note `id`, counting the references of its kind from one.
+    pub fn note_number(&self, kind: NoteKind, id: i64) -> Option<usize> {
+        self.note_references
+            .iter()
+            .filter(|(reference_kind, _)| *reference_kind == kind)
+            .position(|(_, reference_id)| *reference_id == id)
+            .map(|position| position + 1)
+    }
+}
@@ -0,0 +1,192 @@
+use std::collections::{HashMap, HashSet};
+
+use super::{NumberFormat, Numbering, NumberingLevel, NumberingReference};
+
+/// Label of one list paragraph and the level it was made from.
+#[derive(Debug, Clone)]
+pub struct ListLabel {
+    pub text: String,
+    pub level: NumberingLevel,
+}
+
+/// List counters, advanced paragraph by paragraph in document order.
+///
+/// Counters belong to the abstract numbering, so every `w:num` sharing it
+/// continues the same list unless it restarts it with `w:startOverride`.
+#[derive(Debug, Default)]
+pub struct NumberingCounters {
+    counters: HashMap<usize, Vec<Option<i64>>>,
+    started: HashSet<usize>,
+}
+
+impl Numbering {
+    /// Level `level` of `num_id`, with `w:lvlOverride` applied.
+    pub fn get_level(&self, num_id: usize, level: usize) -> Option<&NumberingLevel> {
+        let num = self.nums.get(&num_id)?;
+        num.overrides
+            .iter()
+            .filter(|level_override| level_override.level == level)
+            .find_map(|level_override| level_override.definition.as_ref())
+            .or_else(|| {
+                self.abstract_nums
+                    .get(&num.abstract_num_id)?
+                    .levels
+                    .iter()
+                    .find(|definition| definition.level == level)
+            })
+    }
+
+    fn get_start(&self, num_id: usize, level: usize) -> i64 {
+        let start_override = self.nums.get(&num_id).and_then(|num| {
+            num.overrides
+                .iter()
+                .filter(|level_override| level_override.level == level)
+                .find_map(|level_override| level_override.start)
+        });
+
+        start_override
+            .or_else(|| Some(self.get_level(num_id, level)?.start))
+            .unwrap_or(1)
+    }
+}
+
+impl NumberingCounters {
+    pub fn next_label(
+        &mut self,
+        numbering: &Numbering,
+        reference: NumberingReference,
+    ) -> Option<ListLabel> {
+        let NumberingReference { num_id, level } = reference;
+        let num = numbering.nums.get(&num_id)?;
+        let definition = numbering.get_level(num_id, level)?.clone();
+
+        let counters = self.counters.entry(num.abstract_num_id).or_default();
+        let len = num
+            .overrides
+            .iter()
+            .map(|level_override| level_override.level + 1)
+            .fold(level + 1, usize::max);
+        if counters.len() < len {
+            counters.resize(len, None);
+        }
+
+        if self.started.insert(num_id) {
+            for level_override in num.overrides.iter().filter(|o| o.start.is_some()) {
+                counters[level_override.level] = None;
+            }
+        }
+
+        counters[level] = Some(match counters[level] {
+            Some(value) => value + 1,
+            None => numbering.get_start(num_id, level),
+        });
+
+        for (deeper, counter) in counters.iter_mut().enumerate().skip(level + 1) {
+            let restart = numbering
+                .get_level(num_id, deeper)
+                .and_then(|definition| definition.restart);
+            if restart.is_none_or(|restart| restart > level) {
+                *counter = None;
+            }
+        }
+
+        let text = if definition.format == NumberFormat::Bullet {
+            bullet_text(&definition.text)
+        } else {
+            let mut text = String::new();
+            let mut chars = definition.text.chars().peekable();
+            while let Some(c) = chars.next() {
+                let Some(idx) = chars
+                    .peek()
+                    .and_then(|digit| digit.to_digit(10))
+                    .filter(|digit| c == '%' && *digit != 0)
+                else {
+                    text.push(c);
+                    continue;
+                };
+                chars.next();
+
+                let idx = idx as usize - 1;
+                let value = counters
+                    .get(idx)
+                    .copied()
+                    .flatten()
+                    .unwrap_or_else(|| numbering.get_start(num_id, idx));
+                let format = numbering
+                    .get_level(num_id, idx)
+                    .map(|definition| definition.format.clone())
+                    .unwrap_or_default();
+                text.push_str(&format.format(value));
+            }
+            text
+        };
+
+        Some(ListLabel {
+            text,
+            level: definition,
+        })
+    }
+}
+
+impl NumberFormat {
+    pub fn format(&self, value: i64) -> String {
+        match self {
+            Self::Bullet | Self::None => String::new(),
+            Self::Decimal => value.to_string(),
+            Self::DecimalZero => format!("{:02}", value),
+            _ if value <= 0 => value.to_string(),
+            Self::LowerLetter => letters(value),
+            Self::UpperLetter => letters(value).to_uppercase(),
+            Self::LowerRoman => roman(value).to_lowercase(),
+            Self::UpperRoman => roman(value),
+        }
+    }
+}
+
+/// `a`..`z`, then `aa`, `bb` and so on, like Word does.
+fn letters(value: i64) -> String {
+    let letter = (b'a' + ((value - 1) % 26) as u8) as char;
+    letter.to_string().repeat((value - 1) as usize / 26 + 1)
+}
+
+fn roman(mut value: i64) -> String {
+    const NUMERALS: [(i64, &str); 13] = [
+        (1000, "M"),
+        (900, "CM"),
+        (500, "D"),
+        (400, "CD"),
+        (100, "C"),
+        (90, "XC"),
+        (50, "L"),
+        (40, "XL"),
+        (10, "X"),
+        (9, "IX"),
+        (5, "V"),
+        (4, "IV"),
+        (1, "I"),
+    ];
+
+    let mut result = String::new();
+    for (number, numeral) in NUMERALS {
+        while value >= number {
+            result.push_str(numeral);
+            value -= number;
+        }
+    }
+    result
+}
+
+/// Symbol and Wingdings bullets are stored as private use characters that
+/// regular fonts don't have, so map the common ones to Unicode.
+fn bullet_text(text: &str) -> String {
+    text.chars()
+        .map(|c| match c as u32 {
+            0xF0A7 => '▪',
+            0xF0D8 => '➢',
+            0xF076 => '❖',
+            0xF0FC => '✓',
+            0xF000..=0xF0FF => '•',
+            _ => c,
+        })
+        .collect()
+}
@@ -0,0 +1,39 @@
+use super::{Relationship, Relationships};
+
+impl Relationships {
+    /// Relationships whose type ends with `rel_type`, like `image` or `header`.
+    pub fn get_of_type<'a>(
+        &'a self,
+        rel_type: &'a str,
+    ) -> impl Iterator<Item = (&'a String, &'a Relationship)> {
+        self.relationships.iter().filter(move |(_, relationship)| {
+            relationship.rel_type.rsplit('/').next() == Some(rel_type)
+        })
+    }
+
+    /// Zip path of the part `id` points to, `None` for external targets.
+    pub fn get_part(&self, id: &str) -> Option<String> {
+        let relationship = self
+            .relationships
+            .get(id)
+            .filter(|relationship| !relationship.external)?;
+
+        // Targets are relative to `word/`, where `document.xml` is.
+        let mut parts = if relationship.target.starts_with('/') {
+            Vec::new()
+        } else {
+            vec!["word"]
+        };
+        for segment in relationship.target.split('/') {
+            match segment {
+                "" | "." => {}
+                ".." => {
+                    parts.pop();
+                }
+                segment => parts.push(segment),
+            }
+        }
+
+        Some(parts.join("/"))
+    }
+}
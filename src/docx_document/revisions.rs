@@ -0,0 +1,57 @@
+use super::{Revision, RevisionKind};
+
+impl RevisionKind {
+    pub fn element_name(&self) -> &'static str {
+        match self {
+            Self::Insertion => "w:ins",
+            Self::Deletion => "w:del",
+        }
+    }
+}
+
+impl Revision {
+    pub fn from_element(element: &word_xml::Element) -> Option<Self> {
+        let kind = match element.name.as_str() {
+            "w:ins" => RevisionKind::Insertion,
+            "w:del" => RevisionKind::Deletion,
+            _ => return None,
+        };
+        Some(Self {
+            kind,
+            id: element.get_attr_parsed::<i64>("w:id"),
+            author: element.get_attr_parsed::<String>("w:author"),
+            date: element.get_attr_parsed::<String>("w:date"),
+        })
+    }
+
+    /// `w:ins`/`w:del` to save the runs of the revision in.
+    pub fn to_element(&self) -> word_xml::Element {
+        let mut element = word_xml::Element::new(self.kind.element_name());
+        if let Some(id) = self.id {
+            element.append_attr("w:id", id);
+        }
+        if let Some(author) = &self.author {
+            element.append_attr("w:author", author);
+        }
+        if let Some(date) = &self.date {
+            element.append_attr("w:date", date);
+        }
+        element
+    }
+
+    /// Short description for the status line, like `insertion by Jane`.
+    pub fn describe(&self) -> String {
+        let kind = match self.kind {
+            RevisionKind::Insertion => "insertion",
+            RevisionKind::Deletion => "deletion",
+        };
+        let mut description = kind.to_string();
+        if let Some(author) = &self.author {
+            description += &format!(" by {}", author);
+        }
+        if let Some(date) = &self.date {
+            description += &format!(" on {}", date);
+        }
+        description
+    }
+}
@@ -0,0 +1,170 @@
+use super::{
+    from_word_xml::{apply_paragraph_properties, apply_run_properties, parse_table_properties},
+    ParagraphProperties, Style, StyleSheet, StyleType, TableProperties, TextProperties,
+};
+
+impl StyleSheet {
+    /// Style `style_id` of type `style_type`, or the default style of that
+    /// type when there is no id. An id naming the linked style of the other
+    /// type resolves through `w:link`.
+    pub fn get_style(&self, style_id: Option<&str>, style_type: StyleType) -> Option<&Style> {
+        let Some(style_id) = style_id else {
+            return self
+                .styles
+                .values()
+                .find(|style| style.style_type == style_type && style.is_default);
+        };
+
+        let style = self.styles.get(style_id)?;
+        if style.style_type == style_type {
+            return Some(style);
+        }
+        style
+            .link
+            .as_deref()
+            .and_then(|link| self.styles.get(link))
+            .filter(|linked| linked.style_type == style_type)
+    }
+
+    /// `style` and its `w:basedOn` ancestors, outermost first.
+    pub fn get_chain<'a>(&'a self, style: &'a Style) -> Vec<&'a Style> {
+        let mut chain = vec![style];
+        while let Some(based_on) = chain
+            .last()
+            .and_then(|style| style.based_on.as_deref())
+            .and_then(|based_on| self.styles.get(based_on))
+        {
+            if chain.iter().any(|style| style.id == based_on.id) {
+                break;
+            }
+            chain.push(based_on);
+        }
+        chain.reverse();
+        chain
+    }
+
+    fn get_resolved(&self, style_id: Option<&str>, style_type: StyleType) -> Vec<&Style> {
+        self.get_style(style_id, style_type)
+            .map(|style| self.get_chain(style))
+            .unwrap_or_default()
+    }
+
+    /// Paragraph properties of the document defaults with paragraph style
+    /// `style_id` applied. Run properties of the paragraph mark are left out,
+    /// see [`Self::text_properties`].
+    pub fn paragraph_properties(&self, style_id: Option<&str>) -> ParagraphProperties {
+        let mut properties = ParagraphProperties::default();
+
+        let defaults = self.default_paragraph_properties.iter();
+        let styles = self
+            .get_resolved(style_id, StyleType::Paragraph)
+            .into_iter()
+            .filter_map(|style| style.paragraph_properties.as_ref());
+        for ppr in defaults.chain(styles) {
+            apply_paragraph_properties(ppr, &mut properties);
+        }
+
+        properties
+    }
+
+    /// Run properties of the document defaults, then of paragraph style
+    /// `paragraph_style` and then of character style `run_style`.
+    pub fn text_properties(
+        &self,
+        paragraph_style: Option<&str>,
+        run_style: Option<&str>,
+    ) -> TextProperties {
+        let mut properties = TextProperties::default();
+
+        let defaults = self.default_run_properties.iter();
+        let paragraph = self.get_resolved(paragraph_style, StyleType::Paragraph);
+        let character = match run_style {
+            Some(run_style) => self.get_resolved(Some(run_style), StyleType::Character),
+            None => Vec::new(),
+        };
+        let styles = paragraph
+            .into_iter()
+            .chain(character)
+            .filter_map(|style| style.run_properties.as_ref());
+        for rpr in defaults.chain(styles) {
+            apply_run_properties(rpr, &mut properties);
+        }
+
+        properties
+    }
+
+    pub fn table_properties(&self, style_id: Option<&str>) -> TableProperties {
+        self.get_resolved(style_id, StyleType::Table)
+            .into_iter()
+            .filter_map(|style| style.table_properties.as_ref())
+            .fold(TableProperties::default(), |properties, tbl_pr| {
+                parse_table_properties(tbl_pr, properties)
+            })
+    }
+}
+
+#[cfg(test)]
+pub(crate) mod tests {
+    use super::*;
+    use crate::docx_document::{Justification, TextSize, TextWeight};
+
+    pub const STYLES: &str = r#"<w:styles
+        xmlns:w="http://schemas.openxmlformats.org/wordprocessingml/2006/main">
+        <w:docDefaults>
+            <w:rPrDefault><w:rPr><w:sz w:val="22"/></w:rPr></w:rPrDefault>
+            <w:pPrDefault><w:pPr><w:spacing w:after="160"/></w:pPr></w:pPrDefault>
+        </w:docDefaults>
+        <w:style w:type="paragraph" w:default="1" w:styleId="Normal">
+            <w:pPr><w:jc w:val="both"/></w:pPr>
+        </w:style>
+        <w:style w:type="paragraph" w:styleId="Heading1">
+            <w:basedOn w:val="Normal"/>
+            <w:link w:val="Heading1Char"/>
+            <w:pPr><w:keepNext/><w:jc w:val="center"/></w:pPr>
+            <w:rPr><w:b/><w:sz w:val="32"/></w:rPr>
+        </w:style>
+        <w:style w:type="character" w:styleId="Heading1Char">
+            <w:link w:val="Heading1"/>
+            <w:rPr><w:i/></w:rPr>
+        </w:style>
+    </w:styles>"#;
+
+    pub fn styles() -> StyleSheet {
+        let styles = STYLES.parse::<word_xml::WordXMLDocument>().unwrap();
+        StyleSheet::try_from(&styles).unwrap()
+    }
+
+    #[test]
+    fn paragraph_style_applies_over_its_base_and_the_defaults() {
+        let styles = styles();
+
+        let normal = styles.paragraph_properties(None);
+        assert!(matches!(normal.justify, Some(Justification::Both)));
+        assert_eq!(normal.spacing.after, Some(16.));
+        assert!(!normal.keep_next);
+
+        let heading = styles.paragraph_properties(Some("Heading1"));
+        assert!(matches!(heading.justify, Some(Justification::Center)));
+        assert_eq!(heading.spacing.after, Some(16.));
+        assert!(heading.keep_next);
+    }
+
+    #[test]
+    fn run_properties_resolve_through_paragraph_and_linked_styles() {
+        let styles = styles();
+
+        let normal = styles.text_properties(None, None);
+        assert_eq!(normal.size, Some(TextSize::from(22)));
+        assert_eq!(normal.weight, TextWeight::Regular);
+
+        let heading = styles.text_properties(Some("Heading1"), None);
+        assert_eq!(heading.size, Some(TextSize::from(32)));
+        assert_eq!(heading.weight, TextWeight::Bold);
+        assert!(!heading.italic);
+
+        // A paragraph style used as a character style is its linked one.
+        let linked = styles.text_properties(None, Some("Heading1"));
+        assert_eq!(linked.size, Some(TextSize::from(22)));
+        assert!(linked.italic);
+    }
+}
@@ -0,0 +1,35 @@
+use super::{TextProperties, ThemeFont, ThemeFonts};
+
+impl ThemeFonts {
+    pub fn get(&self, font: ThemeFont) -> Option<&str> {
+        let typeface = match font {
+            ThemeFont::MajorAscii | ThemeFont::MajorHAnsi => &self.major.latin,
+            ThemeFont::MajorEastAsia => &self.major.east_asia,
+            ThemeFont::MajorBidi => &self.major.complex_script,
+            ThemeFont::MinorAscii | ThemeFont::MinorHAnsi => &self.minor.latin,
+            ThemeFont::MinorEastAsia => &self.minor.east_asia,
+            ThemeFont::MinorBidi => &self.minor.complex_script,
+        };
+        typeface.as_deref()
+    }
+}
+
+impl TextProperties {
+    /// Replace the fonts of the slots that refer to the theme with its
+    /// typefaces, which take precedence over the explicit names.
+    pub fn resolve_theme_fonts(&mut self, theme: &ThemeFonts) {
+        let resolve = |font: Option<ThemeFont>, name: &mut Option<String>| {
+            if let Some(typeface) = font.and_then(|font| theme.get(font)) {
+                *name = Some(typeface.to_string());
+            }
+        };
+
+        resolve(self.fonts.ascii_theme, &mut self.font_name);
+        resolve(self.fonts.h_ansi_theme, &mut self.fonts.h_ansi);
+        resolve(self.fonts.east_asia_theme, &mut self.fonts.east_asia);
+        resolve(
+            self.fonts.complex_script_theme,
+            &mut self.fonts.complex_script,
+        );
+    }
+}
@@ -12,6 +12,8 @@ pub struct DrawState<'window> {
     pub queue: wgpu::Queue,
     pub fill_pipeline: FillPipeline,
     pub text_pipeline: TextPipeline,
+    /// Same bindings as `text_pipeline`, sampling RGBA pictures.
+    pub image_pipeline: TextPipeline,
 }
 
 pub struct FillPipeline {
@@ -95,7 +97,12 @@ impl App<'_> {
             }
 
             log::info!("\nDRAW UI\n");
-            draw_state.draw_ui(&mut self.ui_primitives, &state_copy, &mut rpass);
+            draw_state.draw_ui(
+                &mut self.ui_primitives,
+                &state_copy,
+                self.document_draw.as_deref(),
+                &mut rpass,
+            );
             log::info!("\n##END DRAW STATE##\n");
         }
 
@@ -72,6 +72,7 @@ impl<'window> DrawState<'window> {
 
         let fill_pipeline = get_fill_pipeline(&device, &config);
         let text_pipeline = get_text_pipeline(&device, &config);
+        let image_pipeline = get_image_pipeline(&device, &config);
 
         surface.configure(&device, &config);
 
@@ -83,14 +84,29 @@ impl<'window> DrawState<'window> {
             queue,
             fill_pipeline,
             text_pipeline,
+            image_pipeline,
         }
     }
 }
 
 fn get_text_pipeline(device: &wgpu::Device, config: &wgpu::SurfaceConfiguration) -> TextPipeline {
-    let fill_shader =
-        device.create_shader_module(wgpu::include_wgsl!("../shaders/text_shader.wgsl"));
+    let shader = device.create_shader_module(wgpu::include_wgsl!("../shaders/text_shader.wgsl"));
+    get_texture_pipeline(device, config, shader, "Text")
+}
+
+fn get_image_pipeline(device: &wgpu::Device, config: &wgpu::SurfaceConfiguration) -> TextPipeline {
+    let shader = device.create_shader_module(wgpu::include_wgsl!("../shaders/image_shader.wgsl"));
+    get_texture_pipeline(device, config, shader, "Image")
+}
 
+/// Pipeline drawing a textured rect, with a uniform, a texture and a sampler
+/// bound at 0, 1 and 2.
+fn get_texture_pipeline(
+    device: &wgpu::Device,
+    config: &wgpu::SurfaceConfiguration,
+    fill_shader: wgpu::ShaderModule,
+    name: &str,
+) -> TextPipeline {
     let bind_group_layout = device.create_bind_group_layout(&wgpu::BindGroupLayoutDescriptor {
         label: None,
         entries: &[
@@ -123,14 +139,16 @@ fn get_text_pipeline(device: &wgpu::Device, config: &wgpu::SurfaceConfiguration)
         ],
     });
 
+    let layout_label = format!("{} pipeline Layout", name);
     let text_pipeline_layout = device.create_pipeline_layout(&wgpu::PipelineLayoutDescriptor {
-        label: Some("Text pipeline Layout"),
+        label: Some(&layout_label),
         bind_group_layouts: &[&bind_group_layout],
         push_constant_ranges: &[],
     });
 
+    let pipeline_label = format!("{} pipeline", name);
     let pipeline = device.create_render_pipeline(&wgpu::RenderPipelineDescriptor {
-        label: Some("Text pipeline"),
+        label: Some(&pipeline_label),
         layout: Some(&text_pipeline_layout),
         vertex: wgpu::VertexState {
             module: &fill_shader,
@@ -12,6 +12,9 @@ use winit::{
 };
 
 use crate::{
+    docx_document::{
+        DocxDocument, Image, NoteKind, Numbering, Relationships, Settings, StyleSheet, ThemeFonts,
+    },
     document_draw::DocumentCommand,
     log_helper::LogHelper,
     state::{self, Mode, State},
@@ -44,6 +47,10 @@ impl App<'_> {
                     return Ok(());
                 }
 
+                if self.document_navigation(&event)? {
+                    return Ok(());
+                }
+
                 if let PhysicalKey::Code(KeyCode::KeyI) = event.physical_key {
                     let mut state = self.state.lock().to_anyhow()?;
                     state.mode = Mode::Edit;
@@ -146,6 +153,20 @@ impl App<'_> {
         }
     }
 
+    /// Enter follows the link under the cursor and `b` goes back from it,
+    /// `]` and `[` go to the next and the previous tracked change.
+    fn document_navigation(&mut self, event: &winit::event::KeyEvent) -> anyhow::Result<bool> {
+        let command = match event.physical_key {
+            PhysicalKey::Code(KeyCode::Enter) => DocumentCommand::FollowLink,
+            PhysicalKey::Code(KeyCode::KeyB) => DocumentCommand::JumpBack,
+            PhysicalKey::Code(KeyCode::BracketRight) => DocumentCommand::ChangeRevision(1),
+            PhysicalKey::Code(KeyCode::BracketLeft) => DocumentCommand::ChangeRevision(-1),
+            _ => return Ok(false),
+        };
+        self.document_commands.lock().to_anyhow()?.push(command);
+        Ok(true)
+    }
+
     fn process_command_enter(
         &mut self,
         event: &winit::event::KeyEvent,
@@ -175,6 +196,41 @@ impl App<'_> {
                         Arc::clone(&self.draw_state.as_ref().context("no draw state")?.window),
                     ));
                 }
+                "bookmarks" => {
+                    let state = self.state.lock().to_anyhow()?;
+                    let command = match state.get_console_command_arg(1) {
+                        Some(name) => DocumentCommand::GoToBookmark(name.to_string()),
+                        None => DocumentCommand::ListBookmarks,
+                    };
+                    self.document_commands.lock().to_anyhow()?.push(command);
+                }
+                "accept" | "reject" => {
+                    let state = self.state.lock().to_anyhow()?;
+                    self.document_commands.lock().to_anyhow()?.push(
+                        DocumentCommand::ResolveRevisions {
+                            accept: command_name == "accept",
+                            all: state.get_console_command_arg(1) == Some("all"),
+                        },
+                    );
+                }
+                "comment" => {
+                    let mut state = self.state.lock().to_anyhow()?;
+                    let action = state.get_console_command_arg(1).unwrap_or_default().to_string();
+                    let text = state.command_in_process.get(2..).unwrap_or_default().join(" ");
+                    let command = match action.as_str() {
+                        "add" => DocumentCommand::AddComment { text, reply: false },
+                        "reply" => DocumentCommand::AddComment { text, reply: true },
+                        "edit" => DocumentCommand::EditComment(text),
+                        "resolve" => DocumentCommand::ResolveComment,
+                        "delete" => DocumentCommand::DeleteComment,
+                        _ => {
+                            state.status_message =
+                                "comment add|reply|edit|resolve|delete [text]".into();
+                            return Ok(false);
+                        }
+                    };
+                    self.document_commands.lock().to_anyhow()?.push(command);
+                }
                 "save" => {
                     std::thread::spawn(save_document(
                         Arc::clone(&self.document_commands),
@@ -285,6 +341,17 @@ fn load_file_and_write_to_state(state: Arc<Mutex<State>>, window: Arc<Window>) -
 
             println!("{}", document.document);
 
+            if let Some(first) = document.warnings.first() {
+                guard.status_message = format!(
+                    "{} warning(s) while reading, first at {}:{}:{}: {}",
+                    document.warnings.len(),
+                    first.part.as_deref().unwrap_or_default(),
+                    first.line,
+                    first.column,
+                    first.message
+                );
+            }
+
             guard.document = Some(document);
             window.request_redraw();
 
@@ -297,34 +364,157 @@ fn load_file_and_write_to_state(state: Arc<Mutex<State>>, window: Arc<Window>) -
 pub fn read_document_from_file(file: PathBuf) -> anyhow::Result<state::Document> {
     let archive = std::fs::read(file.clone()).context("Can't read archive")?;
 
-    let document = get_element(&archive, "word/document.xml")?;
-    let fonts = get_element(&archive, "word/fontTable.xml")?;
+    let mut warnings = Vec::new();
+    let fonts = get_element(&archive, "word/fontTable.xml", &mut warnings)?;
+    let styles = if has_part(&archive, "word/styles.xml") {
+        let styles = get_element(&archive, "word/styles.xml", &mut warnings)?;
+        StyleSheet::try_from(&styles).context("failed to parse styles")?
+    } else {
+        StyleSheet::default()
+    };
+    let theme_fonts = if has_part(&archive, "word/theme/theme1.xml") {
+        let theme = get_element(&archive, "word/theme/theme1.xml", &mut warnings)?;
+        ThemeFonts::try_from(&theme).context("failed to parse theme")?
+    } else {
+        ThemeFonts::default()
+    };
+
+    let (document_xml, encoding, part_warnings) =
+        word_xml::encoding::decode_lossy(&read_part(&archive, "word/document.xml")?);
+    let mut reader = word_xml::XmlReader::new(&document_xml).lenient();
+    let mut document = DocxDocument::from_reader(&mut reader, &fonts, styles, theme_fonts)
+        .context("failed to parse docx documnet")?;
+    warnings.extend(
+        part_warnings
+            .into_iter()
+            .chain(reader.take_warnings())
+            .map(|warning| warning.with_part("word/document.xml")),
+    );
+
+    if has_part(&archive, "word/numbering.xml") {
+        let numbering = get_element(&archive, "word/numbering.xml", &mut warnings)?;
+        document.numbering =
+            Numbering::try_from(&numbering).context("failed to parse numbering")?;
+    }
+
+    if has_part(&archive, "word/_rels/document.xml.rels") {
+        let relationships = get_element(&archive, "word/_rels/document.xml.rels", &mut warnings)?;
+        document.relationships =
+            Relationships::try_from(&relationships).context("failed to parse relationships")?;
+    }
+
+    let image_parts = document
+        .relationships
+        .get_of_type("image")
+        .filter_map(|(id, _)| Some((id.clone(), document.relationships.get_part(id)?)))
+        .collect::<Vec<_>>();
+    for (id, part) in image_parts {
+        match read_part(&archive, &part).and_then(|bytes| Image::decode(&bytes)) {
+            Ok(image) => {
+                document.images.insert(id, Arc::new(image));
+            }
+            Err(err) => log::warn!("failed to load image {}: {:#}", part, err),
+        }
+    }
+
+    if has_part(&archive, "word/settings.xml") {
+        let settings = get_element(&archive, "word/settings.xml", &mut warnings)?;
+        document.settings = Settings::try_from(&settings).context("failed to parse settings")?;
+    }
+
+    let header_footer_parts = document
+        .relationships
+        .get_of_type("header")
+        .chain(document.relationships.get_of_type("footer"))
+        .filter_map(|(id, _)| Some((id.clone(), document.relationships.get_part(id)?)))
+        .collect::<Vec<_>>();
+    for (id, part) in header_footer_parts {
+        let element = get_element(&archive, &part, &mut warnings)?;
+        let content = document
+            .parse_header_footer(&element)
+            .context(format!("failed to parse {}", part))?;
+        document.headers_footers.insert(id, content);
+    }
+
+    for (rel_type, kind) in [
+        ("footnotes", NoteKind::Footnote),
+        ("endnotes", NoteKind::Endnote),
+    ] {
+        let Some(part) = document
+            .relationships
+            .get_of_type(rel_type)
+            .find_map(|(id, _)| document.relationships.get_part(id))
+        else {
+            continue;
+        };
+        let element = get_element(&archive, &part, &mut warnings)?;
+        document
+            .parse_notes(&element, kind)
+            .context(format!("failed to parse {}", part))?;
+    }
+
+    let comment_parts = ["comments", "commentsExtended"].map(|rel_type| {
+        document
+            .relationships
+            .get_of_type(rel_type)
+            .find_map(|(id, _)| document.relationships.get_part(id))
+    });
+    if let [Some(part), extended] = comment_parts {
+        let element = get_element(&archive, &part, &mut warnings)?;
+        document
+            .parse_comments(&element)
+            .context(format!("failed to parse {}", part))?;
+        if let Some(part) = extended {
+            let element = get_element(&archive, &part, &mut warnings)?;
+            document.parse_comments_extended(&element);
+        }
+    }
+
+    for warning in &warnings {
+        log::warn!("{}", warning);
+    }
 
     Ok(state::Document {
-        document: Arc::new(Box::new(
-            (&document, &fonts)
-                .try_into()
-                .context("failed to parse docx documnet")?,
-        )),
+        document: Arc::new(Box::new(document)),
         zip_document: archive,
         path: file,
+        warnings,
+        encoding,
     })
 }
 
-fn get_element(archive: &Vec<u8>, file: &str) -> anyhow::Result<word_xml::WordXMLDocument> {
+fn has_part(archive: &Vec<u8>, file: &str) -> bool {
+    zip::ZipArchive::new(std::io::Cursor::new(archive))
+        .is_ok_and(|archive| archive.index_for_name(file).is_some())
+}
+
+fn read_part(archive: &Vec<u8>, file: &str) -> anyhow::Result<Vec<u8>> {
     let archive = std::io::Cursor::new(archive);
 
-    let mut document = String::new();
+    let mut document = Vec::new();
     zip::ZipArchive::new(archive)
         .context("Failed to parse archive")?
         .by_name(file)
         .context(format!("Failed to get {} file", file))?
-        .read_to_string(&mut document)
-        .context("Failed to read to string.")?;
+        .read_to_end(&mut document)
+        .context("Failed to read file.")?;
+
+    Ok(document)
+}
+
+fn get_element(
+    archive: &Vec<u8>,
+    file: &str,
+    warnings: &mut Vec<word_xml::ParseWarning>,
+) -> anyhow::Result<word_xml::WordXMLDocument> {
+    let document = read_part(archive, file)?;
+
+    let (element, part_warnings) =
+        word_xml::WordXMLDocument::parse_part_lenient_bytes(file, &document)
+            .context(format!("Failed to parse {} file", file))?;
+    warnings.extend(part_warnings);
 
-    document
-        .parse()
-        .context("Failed to parse document.xml file")
+    Ok(element)
 }
 
 fn save_document(commands: DocumentCommands, window: Arc<Window>) -> impl FnOnce() {
@@ -1,8 +1,15 @@
+use std::sync::Arc;
+
 use glam::u32;
 use rusttype::PositionedGlyph;
 use wgpu::util::DeviceExt;
 
-use crate::{docx_document::Color, draw::DrawState, math, uniforms::Uniforms2d};
+use crate::{
+    docx_document::{Color, Image},
+    draw::DrawState,
+    math,
+    uniforms::Uniforms2d,
+};
 
 #[derive(Default)]
 pub struct Primitive {
@@ -17,6 +24,7 @@ pub enum PrimitiveProperties {
         color: Color,
     },
     PlainText(PlainTextProperties),
+    Image(ImageProperties),
 
     #[default]
     Empty,
@@ -29,6 +37,16 @@ pub struct PlainTextProperties {
     pub font: rusttype::Font<'static>,
     pub color: Color,
     pub scale: f32,
+    /// Extra space after every glyph.
+    pub letter_spacing: f32,
+    /// Horizontal scale of the glyphs.
+    pub stretch: f32,
+}
+
+#[derive(Clone)]
+pub struct ImageProperties {
+    pub rect: math::Rectangle,
+    pub image: Arc<Image>,
 }
 
 #[derive(Default)]
@@ -46,6 +64,12 @@ pub enum PrimitiveWgpu {
         bindgroup: wgpu::BindGroup,
         glyphs: Vec<PositionedGlyph<'static>>,
     },
+    Image {
+        uniform: Uniforms2d,
+        buffer: wgpu::Buffer,
+        texture: wgpu::Texture,
+        bindgroup: wgpu::BindGroup,
+    },
     #[default]
     Empty,
 }
@@ -55,6 +79,7 @@ impl DrawState<'_> {
         match prop.into() {
             PrimitiveProperties::Rect { rect, color } => self.new_rect(rect, color),
             PrimitiveProperties::PlainText(prop) => self.new_plain_text(prop),
+            PrimitiveProperties::Image(prop) => self.new_image(prop),
             _ => Default::default(),
         }
     }
@@ -68,6 +93,7 @@ impl DrawState<'_> {
         match prop.into() {
             PrimitiveProperties::Rect { rect, color } => self.update_rect(rect, color, primitive),
             PrimitiveProperties::PlainText(prop) => self.update_plain_text(prop, primitive),
+            PrimitiveProperties::Image(prop) => self.update_image(prop, primitive),
             _ => {}
         }
     }
@@ -108,6 +134,21 @@ impl DrawState<'_> {
 
                 rpass.pop_debug_group();
             }
+            PrimitiveWgpu::Image { bindgroup, .. } => {
+                log::info!(
+                    "( draw image )\n{:?}",
+                    primitive.get_rect().get_point_and_size()
+                );
+
+                rpass.push_debug_group("Draw Image Primitive");
+
+                rpass.set_pipeline(&self.image_pipeline.pipeline);
+                rpass.set_bind_group(0, bindgroup, &[]);
+                rpass.set_vertex_buffer(0, self.image_pipeline.vertex_buffer.slice(..));
+                rpass.draw(0..6, 0..1);
+
+                rpass.pop_debug_group();
+            }
             _ => {}
         }
     }
@@ -130,16 +171,28 @@ impl DrawState<'_> {
         }
 
         let v_m = prop.font.v_metrics(rusttype::Scale::uniform(prop.scale));
+        let scale = rusttype::Scale {
+            x: prop.scale * prop.stretch,
+            y: prop.scale,
+        };
         let glyphs = prop
             .font
             .layout(
                 prop.content.as_str(),
-                rusttype::Scale::uniform(prop.scale),
+                scale,
                 rusttype::Point {
                     x: 0f32,
                     y: v_m.ascent,
                 },
             )
+            .enumerate()
+            .map(|(idx, glyph)| {
+                let position = glyph.position();
+                glyph.into_unpositioned().positioned(rusttype::Point {
+                    x: position.x + idx as f32 * prop.letter_spacing,
+                    y: position.y,
+                })
+            })
             .collect::<Vec<_>>();
 
         let size = get_glyphs_size(&glyphs, v_m);
@@ -254,7 +307,11 @@ impl DrawState<'_> {
                 },
         } = primitive
         {
-            if prop.content == new_prop.content && prop.scale == new_prop.scale {
+            if prop.content == new_prop.content
+                && prop.scale == new_prop.scale
+                && prop.letter_spacing == new_prop.letter_spacing
+                && prop.stretch == new_prop.stretch
+            {
                 let uniform_value = self.calc_rect_uniform(
                     math::Rectangle::new(
                         new_prop.left_top,
@@ -273,6 +330,112 @@ impl DrawState<'_> {
         }
     }
 
+    fn new_image(&self, prop: ImageProperties) -> Primitive {
+        let ImageProperties { rect, image } = &prop;
+        if image.width == 0 || image.height == 0 {
+            return Primitive::default();
+        }
+
+        let uniform = self.calc_rect_uniform(*rect, Color::rgb(1., 1., 1.));
+
+        let extent = wgpu::Extent3d {
+            width: image.width,
+            height: image.height,
+            depth_or_array_layers: 1,
+        };
+
+        let texture = self.device.create_texture(&wgpu::TextureDescriptor {
+            label: None,
+            size: extent,
+            mip_level_count: 1,
+            sample_count: 1,
+            dimension: wgpu::TextureDimension::D2,
+            format: wgpu::TextureFormat::Rgba8UnormSrgb,
+            usage: wgpu::TextureUsages::TEXTURE_BINDING | wgpu::TextureUsages::COPY_DST,
+            view_formats: &[],
+        });
+
+        self.queue.write_texture(
+            texture.as_image_copy(),
+            &image.pixels,
+            wgpu::ImageDataLayout {
+                offset: 0,
+                bytes_per_row: Some(4 * extent.width),
+                rows_per_image: None,
+            },
+            extent,
+        );
+
+        let buffer = self
+            .device
+            .create_buffer_init(&wgpu::util::BufferInitDescriptor {
+                label: None,
+                contents: bytemuck::cast_slice(&[uniform]),
+                usage: wgpu::BufferUsages::UNIFORM | wgpu::BufferUsages::COPY_DST,
+            });
+
+        let texture_view = texture.create_view(&wgpu::TextureViewDescriptor::default());
+        let sampler = self.device.create_sampler(&wgpu::SamplerDescriptor {
+            label: None,
+            address_mode_u: wgpu::AddressMode::ClampToEdge,
+            address_mode_v: wgpu::AddressMode::ClampToEdge,
+            address_mode_w: wgpu::AddressMode::ClampToEdge,
+            mag_filter: wgpu::FilterMode::Linear,
+            min_filter: wgpu::FilterMode::Linear,
+            ..Default::default()
+        });
+
+        let bindgroup = self.device.create_bind_group(&wgpu::BindGroupDescriptor {
+            layout: &self.image_pipeline.bind_group_layout,
+            entries: &[
+                wgpu::BindGroupEntry {
+                    binding: 0,
+                    resource: buffer.as_entire_binding(),
+                },
+                wgpu::BindGroupEntry {
+                    binding: 1,
+                    resource: wgpu::BindingResource::TextureView(&texture_view),
+                },
+                wgpu::BindGroupEntry {
+                    binding: 2,
+                    resource: wgpu::BindingResource::Sampler(&sampler),
+                },
+            ],
+            label: None,
+        });
+
+        Primitive {
+            prop: PrimitiveProperties::Image(prop),
+            wgpu: PrimitiveWgpu::Image {
+                uniform,
+                buffer,
+                texture,
+                bindgroup,
+            },
+        }
+    }
+
+    fn update_image(&self, new_prop: ImageProperties, primitive: &mut Primitive) {
+        if let Primitive {
+            prop: PrimitiveProperties::Image(prop),
+            wgpu: PrimitiveWgpu::Image {
+                uniform, buffer, ..
+            },
+        } = primitive
+        {
+            if Arc::ptr_eq(&prop.image, &new_prop.image) {
+                let uniform_value = self.calc_rect_uniform(new_prop.rect, Color::rgb(1., 1., 1.));
+                *prop = new_prop;
+
+                *uniform = uniform_value;
+                self.queue
+                    .write_buffer(buffer, 0, bytemuck::cast_slice(&[uniform_value]));
+            } else {
+                *primitive = self.new_image(new_prop);
+            }
+        }
+    }
+
     fn new_rect(&self, rect: math::Rectangle, color: Color) -> Primitive {
         let uniform = self.calc_rect_uniform(rect, color);
         let buffer = self
@@ -396,6 +559,12 @@ impl From<PlainTextProperties> for PrimitiveProperties {
     }
 }
 
+impl From<ImageProperties> for PrimitiveProperties {
+    fn from(prop: ImageProperties) -> Self {
+        Self::Image(prop)
+    }
+}
+
 impl Primitive {
     pub fn is_empty(&self) -> bool {
         matches!(
@@ -427,6 +596,10 @@ impl Primitive {
                 prop: PrimitiveProperties::PlainText(PlainTextProperties { left_top, .. }),
                 wgpu: PrimitiveWgpu::Text { extent, .. },
             } => math::Rectangle::new(*left_top, (extent.width as f32, extent.height as f32)),
+            Primitive {
+                prop: PrimitiveProperties::Image(ImageProperties { rect, .. }),
+                ..
+            } => *rect,
             _ => Default::default(),
         }
     }
@@ -461,6 +634,8 @@ impl PlainTextProperties {
             content,
             font,
             scale,
+            letter_spacing: 0.,
+            stretch: 1.,
         }
     }
 }
@@ -478,6 +653,9 @@ impl std::fmt::Debug for Primitive {
                 scale,
                 ..
             }) => write!(f, "TEXT ( {left_top:?}, {content:?}, {color:?}, {scale:?})"),
+            PrimitiveProperties::Image(ImageProperties { rect, image }) => {
+                write!(f, "IMAGE ({:?}, {:?})", rect, image)
+            }
             PrimitiveProperties::Empty => write!(f, "Text"),
         }
     }
@@ -490,19 +668,16 @@ impl PrimitiveProperties {
                 rect: rect.add_y(delta),
                 color,
             },
-            PrimitiveProperties::PlainText(PlainTextProperties {
-                left_top,
-                content,
-                font,
-                color,
-                scale,
-            }) => Self::PlainText(PlainTextProperties {
-                left_top: (left_top.x, left_top.y + delta).into(),
-                content,
-                font,
-                color,
-                scale,
+            PrimitiveProperties::PlainText(prop) => Self::PlainText(PlainTextProperties {
+                left_top: (prop.left_top.x, prop.left_top.y + delta).into(),
+                ..prop
             }),
+            PrimitiveProperties::Image(ImageProperties { rect, image }) => {
+                Self::Image(ImageProperties {
+                    rect: rect.add_y(delta),
+                    image,
+                })
+            }
             PrimitiveProperties::Empty => PrimitiveProperties::Empty,
         }
     }
@@ -513,19 +688,18 @@ impl PrimitiveProperties {
                 rect: rect.with_size(rect.size() * ratio),
                 color,
             },
-            PrimitiveProperties::PlainText(PlainTextProperties {
-                left_top,
-                content,
-                font,
-                color,
-                scale,
-            }) => Self::PlainText(PlainTextProperties {
-                left_top: (left_top.x, left_top.y + ratio).into(),
-                content,
-                font,
-                color,
-                scale: scale * ratio,
+            PrimitiveProperties::PlainText(prop) => Self::PlainText(PlainTextProperties {
+                left_top: (prop.left_top.x, prop.left_top.y + ratio).into(),
+                scale: prop.scale * ratio,
+                letter_spacing: prop.letter_spacing * ratio,
+                ..prop
             }),
+            PrimitiveProperties::Image(ImageProperties { rect, image }) => {
+                Self::Image(ImageProperties {
+                    rect: rect.with_size(rect.size() * ratio),
+                    image,
+                })
+            }
             PrimitiveProperties::Empty => PrimitiveProperties::Empty,
         }
     }
@@ -536,7 +710,8 @@ impl Primitive {
         match self.prop {
             PrimitiveProperties::Rect { rect, .. } => rect.height(),
             PrimitiveProperties::PlainText(PlainTextProperties { scale, .. }) => scale,
-            PrimitiveProperties::Empty => 0.,
+            // Pictures don't set the spacing to the next word.
+            PrimitiveProperties::Image(_) | PrimitiveProperties::Empty => 0.,
         }
     }
 }
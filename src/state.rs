@@ -9,6 +9,7 @@ use crate::{colorscheme::ColorScheme, docx_document::DocxDocument};
 pub struct State {
     pub mode: Mode,
     pub console_input: String,
+    pub status_message: String,
     pub command_in_process: Vec<String>,
     pub colorscheme: ColorScheme,
     pub document: Option<Document>,
@@ -19,6 +20,8 @@ pub struct Document {
     pub document: Arc<Box<DocxDocument>>,
     pub zip_document: Vec<u8>,
     pub path: PathBuf,
+    pub warnings: Vec<word_xml::ParseWarning>,
+    pub encoding: word_xml::Encoding,
 }
 
 #[derive(Clone, Copy, Default)]
@@ -41,6 +44,7 @@ impl State {
             .map(ToString::to_string)
             .collect::<Vec<_>>();
         self.console_input = String::new();
+        self.status_message = String::new();
         self.mode = Mode::Normal;
         log::info!("command in process: {:?}", self.command_in_process);
     }
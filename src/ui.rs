@@ -1,7 +1,9 @@
 use crate::{
+    colorscheme::ColorScheme,
+    document_draw::DocumentDraw,
     draw::DrawState,
     math,
-    primitives::{PlainTextProperties, Primitive},
+    primitives::{PlainTextProperties, Primitive, PrimitiveProperties},
     state::State,
 };
 
@@ -11,6 +13,9 @@ pub struct UiState {
     pub mode_text: Primitive,
     pub hello_text: Primitive,
     pub console_input: Primitive,
+    pub status_message: Primitive,
+    /// Cards and text of the comments panel.
+    pub comments: Vec<Primitive>,
     pub ui_font: rusttype::Font<'static>,
 }
 
@@ -21,6 +26,8 @@ impl Default for UiState {
                 .unwrap(),
             statusline_rect: Default::default(),
             console_input: Default::default(),
+            status_message: Default::default(),
+            comments: Default::default(),
             mode_rect: Default::default(),
             mode_text: Default::default(),
             hello_text: Default::default(),
@@ -29,16 +36,32 @@ impl Default for UiState {
 }
 
 impl DrawState<'_> {
+    const COMMENTS_PANEL_WIDTH: f32 = 280.;
+    const COMMENT_LINE_HEIGHT: f32 = 16.;
+    const COMMENT_PADDING: f32 = 6.;
+    const COMMENT_REPLY_INDENT: f32 = 20.;
+
     pub fn draw_ui<'a, 'b: 'a>(
         &'b self,
         ui_primitives: &'a mut UiState,
         state: &State,
+        document: Option<&DocumentDraw>,
         rpass: &mut wgpu::RenderPass<'a>,
     ) {
         let colorscheme = state.colorscheme.clone();
 
         let (w_width, w_height) = (self.config.width as f32, self.config.height as f32);
 
+        if let Some(document) = document {
+            self.draw_comments(
+                document,
+                &colorscheme,
+                &mut ui_primitives.comments,
+                ui_primitives.ui_font.clone(),
+                rpass,
+            );
+        }
+
         let status_line_rect = math::Rectangle::from((0., w_height - 40., w_width, w_height));
         self.draw_and_update(
             rpass,
@@ -67,6 +90,115 @@ impl DrawState<'_> {
             ),
             &mut ui_primitives.console_input,
         );
+
+        self.draw_status_message(
+            status_line_rect,
+            colorscheme.statusline_fg_color,
+            state.status_message.clone(),
+            &mut ui_primitives.status_message,
+            ui_primitives.ui_font.clone(),
+            rpass,
+        );
+    }
+
+    fn draw_status_message<'a, 'b: 'a>(
+        &'b self,
+        status_line_rect: math::Rectangle,
+        color: crate::docx_document::Color,
+        message: String,
+        status_message: &'a mut Primitive,
+        ui_font: rusttype::Font<'static>,
+        rpass: &mut wgpu::RenderPass<'a>,
+    ) {
+        let rect = status_line_rect.add_paddings(7.);
+        self.update_prim(
+            PlainTextProperties::new(rect, color, message.clone(), ui_font.clone()),
+            status_message,
+        );
+
+        let width = status_message.get_rect().width();
+        self.draw_and_update(
+            rpass,
+            PlainTextProperties::new(
+                rect.move_left_top((rect.width() - width - 3., 0.)),
+                color,
+                message,
+                ui_font,
+            ),
+            status_message,
+        );
+    }
+
+    /// Cards of the comments at the right of the window, each next to the
+    /// text it is on and its replies under it.
+    fn draw_comments<'a, 'b: 'a>(
+        &'b self,
+        document: &DocumentDraw,
+        colorscheme: &ColorScheme,
+        comments: &'a mut Vec<Primitive>,
+        ui_font: rusttype::Font<'static>,
+        rpass: &mut wgpu::RenderPass<'a>,
+    ) {
+        let (w_width, w_height) = (self.config.width as f32, self.config.height as f32);
+        let anchors = document.get_comment_anchors();
+        let is_anchored = |id| anchors.iter().any(|(comment, _)| comment.id == id);
+
+        // Comments answering none that is shown start threads.
+        let mut cards = Vec::new();
+        for (comment, top) in &anchors {
+            if comment.parent.is_some_and(is_anchored) {
+                continue;
+            }
+            cards.push((*comment, *top, 0.));
+            for (reply, _) in &anchors {
+                if reply.parent == Some(comment.id) {
+                    cards.push((*reply, *top, Self::COMMENT_REPLY_INDENT));
+                }
+            }
+        }
+
+        let mut props = Vec::<PrimitiveProperties>::new();
+        let mut bottom = 0f32;
+        for (comment, anchor, indent) in cards {
+            let (line, padding) = (Self::COMMENT_LINE_HEIGHT, Self::COMMENT_PADDING);
+            let left = w_width - Self::COMMENTS_PANEL_WIDTH + indent;
+            let width = Self::COMMENTS_PANEL_WIDTH - indent - padding;
+            let top = anchor.max(bottom + padding);
+            if top > w_height {
+                break;
+            }
+
+            let author = comment.author.as_deref().unwrap_or_default();
+            let mut header = format!("{} {}", comment.get_initials(), author);
+            if let Some(date) = comment.date.as_deref().and_then(|date| date.get(..10)) {
+                header += &format!(" {}", date);
+            }
+            if comment.done {
+                header += " resolved";
+            }
+            let lines = wrap_text(&ui_font, &comment.text(), width - 2. * padding, line);
+            let height = (lines.len() + 1) as f32 * line + 2. * padding;
+
+            let (bg_color, fg_color) = if comment.done {
+                (colorscheme.page_bg_color, colorscheme.statusline_fg_color)
+            } else {
+                (colorscheme.statusline_bg_color, colorscheme.statusline_fg_color)
+            };
+            props.push((math::Rectangle::new((left, top), (width, height)), bg_color).into());
+            for (idx, text) in [header].into_iter().chain(lines).enumerate() {
+                let rect = math::Rectangle::new(
+                    (left + padding, top + padding + idx as f32 * line),
+                    (width - 2. * padding, line),
+                );
+                props.push(PlainTextProperties::new(rect, fg_color, text, ui_font.clone()).into());
+            }
+            bottom = top + height;
+        }
+
+        comments.resize_with(props.len(), Default::default);
+        for (prop, primitive) in props.into_iter().zip(comments.iter_mut()) {
+            self.draw_and_update(rpass, prop, primitive);
+        }
     }
 
     #[allow(clippy::too_many_arguments)]
@@ -102,3 +234,36 @@ impl DrawState<'_> {
         rect
     }
 }
+
+/// Lines of `text` no wider than `width` in `font` at `height`, broken at
+/// spaces.
+fn wrap_text(font: &rusttype::Font<'static>, text: &str, width: f32, height: f32) -> Vec<String> {
+    let v_m = font.v_metrics(rusttype::Scale::uniform(1.));
+    let scale = rusttype::Scale::uniform(height / (v_m.ascent - v_m.descent));
+    let measure = |line: &str| {
+        font.layout(line, scale, rusttype::point(0., 0.))
+            .last()
+            .map_or(0., |glyph| {
+                glyph.position().x + glyph.unpositioned().h_metrics().advance_width
+            })
+    };
+
+    let mut lines = Vec::new();
+    for paragraph in text.lines() {
+        let mut line = String::new();
+        for word in paragraph.split_whitespace() {
+            let candidate = if line.is_empty() {
+                word.to_string()
+            } else {
+                format!("{} {}", line, word)
+            };
+            if measure(&candidate) > width && !line.is_empty() {
+                lines.push(std::mem::replace(&mut line, word.to_string()));
+            } else {
+                line = candidate;
+            }
+        }
+        lines.push(line);
+    }
+    lines
+}
@@ -0,0 +1,78 @@
+use word_xml::{encoding, Encoding, WordXMLDocument, WriteOptions};
+
+fn utf16le(text: &str, bom: bool) -> Vec<u8> {
+    let bom = if bom { vec![0xFF, 0xFE] } else { vec![] };
+    bom.into_iter()
+        .chain(text.encode_utf16().flat_map(u16::to_le_bytes))
+        .collect()
+}
+
+fn main() -> anyhow::Result<()> {
+    let xml = "<?xml version=\"1.0\" encoding=\"UTF-16\"?>\n<w:t>Привет ü</w:t>";
+
+    for bom in [true, false] {
+        let bytes = utf16le(xml, bom);
+        let document = WordXMLDocument::from_bytes(&bytes)?;
+        assert_eq!(document.root.get_texts(), "Привет ü");
+        assert_eq!(document.encoding.charset, encoding_rs::UTF_16LE);
+        assert_eq!(document.encoding.bom, bom);
+        assert_eq!(document.to_bytes(&WriteOptions::default())?, bytes);
+    }
+
+    let be = xml
+        .encode_utf16()
+        .flat_map(u16::to_be_bytes)
+        .collect::<Vec<_>>();
+    let document = WordXMLDocument::from_bytes(&be)?;
+    assert_eq!(document.encoding.charset, encoding_rs::UTF_16BE);
+    assert_eq!(document.to_bytes(&WriteOptions::default())?, be);
+
+    let utf8_bom = b"\xEF\xBB\xBF<?xml version=\"1.0\"?>\n<w:t>a</w:t>";
+    let document = WordXMLDocument::from_bytes(utf8_bom)?;
+    assert_eq!(document.header, "<?xml version=\"1.0\"?>");
+    assert!(document.encoding.bom);
+    assert_eq!(document.to_bytes(&WriteOptions::default())?, utf8_bom);
+
+    let with_bom = std::str::from_utf8(utf8_bom)?.parse::<WordXMLDocument>()?;
+    assert_eq!(with_bom.header, "<?xml version=\"1.0\"?>");
+
+    let latin1 = b"<?xml version='1.0' encoding='ISO-8859-1'?>\n<w:t>caf\xE9</w:t>";
+    let mut document = WordXMLDocument::from_bytes(latin1)?;
+    assert_eq!(document.root.get_texts(), "café");
+    assert_eq!(document.to_bytes(&WriteOptions::default())?, latin1);
+
+    document.set_encoding(Encoding::default());
+    assert_eq!(document.header, "<?xml version='1.0' encoding='UTF-8'?>");
+
+    let mut document = "<?xml version=\"1.0\"?><w:t/>".parse::<WordXMLDocument>()?;
+    document.set_encoding(Encoding {
+        charset: encoding_rs::UTF_16LE,
+        bom: true,
+    });
+    assert_eq!(
+        document.header,
+        "<?xml version=\"1.0\" encoding=\"UTF-16\"?>"
+    );
+    assert_eq!(
+        WordXMLDocument::from_bytes(&document.to_bytes(&WriteOptions::default())?)?.root,
+        document.root
+    );
+
+    assert!(WordXMLDocument::from_bytes(b"<?xml version=\"1.0\"?><w:t>\xFF</w:t>").is_err());
+    assert!(
+        WordXMLDocument::from_bytes(b"<?xml version=\"1.0\" encoding=\"bogus\"?><a/>").is_err()
+    );
+
+    let (document, warnings) =
+        WordXMLDocument::parse_lenient_bytes(b"<?xml version=\"1.0\"?><w:t>\xFF</w:t>")?;
+    assert_eq!(document.root.get_texts(), "\u{FFFD}");
+    assert_eq!(warnings.len(), 1);
+
+    assert_eq!(
+        encoding::declared_encoding(b"<?xml version=\"1.0\" encoding = \"windows-1251\" ?>"),
+        Some("windows-1251")
+    );
+
+    println!("encoding: ok");
+    Ok(())
+}
@@ -0,0 +1,29 @@
+fn main() -> anyhow::Result<()> {
+    let data =
+        "<?xml version=\"1.0\"?>\n<w:document>\n  <w:body><w:p></w:r></w:body>\n</w:document>";
+    let err = word_xml::WordXMLDocument::parse_part("word/document.xml", data).unwrap_err();
+
+    println!("{}", err);
+    assert_eq!(err.part.as_deref(), Some("word/document.xml"));
+    assert_eq!((err.line, err.column), (3, 16));
+    assert!(err.message.contains("</w:p>"));
+    assert_eq!(err.offset, data.find("</w:r>").unwrap());
+    assert_eq!(err.snippet, "  <w:body><w:p></w:r></w:body>");
+
+    let data = "<?xml version=\"1.0\"?>\n<w:t w:val=\"&bogus;\"/>";
+    let err = data.parse::<word_xml::WordXMLDocument>().unwrap_err();
+
+    println!("{}", err);
+    assert_eq!(err.part, None);
+    assert_eq!((err.line, err.column), (2, 13));
+    assert!(err.message.contains("&bogus;"));
+
+    let long_line = format!("<?xml version=\"1.0\"?>\n<w:p>{}<</w:p>", "x".repeat(500));
+    let err = long_line.parse::<word_xml::WordXMLDocument>().unwrap_err();
+
+    println!("{}", err);
+    assert_eq!(err.line, 2);
+    assert!(err.snippet.len() <= 81);
+
+    Ok(())
+}
@@ -0,0 +1,57 @@
+use word_xml::WordXMLDocument;
+
+fn main() -> anyhow::Result<()> {
+    let (document, warnings) =
+        WordXMLDocument::parse_lenient(include_str!("../check_parse/document.xml"))?;
+    assert!(warnings.is_empty());
+    assert_eq!(document.root.name, "w:document");
+
+    let data = concat!(
+        "<?xml version=\"1.0\"?>\n",
+        "<w:document><w:body>",
+        "<w:p><w:r><w:t>1 > 0 & 2 < 3</w:t></w:r>",
+        "<w:p><w:r><w:t>second</w:t></w:x></w:r></w:p>",
+        "<w:p w:rsidR=00A1 w:empty><w:r><w:t>third",
+        "</w:body></w:document>",
+    );
+    let (document, warnings) = WordXMLDocument::parse_part_lenient("word/document.xml", data)?;
+    for warning in &warnings {
+        println!("{}", warning);
+    }
+
+    let body = document.root.get_child("w:body").unwrap();
+    let paragraphs = body.get_children("w:p").collect::<Vec<_>>();
+    assert_eq!(paragraphs.len(), 1);
+
+    let texts = collect_texts(&document.root);
+    assert_eq!(texts, ["1 > 0 & 2 < 3", "second", "third"]);
+
+    let third = paragraphs[0].get_children("w:p").nth(1).unwrap();
+    assert_eq!(third.get_attr_parsed::<String>("w:rsidR").unwrap(), "00A1");
+
+    assert!(warnings
+        .iter()
+        .all(|w| w.part.as_deref() == Some("word/document.xml")));
+    assert!(warnings.iter().any(|w| w.message.contains("</w:x>")));
+    assert!(warnings.iter().any(|w| w.message.contains("not quoted")));
+    assert!(warnings
+        .iter()
+        .any(|w| w.message.contains("closed implicitly")));
+
+    assert!(WordXMLDocument::parse_lenient("just text").is_err());
+
+    println!("lenient: ok");
+    Ok(())
+}
+
+fn collect_texts(element: &word_xml::Element) -> Vec<String> {
+    let mut texts = Vec::new();
+    for node in &element.inners {
+        match node {
+            word_xml::Node::Element(child) if child.name == "w:t" => texts.push(child.get_texts()),
+            word_xml::Node::Element(child) => texts.extend(collect_texts(child)),
+            _ => {}
+        }
+    }
+    texts
+}
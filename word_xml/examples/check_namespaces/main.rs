@@ -0,0 +1,76 @@
+use word_xml::{namespaces::W_NS, Event, Namespaces, WordXMLDocument, XmlReader};
+
+const OTHER_NS: &str = "urn:other";
+const DEFAULT_NS: &str = "urn:default";
+
+fn main() -> anyhow::Result<()> {
+    let data = concat!(
+        r#"<w:document xmlns:w="http://schemas.openxmlformats.org/wordprocessingml/2006/main">"#,
+        r#"<w:body><w:p xmlns:w="urn:other"><w:r/></w:p>"#,
+        r#"<x xmlns="urn:default"><y/></x><w:sectPr/></w:body></w:document>"#,
+    );
+
+    // The reader resolves every start tag in the scope it is opened in.
+    let mut reader = XmlReader::new(data);
+    let mut resolved = Vec::new();
+    while let Some(event) = reader.next_event()? {
+        if let Event::Start(tag) = event {
+            let element = tag.to_element();
+            resolved.push((
+                tag.name,
+                element.namespace(reader.namespaces()).map(str::to_string),
+            ));
+        }
+    }
+    let resolved = resolved
+        .iter()
+        .map(|(name, uri)| (*name, uri.as_deref()))
+        .collect::<Vec<_>>();
+    assert_eq!(
+        resolved,
+        [
+            ("w:document", Some(W_NS)),
+            ("w:body", Some(W_NS)),
+            ("w:p", Some(OTHER_NS)),
+            ("w:r", Some(OTHER_NS)),
+            ("x", Some(DEFAULT_NS)),
+            ("y", Some(DEFAULT_NS)),
+            ("w:sectPr", Some(W_NS)),
+        ]
+    );
+    assert_eq!(reader.namespaces().resolve("w"), None);
+
+    // Scopes pushed by hand shadow the outer declarations until popped.
+    let document = data.parse::<WordXMLDocument>()?;
+    let mut namespaces = Namespaces::of(&document.root);
+    let body = document.root.get_child("w:body").expect("body");
+    let paragraph = body.get_child("w:p").expect("paragraph");
+    assert!(body.is_ns(&namespaces, W_NS, "body"));
+    namespaces.push_scope(body);
+    namespaces.push_scope(paragraph);
+    assert!(paragraph.is_ns(&namespaces, OTHER_NS, "p"));
+    assert_eq!(namespaces.prefix_of(W_NS), None);
+    assert_eq!(namespaces.iter().filter(|(prefix, _)| *prefix == "w").count(), 1);
+    namespaces.pop_scope();
+    assert_eq!(namespaces.prefix_of(W_NS), Some("w"));
+    assert!(body.get_child("w:sectPr").expect("sectPr").is_ns(&namespaces, W_NS, "sectPr"));
+
+    // Canonical output sorts attributes by the URI in scope of their element.
+    let data = concat!(
+        r#"<r xmlns:a="urn:z" xmlns:b="urn:y"><e a:x="1" b:x="2"/>"#,
+        r#"<e xmlns:a="urn:a" a:x="1" b:x="2"/></r>"#,
+    );
+    let mut out = Vec::new();
+    data.parse::<WordXMLDocument>()?
+        .write_with(&mut out, &word_xml::WriteOptions::canonical())?;
+    assert_eq!(
+        String::from_utf8(out)?,
+        concat!(
+            r#"<r xmlns:a="urn:z" xmlns:b="urn:y"><e b:x="2" a:x="1"></e>"#,
+            r#"<e xmlns:a="urn:a" a:x="1" b:x="2"></e></r>"#,
+        )
+    );
+
+    println!("namespaces: ok");
+    Ok(())
+}
@@ -0,0 +1,74 @@
+use word_xml::{Element, WordXMLDocument};
+
+fn names<'a>(elements: impl IntoIterator<Item = &'a Element>) -> Vec<&'a str> {
+    elements
+        .into_iter()
+        .map(|elem| elem.name.as_str())
+        .collect()
+}
+
+fn main() -> anyhow::Result<()> {
+    let data = r#"<w:document><w:body>
+        <w:p w:rsidR="01"><w:r w:rsidR="01"><w:t>a</w:t></w:r><w:r><w:t>b</w:t></w:r></w:p>
+        <w:tbl><w:tr><w:tc><w:p><w:r w:rsidR="02"><w:t>c</w:t></w:r></w:p></w:tc></w:tr></w:tbl>
+        <w:sectPr/>
+    </w:body></w:document>"#;
+    let mut document = data.parse::<WordXMLDocument>()?;
+    let root = &document.root;
+
+    assert_eq!(root.descendants().count(), 13);
+    assert_eq!(
+        names(root.descendants().take(4)),
+        ["w:body", "w:p", "w:r", "w:t"]
+    );
+
+    assert_eq!(root.find_path("w:body/w:p/w:r").count(), 2);
+    assert_eq!(root.find_path("w:body/*/w:tr/w:tc/w:p/w:r").count(), 1);
+    assert!(root.find_path_first("w:body/w:sectPr").is_some());
+    assert!(root.find_path_first("w:body/w:r").is_none());
+
+    let runs = root.select("w:p > w:r[w:rsidR]")?;
+    assert_eq!(runs.len(), 2);
+    assert!(runs.iter().all(|run| run.get_attr("w:rsidR").is_some()));
+
+    assert_eq!(root.select("w:tbl w:t")?.len(), 1);
+    assert_eq!(root.select("w:body > w:p > w:r > w:t")?.len(), 2);
+    assert_eq!(
+        root.select("w:document > w:body > w:p[w:rsidR='01']")?
+            .len(),
+        1
+    );
+    assert_eq!(root.select("[w:rsidR=\"02\"]")?.len(), 1);
+    assert!(root.select("w:p >").is_err());
+    assert!(root.select("> w:p").is_err());
+    assert!(root.select("w:p[w:rsidR").is_err());
+
+    let body = document.root.get_child_mut("w:body").unwrap();
+    let sect = body.remove_child("w:sectPr");
+    assert!(sect.is_some());
+    assert!(!body.has_child("w:sectPr"));
+
+    body.retain_children(|elem| elem.name != "w:tbl");
+    assert_eq!(names(body.get_children("w:tbl")), Vec::<&str>::new());
+
+    let paragraph = body.get_child_mut("w:p").unwrap();
+    paragraph.set_attr("w:rsidR", "FF");
+    paragraph.set_attr("w:rsidP", "AA");
+    assert_eq!(paragraph.get_attr("w:rsidR"), Some("FF"));
+    assert_eq!(paragraph.attrs.len(), 2);
+    assert_eq!(paragraph.remove_attr("w:rsidP").as_deref(), Some("AA"));
+    assert_eq!(paragraph.remove_attr("w:rsidP"), None);
+
+    for run in paragraph.get_children_mut("w:r") {
+        run.remove_attr("w:rsidR");
+    }
+    let old = paragraph.replace_child("w:r", Element::new("w:r").with_text("x"));
+    assert_eq!(
+        old.map(|run| run.get_childs_texts("w:t")),
+        Some(Some("a".into()))
+    );
+    assert!(paragraph.select("w:r[w:rsidR]")?.is_empty());
+
+    println!("query: ok");
+    Ok(())
+}
@@ -0,0 +1,67 @@
+use word_xml::{Event, XmlReader};
+
+fn main() -> anyhow::Result<()> {
+    let data = "<?xml version=\"1.0\"?>\n<w:body a=\"1 &amp; 2\"><w:p><w:r/>x &lt; y</w:p><!-- c --></w:body>";
+
+    let events = XmlReader::new(data).collect::<Result<Vec<_>, _>>()?;
+    let names = events
+        .iter()
+        .map(|event| match event {
+            Event::Declaration(_) => "decl".to_string(),
+            Event::Start(tag) => format!("<{}>", tag.name),
+            Event::End(name) => format!("</{}>", name),
+            Event::Text(text) => format!("{:?}", text),
+            Event::Comment(comment) => format!("<!--{}-->", comment),
+            otherwise => format!("{:?}", otherwise),
+        })
+        .collect::<Vec<_>>();
+    println!("{:?}", names);
+    assert_eq!(
+        names,
+        [
+            "decl",
+            "<w:body>",
+            "<w:p>",
+            "<w:r>",
+            "</w:r>",
+            "\"x < y\"",
+            "</w:p>",
+            "<!-- c -->",
+            "</w:body>",
+        ]
+    );
+
+    let Event::Start(body) = &events[1] else {
+        panic!("expected <w:body>, found {:?}", events[1]);
+    };
+    assert_eq!(body.attr("a"), Some("1 & 2"));
+
+    let mut reader = XmlReader::new(data);
+    while let Some(event) = reader.next_event()? {
+        if let Event::Start(tag) = event {
+            if tag.name == "w:p" {
+                let paragraph = reader.read_element(tag)?;
+                assert_eq!(paragraph.get_texts(), "x < y");
+                assert!(paragraph.has_child("w:r"));
+                assert_eq!(reader.depth(), 1);
+            }
+        }
+    }
+
+    let data = concat!(
+        "<w:p><w:r><w:t xml:space=\"preserve\"> </w:t><w:t> </w:t></w:r>",
+        "<w:r xml:space=\"preserve\"><w:t>\t</w:t><w:t xml:space=\"default\"> </w:t></w:r>\n</w:p>"
+    );
+    let document = data.parse::<word_xml::WordXMLDocument>()?;
+    let texts = document
+        .root
+        .descendants()
+        .filter(|elem| elem.name == "w:t")
+        .map(|elem| elem.get_texts())
+        .collect::<Vec<_>>();
+    assert_eq!(texts, [" ", "", "\t", ""]);
+    assert_eq!(document.root.inners.len(), 2);
+
+    println!("reader: ok");
+    Ok(())
+}
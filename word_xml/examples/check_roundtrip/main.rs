@@ -0,0 +1,97 @@
+fn main() -> anyhow::Result<()> {
+    let samples = [
+        (
+            "check_parse/document.xml",
+            include_str!("../check_parse/document.xml"),
+        ),
+        (
+            "check_parse/document_formated.xml",
+            include_str!("../check_parse/document_formated.xml"),
+        ),
+        (
+            "check_write/document_formated.xml",
+            include_str!("../check_write/document_formated.xml"),
+        ),
+        ("check_roundtrip/escaped.xml", include_str!("./escaped.xml")),
+        (
+            "check_roundtrip/misc_nodes.xml",
+            include_str!("./misc_nodes.xml"),
+        ),
+    ];
+
+    for (name, data) in samples {
+        let document: word_xml::WordXMLDocument = data.parse()?;
+
+        let mut buf = Vec::new();
+        document.write_to(&mut buf)?;
+        let written = String::from_utf8(buf)?;
+
+        let reparsed: word_xml::WordXMLDocument = written.parse()?;
+        assert_eq!(
+            document.root, reparsed.root,
+            "{name}: tree changed after round-trip"
+        );
+        assert_eq!(document.prolog, reparsed.prolog, "{name}: prolog changed");
+        assert_eq!(document.epilog, reparsed.epilog, "{name}: epilog changed");
+        assert_eq!(
+            document.doctype, reparsed.doctype,
+            "{name}: doctype changed"
+        );
+
+        let mut buf = Vec::new();
+        reparsed.write_to(&mut buf)?;
+        assert_eq!(
+            written,
+            String::from_utf8(buf)?,
+            "{name}: output is not stable"
+        );
+
+        println!("{name}: ok");
+    }
+
+    let document: word_xml::WordXMLDocument = include_str!("./escaped.xml").parse()?;
+    let body = document.root.get_child("w:body").unwrap();
+    let mut paragraphs = body.get_children("w:p");
+
+    let text = paragraphs
+        .next()
+        .and_then(|p| p.get_child("w:r"))
+        .and_then(|r| r.get_childs_texts("w:t"))
+        .unwrap();
+    assert_eq!(
+        text,
+        "Fish & chips <3 > \"quoted\" 'single' \u{2013} \u{2014}"
+    );
+
+    let paragraph = paragraphs.next().unwrap();
+    assert_eq!(
+        paragraph.get_attr_parsed::<String>("w:rsidR").unwrap(),
+        "00A1\"30C0"
+    );
+    assert_eq!(
+        paragraph.get_attr_parsed::<String>("w:custom").unwrap(),
+        "a & b < c \"d\" \ttab"
+    );
+
+    let bad = "<?xml version=\"1.0\"?>\n<w:t>&nbsp;</w:t>";
+    assert!(bad.parse::<word_xml::WordXMLDocument>().is_err());
+
+    println!("escaping: ok");
+
+    let document: word_xml::WordXMLDocument = include_str!("./misc_nodes.xml").parse()?;
+    assert_eq!(
+        document.doctype.as_deref(),
+        Some("<!DOCTYPE root [ <!ELEMENT root ANY> ]>")
+    );
+    assert!(document.prolog[0].is_processing_instruction());
+    assert!(document.prolog[1].is_comment());
+    assert!(document.epilog[0].is_comment());
+    assert_eq!(
+        document.root.get_child("item").unwrap().get_texts(),
+        "a < b && c > d"
+    );
+
+    println!("misc nodes: ok");
+
+    Ok(())
+}
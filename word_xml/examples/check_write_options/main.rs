@@ -0,0 +1,55 @@
+use word_xml::{WordXMLDocument, WriteOptions};
+
+fn write(document: &WordXMLDocument, options: &WriteOptions) -> anyhow::Result<String> {
+    let mut out = Vec::new();
+    document.write_with(&mut out, options)?;
+    Ok(String::from_utf8(out)?)
+}
+
+fn main() -> anyhow::Result<()> {
+    let data = concat!(
+        "<?xml version=\"1.0\"?>\n<!-- c -->\n",
+        "<w:document w:b=\"2\" xmlns:w=\"urn:w\" a=\"1\" xmlns=\"urn:d\">",
+        "<w:body><w:p><w:r><w:t xml:space=\"preserve\">  two  spaces </w:t></w:r>",
+        "<w:r><w:t>a<![CDATA[<b>]]></w:t></w:r></w:p><w:sectPr></w:sectPr><!-- in --></w:body>",
+        "</w:document>"
+    );
+    let document = data.parse::<WordXMLDocument>()?;
+
+    let default = write(&document, &WriteOptions::default())?;
+    let mut plain = Vec::new();
+    document.write_to(&mut plain)?;
+    assert_eq!(default.as_bytes(), plain);
+    assert!(!default.contains("/>"));
+
+    let pretty = write(&document, &WriteOptions::pretty())?;
+    println!("{}\n", pretty);
+    assert!(pretty.contains(
+        "<w:document xmlns=\"urn:d\" xmlns:w=\"urn:w\" a=\"1\" w:b=\"2\">\n  <w:body>\n    <w:p>"
+    ));
+    assert!(pretty.contains("\n        <w:t xml:space=\"preserve\">  two  spaces </w:t>\n"));
+    assert!(pretty.contains("<w:t>a<![CDATA[<b>]]></w:t>"));
+    assert!(pretty.contains("\n    <w:sectPr/>\n    <!-- in -->\n  </w:body>"));
+    assert_eq!(
+        pretty.parse::<WordXMLDocument>()?.root.inners,
+        document.root.inners
+    );
+
+    let tabs = write(&document, &WriteOptions::pretty().with_indent("\t"))?;
+    assert!(tabs.contains("\n\t<w:body>"));
+
+    let canonical = write(&document, &WriteOptions::canonical())?;
+    println!("{}", canonical);
+    assert_eq!(
+        canonical,
+        concat!(
+            "<w:document xmlns=\"urn:d\" xmlns:w=\"urn:w\" a=\"1\" w:b=\"2\">",
+            "<w:body><w:p><w:r><w:t xml:space=\"preserve\">  two  spaces </w:t></w:r>",
+            "<w:r><w:t>a&lt;b&gt;</w:t></w:r></w:p><w:sectPr></w:sectPr></w:body>",
+            "</w:document>"
+        )
+    );
+
+    println!("write options: ok");
+    Ok(())
+}
@@ -0,0 +1,246 @@
+use crate::{ParseError, ParseWarning, WordXMLDocument, WriteOptions};
+
+/// Character encoding a part was stored in, kept so it can be written back
+/// the same way.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Encoding {
+    pub charset: &'static encoding_rs::Encoding,
+    pub bom: bool,
+}
+
+impl Default for Encoding {
+    fn default() -> Self {
+        Self {
+            charset: encoding_rs::UTF_8,
+            bom: false,
+        }
+    }
+}
+
+impl Encoding {
+    /// Name to put in the `encoding=` pseudo-attribute.
+    pub fn name(&self) -> &'static str {
+        if self.is_utf16() {
+            "UTF-16"
+        } else {
+            self.charset.name()
+        }
+    }
+
+    pub fn is_utf16(&self) -> bool {
+        self.charset == encoding_rs::UTF_16LE || self.charset == encoding_rs::UTF_16BE
+    }
+
+    pub fn encode(&self, text: &str) -> Vec<u8> {
+        let mut bytes = Vec::with_capacity(text.len() + 3);
+        if self.bom {
+            bytes.extend_from_slice(match self.charset.name() {
+                "UTF-16LE" => b"\xFF\xFE".as_slice(),
+                "UTF-16BE" => b"\xFE\xFF",
+                _ => b"\xEF\xBB\xBF",
+            });
+        }
+
+        if self.charset == encoding_rs::UTF_16LE {
+            bytes.extend(text.encode_utf16().flat_map(u16::to_le_bytes));
+        } else if self.charset == encoding_rs::UTF_16BE {
+            bytes.extend(text.encode_utf16().flat_map(u16::to_be_bytes));
+        } else {
+            // Unmappable characters become numeric character references.
+            bytes.extend_from_slice(&self.charset.encode(text).0);
+        }
+        bytes
+    }
+}
+
+/// Decode a raw part, detecting the encoding from a BOM, the first bytes of
+/// a UTF-16 declaration or the `encoding=` pseudo-attribute.
+pub fn decode(bytes: &[u8]) -> Result<(String, Encoding), ParseError> {
+    let (encoding, bom_len) = detect(bytes).map_err(|message| ParseError::new("", 0, message))?;
+    let body = &bytes[bom_len..];
+
+    if encoding.charset == encoding_rs::UTF_8 {
+        return match std::str::from_utf8(body) {
+            Ok(text) => Ok((text.to_string(), encoding)),
+            Err(err) => {
+                let valid = String::from_utf8_lossy(&body[..err.valid_up_to()]);
+                Err(ParseError::new(
+                    &valid,
+                    valid.len(),
+                    format!("Invalid UTF-8 at byte {}", bom_len + err.valid_up_to()),
+                ))
+            }
+        };
+    }
+
+    match encoding
+        .charset
+        .decode_without_bom_handling_and_without_replacement(body)
+    {
+        Some(text) => Ok((text.into_owned(), encoding)),
+        None => Err(ParseError::new(
+            "",
+            0,
+            format!("Invalid {} byte sequence", encoding.charset.name()),
+        )),
+    }
+}
+
+/// Like [`decode`], but falls back to UTF-8 for unknown encodings and
+/// replaces malformed sequences, reporting both as warnings.
+pub fn decode_lossy(bytes: &[u8]) -> (String, Encoding, Vec<ParseWarning>) {
+    let mut warnings = Vec::new();
+    let (encoding, bom_len) = detect(bytes).unwrap_or_else(|message| {
+        warnings.push(ParseError::new(
+            "",
+            0,
+            format!("{}; read as UTF-8", message),
+        ));
+        (Encoding::default(), 0)
+    });
+
+    let (text, had_errors) = encoding
+        .charset
+        .decode_without_bom_handling(&bytes[bom_len..]);
+    if had_errors {
+        warnings.push(ParseError::new(
+            "",
+            0,
+            format!(
+                "Invalid {} byte sequences replaced",
+                encoding.charset.name()
+            ),
+        ));
+    }
+
+    (text.into_owned(), encoding, warnings)
+}
+
+fn detect(bytes: &[u8]) -> Result<(Encoding, usize), String> {
+    if let Some((charset, bom_len)) = encoding_rs::Encoding::for_bom(bytes) {
+        return Ok((Encoding { charset, bom: true }, bom_len));
+    }
+
+    let charset = match bytes {
+        [0x3C, 0, 0x3F, 0, ..] => encoding_rs::UTF_16LE,
+        [0, 0x3C, 0, 0x3F, ..] => encoding_rs::UTF_16BE,
+        _ => match declared_encoding(bytes) {
+            Some(label) => match encoding_rs::Encoding::for_label(label.as_bytes()) {
+                // Without a BOM the declaration itself was read as 8-bit
+                // text, so a `UTF-16` label cannot be right.
+                Some(charset) if charset == encoding_rs::UTF_16LE => encoding_rs::UTF_8,
+                Some(charset) if charset == encoding_rs::UTF_16BE => encoding_rs::UTF_8,
+                Some(charset) => charset,
+                None => return Err(format!("Unsupported encoding {:?}", label)),
+            },
+            None => encoding_rs::UTF_8,
+        },
+    };
+
+    Ok((
+        Encoding {
+            charset,
+            bom: false,
+        },
+        0,
+    ))
+}
+
+/// Value of the `encoding=` pseudo-attribute of an 8-bit XML declaration.
+pub fn declared_encoding(bytes: &[u8]) -> Option<&str> {
+    let prefix = bytes.strip_prefix(b"<?xml")?;
+    let end = prefix.windows(2).position(|window| window == b"?>")?;
+    let declaration = std::str::from_utf8(&prefix[..end]).ok()?;
+
+    pseudo_attr(declaration, "encoding").map(|(_, value)| value)
+}
+
+/// Byte range and value of the `name` pseudo-attribute in a declaration.
+pub(crate) fn pseudo_attr<'a>(
+    declaration: &'a str,
+    name: &str,
+) -> Option<(std::ops::Range<usize>, &'a str)> {
+    let mut search = 0;
+    while let Some(idx) = declaration[search..].find(name) {
+        let start = search + idx;
+        search = start + name.len();
+
+        let preceded_by_ws = declaration[..start].ends_with(char::is_whitespace);
+        let rest = declaration[search..].trim_start();
+        let Some(rest) = rest.strip_prefix('=').filter(|_| preceded_by_ws) else {
+            continue;
+        };
+        let rest = rest.trim_start();
+        let quote = rest.chars().next().filter(|c| *c == '"' || *c == '\'')?;
+        let value_start = declaration.len() - rest.len() + 1;
+        let value_len = rest[1..].find(quote)?;
+
+        return Some((
+            value_start..value_start + value_len,
+            &declaration[value_start..value_start + value_len],
+        ));
+    }
+    None
+}
+
+impl WordXMLDocument {
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, ParseError> {
+        let (text, encoding) = decode(bytes)?;
+        let mut document = text.parse::<Self>()?;
+        document.encoding = encoding;
+        Ok(document)
+    }
+
+    pub fn parse_part_bytes(part: &str, bytes: &[u8]) -> Result<Self, ParseError> {
+        Self::from_bytes(bytes).map_err(|err| err.with_part(part))
+    }
+
+    pub fn parse_lenient_bytes(bytes: &[u8]) -> Result<(Self, Vec<ParseWarning>), ParseError> {
+        let (text, encoding, mut warnings) = decode_lossy(bytes);
+        let (mut document, parse_warnings) = Self::parse_lenient(&text)?;
+        warnings.extend(parse_warnings);
+        document.encoding = encoding;
+        Ok((document, warnings))
+    }
+
+    pub fn parse_part_lenient_bytes(
+        part: &str,
+        bytes: &[u8],
+    ) -> Result<(Self, Vec<ParseWarning>), ParseError> {
+        let (document, warnings) =
+            Self::parse_lenient_bytes(bytes).map_err(|err| err.with_part(part))?;
+        Ok((
+            document,
+            warnings
+                .into_iter()
+                .map(|warning| warning.with_part(part))
+                .collect(),
+        ))
+    }
+
+    /// Change the encoding used by `to_bytes`, keeping the `encoding=`
+    /// pseudo-attribute of the declaration in sync.
+    pub fn set_encoding(&mut self, encoding: Encoding) {
+        self.encoding = encoding;
+        if self.header.is_empty() {
+            return;
+        }
+
+        if let Some((range, _)) = pseudo_attr(&self.header, "encoding") {
+            self.header.replace_range(range, encoding.name());
+        } else if encoding.charset != encoding_rs::UTF_8 {
+            let at = pseudo_attr(&self.header, "version")
+                .map(|(range, _)| range.end + 1)
+                .unwrap_or("<?xml".len());
+            self.header
+                .insert_str(at, &format!(" encoding=\"{}\"", encoding.name()));
+        }
+    }
+
+    /// Serialize in the encoding the document was read with.
+    pub fn to_bytes(&self, options: &WriteOptions) -> anyhow::Result<Vec<u8>> {
+        let mut out = Vec::new();
+        self.write_with(&mut out, options)?;
+        Ok(self.encoding.encode(&String::from_utf8(out)?))
+    }
+}
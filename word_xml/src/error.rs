@@ -0,0 +1,82 @@
+#[derive(Debug, Clone, PartialEq)]
+pub struct ParseError {
+    pub part: Option<String>,
+    pub line: usize,
+    pub column: usize,
+    pub offset: usize,
+    pub snippet: String,
+    pub message: String,
+}
+
+impl ParseError {
+    const SNIPPET_RADIUS: usize = 40;
+
+    pub fn new(input: &str, offset: usize, message: impl ToString) -> Self {
+        let offset = floor_char_boundary(input, offset.min(input.len()));
+        let line_start = input[..offset].rfind('\n').map(|idx| idx + 1).unwrap_or(0);
+        let line = input[..offset].matches('\n').count() + 1;
+        let column = input[line_start..offset].chars().count() + 1;
+
+        Self {
+            part: None,
+            line,
+            column,
+            offset,
+            snippet: snippet(input, line_start, offset),
+            message: message.to_string(),
+        }
+    }
+
+    pub fn with_part(mut self, part: impl ToString) -> Self {
+        self.part = Some(part.to_string());
+        self
+    }
+
+    /// Column of the error inside `snippet`, used to place the caret.
+    pub fn snippet_column(&self) -> usize {
+        self.column.min(Self::SNIPPET_RADIUS + 1)
+    }
+}
+
+fn snippet(input: &str, line_start: usize, offset: usize) -> String {
+    let line_end = input[offset..]
+        .find('\n')
+        .map(|idx| offset + idx)
+        .unwrap_or(input.len());
+
+    let before = input[line_start..offset]
+        .chars()
+        .rev()
+        .take(ParseError::SNIPPET_RADIUS)
+        .collect::<Vec<_>>();
+    let after = input[offset..line_end]
+        .chars()
+        .take(ParseError::SNIPPET_RADIUS)
+        .collect::<String>();
+
+    before.into_iter().rev().collect::<String>() + after.trim_end_matches('\r')
+}
+
+fn floor_char_boundary(input: &str, mut offset: usize) -> usize {
+    while !input.is_char_boundary(offset) {
+        offset -= 1;
+    }
+    offset
+}
+
+impl std::fmt::Display for ParseError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        writeln!(
+            f,
+            "{}:{}:{}: {}",
+            self.part.as_deref().unwrap_or("<xml>"),
+            self.line,
+            self.column,
+            self.message
+        )?;
+        writeln!(f, "  | {}", self.snippet)?;
+        write!(f, "  | {}^", " ".repeat(self.snippet_column() - 1))
+    }
+}
+
+impl std::error::Error for ParseError {}
@@ -0,0 +1,93 @@
+use std::borrow::Cow;
+
+use anyhow::Context;
+
+pub fn unescape(s: &str) -> anyhow::Result<Cow<'_, str>> {
+    if !s.contains('&') {
+        return Ok(Cow::Borrowed(s));
+    }
+
+    let mut result = String::with_capacity(s.len());
+    let mut rest = s;
+
+    while let Some(start) = rest.find('&') {
+        result.push_str(&rest[..start]);
+        rest = &rest[start + 1..];
+
+        let end = rest
+            .find(';')
+            .context(format!("Unterminated entity reference: `&{}`", rest))?;
+        let entity = &rest[..end];
+        rest = &rest[end + 1..];
+
+        result.push(decode_entity(entity)?);
+    }
+    result.push_str(rest);
+
+    Ok(Cow::Owned(result))
+}
+
+fn decode_entity(entity: &str) -> anyhow::Result<char> {
+    let code = match entity {
+        "amp" => return Ok('&'),
+        "lt" => return Ok('<'),
+        "gt" => return Ok('>'),
+        "quot" => return Ok('"'),
+        "apos" => return Ok('\''),
+        _ => {
+            if let Some(hex) = entity
+                .strip_prefix("#x")
+                .or_else(|| entity.strip_prefix("#X"))
+            {
+                u32::from_str_radix(hex, 16)
+            } else if let Some(dec) = entity.strip_prefix('#') {
+                dec.parse::<u32>()
+            } else {
+                return Err(anyhow::Error::msg(format!(
+                    "Unknown entity reference: `&{};`",
+                    entity
+                )));
+            }
+        }
+    }
+    .context(format!("Invalid character reference: `&{};`", entity))?;
+
+    char::from_u32(code).context(format!("Invalid character code: `&{};`", entity))
+}
+
+pub fn escape_text(s: &str) -> Cow<'_, str> {
+    escape(s, |c| match c {
+        '&' => Some("&amp;"),
+        '<' => Some("&lt;"),
+        '>' => Some("&gt;"),
+        _ => None,
+    })
+}
+
+pub fn escape_attr(s: &str) -> Cow<'_, str> {
+    escape(s, |c| match c {
+        '&' => Some("&amp;"),
+        '<' => Some("&lt;"),
+        '"' => Some("&quot;"),
+        '\t' => Some("&#x9;"),
+        '\n' => Some("&#xA;"),
+        '\r' => Some("&#xD;"),
+        _ => None,
+    })
+}
+
+fn escape(s: &str, replacement: impl Fn(char) -> Option<&'static str>) -> Cow<'_, str> {
+    if !s.chars().any(|c| replacement(c).is_some()) {
+        return Cow::Borrowed(s);
+    }
+
+    let mut result = String::with_capacity(s.len() + 8);
+    for c in s.chars() {
+        match replacement(c) {
+            Some(escaped) => result.push_str(escaped),
+            None => result.push(c),
+        }
+    }
+
+    Cow::Owned(result)
+}
@@ -1,148 +1,80 @@
-use anyhow::Context;
-use pest::{iterators::Pair, Parser};
 use std::str::FromStr;
 
-use crate::{Element, WordXMLDocument};
-use pest_derive::Parser;
+use crate::{
+    reader::{Event, XmlReader},
+    Namespaces, Node, ParseError, ProcessingInstruction, WordXMLDocument,
+};
 
-#[derive(Parser)]
-#[grammar = "./xml.pest"]
-struct XMLParser;
-
-impl FromStr for WordXMLDocument {
-    type Err = anyhow::Error;
-
-    fn from_str(s: &str) -> Result<Self, Self::Err> {
-        let word_xml = XMLParser::parse(Rule::word_xml, s)
-            .context("Failed to parse xml word document.")?
-            .next()
-            .context("XML document must containt word_xml rule")?;
+impl WordXMLDocument {
+    pub fn parse_part(part: &str, s: &str) -> Result<Self, ParseError> {
+        s.parse::<Self>().map_err(|err| err.with_part(part))
+    }
 
+    /// Build the whole tree from the remaining events of `reader`.
+    pub fn from_reader(reader: &mut XmlReader<'_>) -> Result<Self, ParseError> {
         let mut xml_document = WordXMLDocument {
             header: Default::default(),
+            doctype: None,
+            prolog: Vec::new(),
             root: Default::default(),
+            epilog: Vec::new(),
+            namespaces: Default::default(),
+            encoding: Default::default(),
         };
+        let mut root = None;
 
-        for rule in word_xml.into_inner() {
-            match rule.as_rule() {
-                Rule::header => xml_document.header = rule.as_str().to_string(),
-                Rule::element => {
-                    xml_document.root = rule.try_into()?;
-                }
-                otherwise => {
-                    return Err(anyhow::Error::msg(format!(
-                        "Invalid inner rule while parse xml_document: {:?}",
-                        otherwise
-                    )))
+        while let Some(event) = reader.next_event()? {
+            let node = match event {
+                Event::Declaration(header) => {
+                    xml_document.header = header.to_string();
+                    continue;
                 }
-            }
-        }
-
-        Ok(xml_document)
-    }
-}
-
-impl TryFrom<Pair<'_, Rule>> for super::Element {
-    type Error = anyhow::Error;
-
-    fn try_from(value: Pair<'_, Rule>) -> Result<Self, Self::Error> {
-        let mut element = Element::default();
-        for rule in value.into_inner() {
-            match rule.as_rule() {
-                Rule::open_tag => {
-                    for rule in rule.into_inner() {
-                        match rule.as_rule() {
-                            Rule::tag_name => element.name = rule.as_str().to_string(),
-                            Rule::attr => element.attrs.push(rule.try_into()?),
-                            otherwise => {
-                                return Err(anyhow::Error::msg(format!(
-                                    "Invalid inner rule while parse open_tag: {:?}",
-                                    otherwise
-                                )))
-                            }
-                        }
-                    }
+                Event::Doctype(doctype) => {
+                    xml_document.doctype = Some(doctype.to_string());
+                    continue;
                 }
-                Rule::open_close_tag => {
-                    for rule in rule.into_inner() {
-                        match rule.as_rule() {
-                            Rule::tag_name => element.name = rule.as_str().to_string(),
-                            Rule::attr => element.attrs.push(rule.try_into()?),
-                            otherwise => {
-                                return Err(anyhow::Error::msg(format!(
-                                    "Invalid inner rule while parse open_tag: {:?}",
-                                    otherwise
-                                )))
-                            }
-                        }
+                Event::Start(tag) => {
+                    let element = reader.read_element(tag)?;
+                    match root.as_mut() {
+                        None => root = Some(element),
+                        Some(root) => root.append_element(element),
                     }
+                    continue;
                 }
-                Rule::inner => element.inners.push(rule.try_into()?),
-                otherwise => {
-                    return Err(anyhow::Error::msg(format!(
-                        "Invalid inner rule while parse element: {:?}",
-                        otherwise
-                    )))
+                Event::Comment(comment) => Node::Comment(super::Comment(comment.to_string())),
+                Event::ProcessingInstruction { target, content } => {
+                    Node::ProcessingInstruction(ProcessingInstruction {
+                        target: target.to_string(),
+                        content: content.to_string(),
+                    })
                 }
-            }
-        }
-
-        Ok(element)
-    }
-}
+                Event::End(_) | Event::Text(_) | Event::CData(_) => continue,
+            };
 
-impl TryFrom<Pair<'_, Rule>> for super::Attr {
-    type Error = anyhow::Error;
-
-    fn try_from(value: Pair<'_, Rule>) -> Result<Self, Self::Error> {
-        let mut attr = super::Attr::default();
-        for rule in value.into_inner() {
-            match rule.as_rule() {
-                Rule::attr_name => attr.name = rule.as_str().to_string(),
-                Rule::attr_value => attr.value = rule.as_str().to_string(),
-                otherwise => {
-                    return Err(anyhow::Error::msg(format!(
-                        "Invalid inner rule while parse attr: {:?}",
-                        otherwise
-                    )))
-                }
+            if root.is_some() {
+                xml_document.epilog.push(node);
+            } else {
+                xml_document.prolog.push(node);
             }
         }
 
-        Ok(attr)
-    }
-}
+        xml_document.root = root.ok_or_else(|| {
+            ParseError::new(
+                reader.input(),
+                reader.offset(),
+                "Document has no root element",
+            )
+        })?;
+        xml_document.namespaces = Namespaces::of(&xml_document.root);
 
-impl TryFrom<Pair<'_, Rule>> for super::Node {
-    type Error = anyhow::Error;
-
-    fn try_from(value: Pair<'_, Rule>) -> Result<Self, Self::Error> {
-        if let Some(rule) = value.into_inner().next() {
-            match rule.as_rule() {
-                Rule::element => Ok(Self::Element(rule.try_into()?)),
-                Rule::text => Ok(Self::Text(rule.try_into()?)),
-                otherwise => Err(anyhow::Error::msg(format!(
-                    "Invalid inner rule while parse node: {:?}",
-                    otherwise
-                ))),
-            }
-        } else {
-            Err(anyhow::Error::msg("Innder rule mustn't be empty"))
-        }
+        Ok(xml_document)
     }
 }
 
-impl TryFrom<Pair<'_, Rule>> for super::Text {
-    type Error = anyhow::Error;
+impl FromStr for WordXMLDocument {
+    type Err = ParseError;
 
-    fn try_from(value: Pair<'_, Rule>) -> Result<Self, Self::Error> {
-        if let Rule::text = value.as_rule() {
-            Ok(Self(value.as_str().to_string()))
-        } else {
-            Err(anyhow::Error::msg(format!(
-                "Text rule isn't text: {:?}",
-                value.as_rule()
-            )))
-        }
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::from_reader(&mut XmlReader::new(s))
     }
 }
@@ -22,6 +22,12 @@ impl super::Element {
             .find(|elem| elem.name.as_str() == name)
     }
 
+    pub fn get_attr(&self, attr_name: &str) -> Option<&str> {
+        self.attrs
+            .iter()
+            .find_map(|attr| (attr.name.as_str() == attr_name).then_some(attr.value.as_str()))
+    }
+
     pub fn get_attr_parsed<T: FromStr>(&self, attr_name: &str) -> Option<T> {
         self.attrs.iter().find_map(|attr| {
             (attr.name.as_str() == attr_name)
@@ -41,8 +47,13 @@ impl super::Element {
     pub fn get_texts(&self) -> String {
         self.inners
             .iter()
-            .filter_map(super::Node::get_text)
-            .fold(String::new(), |acc, curr| acc + curr.0.as_str())
+            .filter_map(|node| match node {
+                super::Node::Text(super::Text(text)) | super::Node::CData(super::CData(text)) => {
+                    Some(text.as_str())
+                }
+                _ => None,
+            })
+            .fold(String::new(), |acc, curr| acc + curr)
     }
 
     pub fn get_childs_texts(&self, child_name: &str) -> Option<String> {
@@ -52,4 +63,56 @@ impl super::Element {
     pub fn has_child(&self, child_name: &str) -> bool {
         self.get_child(child_name).is_some()
     }
+
+    /// All elements below this one, depth first in document order.
+    pub fn descendants(&self) -> Descendants<'_> {
+        Descendants {
+            stack: vec![self.inners.iter()],
+        }
+    }
+
+    /// Elements reached by following `path` of child names separated by `/`
+    /// from this element; `*` matches any name.
+    pub fn find_path<'a>(&'a self, path: &'a str) -> impl Iterator<Item = &'a super::Element> {
+        path.split('/').filter(|name| !name.is_empty()).fold(
+            Box::new(std::iter::once(self)) as Box<dyn Iterator<Item = &'a super::Element>>,
+            |elements, name| {
+                Box::new(elements.flat_map(move |elem| {
+                    elem.inners
+                        .iter()
+                        .filter_map(super::Node::get_element)
+                        .filter(move |child| name == "*" || child.name == name)
+                }))
+            },
+        )
+    }
+
+    pub fn find_path_first<'a>(&'a self, path: &'a str) -> Option<&'a super::Element> {
+        self.find_path(path).next()
+    }
+}
+
+pub struct Descendants<'a> {
+    stack: Vec<std::slice::Iter<'a, super::Node>>,
+}
+
+impl<'a> Iterator for Descendants<'a> {
+    type Item = &'a super::Element;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            let node = match self.stack.last_mut()?.next() {
+                Some(node) => node,
+                None => {
+                    self.stack.pop();
+                    continue;
+                }
+            };
+
+            if let super::Node::Element(elem) = node {
+                self.stack.push(elem.inners.iter());
+                return Some(elem);
+            }
+        }
+    }
 }
@@ -0,0 +1,28 @@
+use crate::{reader::XmlReader, ParseError, WordXMLDocument};
+
+pub type ParseWarning = ParseError;
+
+impl WordXMLDocument {
+    /// Parse `s`, recovering from malformed markup instead of failing.
+    /// Unclosed elements are closed implicitly and stray markup characters
+    /// are kept as text; every recovery is reported as a warning.
+    pub fn parse_lenient(s: &str) -> Result<(Self, Vec<ParseWarning>), ParseError> {
+        let mut reader = XmlReader::new(s).lenient();
+        let document = Self::from_reader(&mut reader)?;
+        Ok((document, reader.take_warnings()))
+    }
+
+    pub fn parse_part_lenient(
+        part: &str,
+        s: &str,
+    ) -> Result<(Self, Vec<ParseWarning>), ParseError> {
+        let (document, warnings) = Self::parse_lenient(s).map_err(|err| err.with_part(part))?;
+        Ok((
+            document,
+            warnings
+                .into_iter()
+                .map(|warning| warning.with_part(part))
+                .collect(),
+        ))
+    }
+}
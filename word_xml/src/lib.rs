@@ -1,34 +1,71 @@
 mod from_str;
 mod getters;
 mod builder;
+mod mutate;
 mod write;
+pub mod encoding;
+pub mod error;
+pub mod escape;
+pub mod lenient;
+pub mod namespaces;
+pub mod reader;
+pub mod selector;
+
+pub use encoding::Encoding;
+pub use error::ParseError;
+pub use getters::Descendants;
+pub use lenient::ParseWarning;
+pub use namespaces::Namespaces;
+pub use reader::{Event, StartTag, XmlReader};
+pub use selector::Selector;
+pub use write::WriteOptions;
 
 #[derive(Debug)]
 pub struct WordXMLDocument {
     pub header: String,
+    pub doctype: Option<String>,
+    pub prolog: Vec<Node>,
     pub root: Element,
+    pub epilog: Vec<Node>,
+    pub namespaces: Namespaces,
+    pub encoding: Encoding,
 }
 
-#[derive(Default, Debug, Clone)]
+#[derive(Default, Debug, Clone, PartialEq)]
 pub struct Element {
     pub name: String,
     pub attrs: Vec<Attr>,
     pub inners: Vec<Node>,
 }
 
-#[derive(Default, Debug, Clone)]
+#[derive(Default, Debug, Clone, PartialEq)]
 pub struct Attr {
     pub name: String,
     pub value: String,
 }
 
-#[derive(Default, Debug, Clone)]
+#[derive(Default, Debug, Clone, PartialEq)]
 pub struct Text(pub String);
 
-#[derive(Debug, Clone)]
+#[derive(Default, Debug, Clone, PartialEq)]
+pub struct Comment(pub String);
+
+#[derive(Default, Debug, Clone, PartialEq)]
+pub struct CData(pub String);
+
+#[derive(Default, Debug, Clone, PartialEq)]
+pub struct ProcessingInstruction {
+    pub target: String,
+    pub content: String,
+}
+
+#[derive(Debug, Clone, PartialEq)]
 pub enum Node {
     Element(Element),
     Text(Text),
+    Comment(Comment),
+    ProcessingInstruction(ProcessingInstruction),
+    CData(CData),
 }
 
 impl Node {
@@ -38,6 +75,15 @@ impl Node {
     pub fn is_text(&self) -> bool {
         matches!(self, Self::Text(_))
     }
+    pub fn is_comment(&self) -> bool {
+        matches!(self, Self::Comment(_))
+    }
+    pub fn is_processing_instruction(&self) -> bool {
+        matches!(self, Self::ProcessingInstruction(_))
+    }
+    pub fn is_cdata(&self) -> bool {
+        matches!(self, Self::CData(_))
+    }
     pub fn get_element(&self) -> Option<&Element> {
         match self {
             Node::Element(elem) => Some(elem),
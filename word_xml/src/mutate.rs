@@ -0,0 +1,58 @@
+impl super::Element {
+    /// Remove the first child element called `name` and return it.
+    pub fn remove_child(&mut self, name: &str) -> Option<super::Element> {
+        let idx = self
+            .inners
+            .iter()
+            .position(|node| matches!(node, super::Node::Element(elem) if elem.name == name))?;
+
+        match self.inners.remove(idx) {
+            super::Node::Element(elem) => Some(elem),
+            _ => None,
+        }
+    }
+
+    /// Replace the first child element called `name`, appending `element`
+    /// when there is none. Returns the replaced element.
+    pub fn replace_child(&mut self, name: &str, element: super::Element) -> Option<super::Element> {
+        match self.get_child_mut(name) {
+            Some(child) => Some(std::mem::replace(child, element)),
+            None => {
+                self.append_element(element);
+                None
+            }
+        }
+    }
+
+    /// Keep only the child elements for which `f` returns `true`; text and
+    /// other nodes are left untouched.
+    pub fn retain_children(&mut self, mut f: impl FnMut(&super::Element) -> bool) {
+        self.inners.retain(|node| match node {
+            super::Node::Element(elem) => f(elem),
+            _ => true,
+        });
+    }
+
+    pub fn set_attr(&mut self, name: impl ToString, value: impl ToString) {
+        let name = name.to_string();
+        match self.attrs.iter_mut().find(|attr| attr.name == name) {
+            Some(attr) => attr.value = value.to_string(),
+            None => self.append_attr(name, value),
+        }
+    }
+
+    pub fn remove_attr(&mut self, name: &str) -> Option<String> {
+        let idx = self.attrs.iter().position(|attr| attr.name == name)?;
+        Some(self.attrs.remove(idx).value)
+    }
+
+    pub fn get_children_mut<'a>(
+        &'a mut self,
+        name: &'a str,
+    ) -> impl Iterator<Item = &'a mut super::Element> {
+        self.inners
+            .iter_mut()
+            .filter_map(super::Node::get_element_mut)
+            .filter(move |elem| elem.name.as_str() == name)
+    }
+}
@@ -0,0 +1,177 @@
+use std::str::FromStr;
+
+pub const XML_NS: &str = "http://www.w3.org/XML/1998/namespace";
+pub const XMLNS_NS: &str = "http://www.w3.org/2000/xmlns/";
+
+pub const W_NS: &str = "http://schemas.openxmlformats.org/wordprocessingml/2006/main";
+pub const R_NS: &str = "http://schemas.openxmlformats.org/officeDocument/2006/relationships";
+pub const M_NS: &str = "http://schemas.openxmlformats.org/officeDocument/2006/math";
+pub const WP_NS: &str = "http://schemas.openxmlformats.org/drawingml/2006/wordprocessingDrawing";
+pub const A_NS: &str = "http://schemas.openxmlformats.org/drawingml/2006/main";
+pub const PIC_NS: &str = "http://schemas.openxmlformats.org/drawingml/2006/picture";
+pub const MC_NS: &str = "http://schemas.openxmlformats.org/markup-compatibility/2006";
+
+/// `xmlns` declarations in scope, one scope per open element with the
+/// innermost last, so a prefix redeclared or a default namespace declared
+/// on a descendant shadows the outer one only inside that element. The
+/// default namespace is stored under the empty prefix.
+#[derive(Default, Debug, Clone)]
+pub struct Namespaces {
+    scopes: Vec<Vec<(String, String)>>,
+}
+
+impl Namespaces {
+    /// Declarations in scope at `element` when it is the root.
+    pub fn of(element: &super::Element) -> Self {
+        let mut namespaces = Self::default();
+        namespaces.push_scope(element);
+        namespaces
+    }
+
+    /// Declare `prefix` in the innermost scope.
+    pub fn declare(&mut self, prefix: impl ToString, uri: impl ToString) {
+        let prefix = prefix.to_string();
+        if self.scopes.is_empty() {
+            self.scopes.push(Vec::new());
+        }
+        let scope = self.scopes.last_mut().expect("scope was just pushed");
+        scope.retain(|(curr, _)| *curr != prefix);
+        scope.push((prefix, uri.to_string()));
+    }
+
+    /// Open the scope of `element` with the declarations on it.
+    pub fn push_scope(&mut self, element: &super::Element) {
+        self.push_declarations(element.attrs.iter().map(|attr| (&*attr.name, &*attr.value)));
+    }
+
+    /// Open a scope with the declarations among `attrs`.
+    pub fn push_declarations<'a>(
+        &mut self,
+        attrs: impl IntoIterator<Item = (&'a str, &'a str)>,
+    ) {
+        let scope = attrs
+            .into_iter()
+            .filter_map(|(name, uri)| {
+                let prefix = match name {
+                    "xmlns" => "",
+                    name => name.strip_prefix("xmlns:")?,
+                };
+                Some((prefix.to_string(), uri.to_string()))
+            })
+            .collect();
+        self.scopes.push(scope);
+    }
+
+    /// Close the innermost scope.
+    pub fn pop_scope(&mut self) {
+        self.scopes.pop();
+    }
+
+    pub fn resolve(&self, prefix: &str) -> Option<&str> {
+        match prefix {
+            "xml" => Some(XML_NS),
+            "xmlns" => Some(XMLNS_NS),
+            _ => self
+                .scopes
+                .iter()
+                .rev()
+                .flatten()
+                .find_map(|(curr, uri)| (curr == prefix).then_some(uri.as_str())),
+        }
+    }
+
+    /// Prefix bound to `uri` in the innermost scope that is not shadowed.
+    pub fn prefix_of(&self, uri: &str) -> Option<&str> {
+        self.iter()
+            .find_map(|(prefix, curr)| (curr == uri).then_some(prefix))
+    }
+
+    /// Declarations in effect, the innermost first.
+    pub fn iter(&self) -> impl Iterator<Item = (&str, &str)> {
+        let mut seen = Vec::<&str>::new();
+        self.scopes
+            .iter()
+            .rev()
+            .flat_map(|scope| scope.iter().rev())
+            .filter(move |(prefix, _)| {
+                let shadowed = seen.contains(&prefix.as_str());
+                seen.push(prefix);
+                !shadowed
+            })
+            .map(|(prefix, uri)| (prefix.as_str(), uri.as_str()))
+    }
+}
+
+pub fn split_qname(qname: &str) -> (Option<&str>, &str) {
+    match qname.split_once(':') {
+        Some((prefix, local)) => (Some(prefix), local),
+        None => (None, qname),
+    }
+}
+
+impl super::Element {
+    pub fn prefix(&self) -> Option<&str> {
+        split_qname(&self.name).0
+    }
+
+    pub fn local_name(&self) -> &str {
+        split_qname(&self.name).1
+    }
+
+    pub fn namespace<'a>(&self, namespaces: &'a Namespaces) -> Option<&'a str> {
+        namespaces.resolve(self.prefix().unwrap_or_default())
+    }
+
+    pub fn is_ns(&self, namespaces: &Namespaces, uri: &str, local_name: &str) -> bool {
+        self.local_name() == local_name && self.namespace(namespaces) == Some(uri)
+    }
+
+    pub fn get_children_ns<'a>(
+        &'a self,
+        namespaces: &'a Namespaces,
+        uri: &'a str,
+        local_name: &'a str,
+    ) -> impl Iterator<Item = &'a super::Element> {
+        self.inners
+            .iter()
+            .filter_map(super::Node::get_element)
+            .filter(move |elem| elem.is_ns(namespaces, uri, local_name))
+    }
+
+    pub fn get_child_ns(
+        &self,
+        namespaces: &Namespaces,
+        uri: &str,
+        local_name: &str,
+    ) -> Option<&Self> {
+        self.inners
+            .iter()
+            .filter_map(super::Node::get_element)
+            .find(|elem| elem.is_ns(namespaces, uri, local_name))
+    }
+
+    pub fn get_attr_ns(
+        &self,
+        namespaces: &Namespaces,
+        uri: &str,
+        local_name: &str,
+    ) -> Option<&str> {
+        self.attrs.iter().find_map(|attr| {
+            let (prefix, local) = split_qname(&attr.name);
+            let attr_uri = namespaces.resolve(prefix?)?;
+
+            (local == local_name && attr_uri == uri).then_some(attr.value.as_str())
+        })
+    }
+
+    pub fn get_attr_ns_parsed<T: FromStr>(
+        &self,
+        namespaces: &Namespaces,
+        uri: &str,
+        local_name: &str,
+    ) -> Option<T> {
+        self.get_attr_ns(namespaces, uri, local_name)?
+            .parse::<T>()
+            .ok()
+    }
+}
@@ -0,0 +1,576 @@
+use std::{borrow::Cow, collections::VecDeque};
+
+use crate::{escape::unescape, Element, Namespaces, Node, ParseError, ParseWarning};
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum Event<'a> {
+    Declaration(&'a str),
+    Doctype(&'a str),
+    Start(StartTag<'a>),
+    End(&'a str),
+    Text(Cow<'a, str>),
+    CData(&'a str),
+    Comment(&'a str),
+    ProcessingInstruction { target: &'a str, content: &'a str },
+}
+
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct StartTag<'a> {
+    pub name: &'a str,
+    pub attrs: Vec<(&'a str, Cow<'a, str>)>,
+}
+
+/// Pull parser over a borrowed XML part. Every start tag is followed by a
+/// matching end event, including self-closing ones. In lenient mode
+/// malformed markup is recovered from and reported through `warnings`
+/// instead of failing.
+pub struct XmlReader<'a> {
+    input: &'a str,
+    pos: usize,
+    stack: Vec<&'a str>,
+    preserve_space: Vec<bool>,
+    namespaces: Namespaces,
+    pending: VecDeque<Event<'a>>,
+    root_seen: bool,
+    doctype_seen: bool,
+    finished: bool,
+    lenient: bool,
+    warnings: Vec<ParseWarning>,
+}
+
+impl<'a> XmlReader<'a> {
+    pub fn new(input: &'a str) -> Self {
+        Self {
+            input,
+            // A byte order mark left by `read_to_string` is not content.
+            pos: input.len() - input.trim_start_matches('\u{feff}').len(),
+            stack: Vec::new(),
+            preserve_space: Vec::new(),
+            namespaces: Namespaces::default(),
+            pending: VecDeque::new(),
+            root_seen: false,
+            doctype_seen: false,
+            finished: false,
+            lenient: false,
+            warnings: Vec::new(),
+        }
+    }
+
+    pub fn lenient(mut self) -> Self {
+        self.lenient = true;
+        self
+    }
+
+    pub fn is_lenient(&self) -> bool {
+        self.lenient
+    }
+
+    pub fn input(&self) -> &'a str {
+        self.input
+    }
+
+    pub fn offset(&self) -> usize {
+        self.pos
+    }
+
+    /// Number of currently open elements.
+    pub fn depth(&self) -> usize {
+        self.stack.len()
+    }
+
+    /// Whether `xml:space="preserve"` is in scope for the open element.
+    pub fn preserves_space(&self) -> bool {
+        self.preserve_space.last().copied().unwrap_or(false)
+    }
+
+    /// `xmlns` declarations in scope for the open element.
+    pub fn namespaces(&self) -> &Namespaces {
+        &self.namespaces
+    }
+
+    pub fn warnings(&self) -> &[ParseWarning] {
+        &self.warnings
+    }
+
+    pub fn take_warnings(&mut self) -> Vec<ParseWarning> {
+        std::mem::take(&mut self.warnings)
+    }
+
+    pub fn next_event(&mut self) -> Result<Option<Event<'a>>, ParseError> {
+        loop {
+            if let Some(event) = self.pending.pop_front() {
+                if let Event::End(_) = event {
+                    self.stack.pop();
+                    self.preserve_space.pop();
+                    self.namespaces.pop_scope();
+                }
+                return Ok(Some(event));
+            }
+
+            if self.pos >= self.input.len() {
+                return self.finish();
+            }
+
+            let rest = self.rest();
+            let at_start = self.input[..self.pos]
+                .trim_start_matches('\u{feff}')
+                .is_empty();
+            let event = if at_start && is_declaration(rest) {
+                self.read_declaration()?
+            } else if rest.starts_with("<!--") {
+                self.read_comment()?
+            } else if rest.starts_with("<![CDATA[") {
+                self.read_cdata()?
+            } else if rest.starts_with("<!DOCTYPE") {
+                self.read_doctype()?
+            } else if rest.starts_with("<?") {
+                self.read_pi()?
+            } else if rest.starts_with("</") {
+                self.read_end_tag()?
+            } else if rest.starts_with('<') {
+                self.read_start_tag()?
+            } else {
+                self.read_text()?
+            };
+
+            if event.is_some() {
+                return Ok(event);
+            }
+        }
+    }
+
+    /// Build the element opened by `start`, consuming events up to and
+    /// including its end tag. Whitespace-only text is dropped unless
+    /// `xml:space="preserve"` is in scope.
+    pub fn read_element(&mut self, start: StartTag<'a>) -> Result<Element, ParseError> {
+        let mut stack = vec![start.to_element()];
+
+        while let Some(event) = self.next_event()? {
+            let parent = stack.last_mut().expect("element stack is never empty here");
+            match event {
+                Event::Start(tag) => stack.push(tag.to_element()),
+                Event::End(_) => {
+                    let element = stack.pop().expect("element stack is never empty here");
+                    match stack.last_mut() {
+                        Some(parent) => parent.append_element(element),
+                        None => return Ok(element),
+                    }
+                }
+                Event::Text(text) if text.trim().is_empty() && !self.preserves_space() => {}
+                Event::Text(text) => match parent.inners.last_mut() {
+                    Some(Node::Text(crate::Text(prev))) => prev.push_str(&text),
+                    _ => parent.append_text(&text),
+                },
+                Event::CData(data) => parent.append(Node::CData(crate::CData(data.to_string()))),
+                Event::Comment(comment) => {
+                    parent.append(Node::Comment(crate::Comment(comment.to_string())))
+                }
+                Event::ProcessingInstruction { target, content } => {
+                    parent.append(Node::ProcessingInstruction(crate::ProcessingInstruction {
+                        target: target.to_string(),
+                        content: content.to_string(),
+                    }))
+                }
+                Event::Declaration(_) | Event::Doctype(_) => {}
+            }
+        }
+
+        Err(ParseError::new(
+            self.input,
+            self.pos,
+            "Unexpected end of document",
+        ))
+    }
+
+    fn finish(&mut self) -> Result<Option<Event<'a>>, ParseError> {
+        if self.finished {
+            return Ok(None);
+        }
+
+        if let Some(name) = self.stack.last().copied() {
+            self.recover(
+                self.input.len(),
+                format!("Element <{}> is not closed at end of document", name),
+            )?;
+            self.pending.push_back(Event::End(name));
+            return self.next_event();
+        }
+
+        self.finished = true;
+        if !self.root_seen {
+            return Err(ParseError::new(
+                self.input,
+                self.input.len(),
+                "Document has no root element",
+            ));
+        }
+        Ok(None)
+    }
+
+    fn rest(&self) -> &'a str {
+        &self.input[self.pos..]
+    }
+
+    /// Fail in strict mode, record a warning and carry on in lenient mode.
+    fn recover(&mut self, offset: usize, message: impl ToString) -> Result<(), ParseError> {
+        let err = ParseError::new(self.input, offset, message);
+        if self.lenient {
+            self.warnings.push(err);
+            Ok(())
+        } else {
+            Err(err)
+        }
+    }
+
+    /// Slice between `open` and `close` starting at the current position,
+    /// advancing past `close` (or to the end of input if it is missing).
+    fn read_delimited(&mut self, open: &str, close: &str) -> Result<&'a str, ParseError> {
+        let start = self.pos;
+        let body_start = start + open.len();
+        match self.input[body_start..].find(close) {
+            Some(len) => {
+                self.pos = body_start + len + close.len();
+                Ok(&self.input[body_start..body_start + len])
+            }
+            None => {
+                self.recover(start, format!("Missing `{}`", close))?;
+                self.pos = self.input.len();
+                Ok(&self.input[body_start..])
+            }
+        }
+    }
+
+    fn read_declaration(&mut self) -> Result<Option<Event<'a>>, ParseError> {
+        let start = self.pos;
+        self.read_delimited("<?", "?>")?;
+        Ok(Some(Event::Declaration(&self.input[start..self.pos])))
+    }
+
+    fn read_comment(&mut self) -> Result<Option<Event<'a>>, ParseError> {
+        let comment = self.read_delimited("<!--", "-->")?;
+        Ok(Some(Event::Comment(comment)))
+    }
+
+    fn read_cdata(&mut self) -> Result<Option<Event<'a>>, ParseError> {
+        let start = self.pos;
+        let data = self.read_delimited("<![CDATA[", "]]>")?;
+        if self.stack.is_empty() {
+            self.recover(start, "CDATA outside of root element ignored")?;
+            return Ok(None);
+        }
+        Ok(Some(Event::CData(data)))
+    }
+
+    fn read_doctype(&mut self) -> Result<Option<Event<'a>>, ParseError> {
+        let start = self.pos;
+        let mut depth = 0;
+        let mut end = None;
+        for (idx, c) in self.rest().char_indices() {
+            match c {
+                '[' => depth += 1,
+                ']' => depth -= 1,
+                '>' if depth <= 0 => {
+                    end = Some(start + idx + 1);
+                    break;
+                }
+                _ => {}
+            }
+        }
+
+        let end = match end {
+            Some(end) => end,
+            None => {
+                self.recover(start, "Missing `>`")?;
+                self.input.len()
+            }
+        };
+        self.pos = end;
+
+        if self.root_seen || self.doctype_seen {
+            self.recover(
+                start,
+                "DOCTYPE is only allowed once before the root element",
+            )?;
+            return Ok(None);
+        }
+        self.doctype_seen = true;
+        Ok(Some(Event::Doctype(&self.input[start..end])))
+    }
+
+    fn read_pi(&mut self) -> Result<Option<Event<'a>>, ParseError> {
+        let start = self.pos;
+        let body = self.read_delimited("<?", "?>")?;
+        let target = take_name(body);
+        if target.is_empty() {
+            self.recover(start + 2, "Processing instruction must have a target")?;
+        }
+
+        Ok(Some(Event::ProcessingInstruction {
+            target,
+            content: body[target.len()..].trim_start(),
+        }))
+    }
+
+    fn read_end_tag(&mut self) -> Result<Option<Event<'a>>, ParseError> {
+        let start = self.pos;
+        let rest = &self.rest()[2..];
+        let name = take_name(rest);
+        let after_name = &rest[name.len()..];
+        let trimmed = after_name.trim_start();
+
+        if trimmed.starts_with('>') {
+            self.pos += 2 + rest.len() - trimmed.len() + 1;
+        } else {
+            self.recover(start, format!("Close tag </{}> is not terminated", name))?;
+            let len = rest.find(['>', '<']).unwrap_or(rest.len());
+            self.pos += 2 + len + rest[len..].starts_with('>') as usize;
+        }
+
+        match self.stack.last() {
+            Some(&open) if open == name => {
+                self.pending.push_back(Event::End(name));
+                return Ok(None);
+            }
+            Some(&open) if !self.lenient => {
+                return Err(ParseError::new(
+                    self.input,
+                    start,
+                    format!("Expected </{}>, found </{}>", open, name),
+                ))
+            }
+            None if !self.lenient => {
+                return Err(ParseError::new(
+                    self.input,
+                    start,
+                    format!("Unexpected close tag </{}>", name),
+                ))
+            }
+            _ => {}
+        }
+
+        let Some(depth) = self.stack.iter().rposition(|&open| open == name) else {
+            self.recover(start, format!("Unexpected close tag </{}> ignored", name))?;
+            return Ok(None);
+        };
+
+        for &unclosed in self.stack[depth + 1..].iter().rev() {
+            self.warnings.push(ParseError::new(
+                self.input,
+                start,
+                format!("Element <{}> closed implicitly by </{}>", unclosed, name),
+            ));
+            self.pending.push_back(Event::End(unclosed));
+        }
+        self.pending.push_back(Event::End(name));
+        Ok(None)
+    }
+
+    fn read_start_tag(&mut self) -> Result<Option<Event<'a>>, ParseError> {
+        let start = self.pos;
+        let name = take_name(&self.rest()[1..]);
+        if name.is_empty() {
+            self.recover(start, "Stray `<` treated as text")?;
+            self.pos += 1;
+            return Ok(Some(Event::Text(Cow::Borrowed("<"))));
+        }
+        self.pos += 1 + name.len();
+
+        let mut tag = StartTag {
+            name,
+            attrs: Vec::new(),
+        };
+        let mut self_closing = false;
+
+        loop {
+            let before_ws = self.pos;
+            self.skip_whitespace();
+            let rest = self.rest();
+
+            if rest.starts_with("/>") {
+                self.pos += 2;
+                self_closing = true;
+                break;
+            } else if rest.starts_with('>') {
+                self.pos += 1;
+                break;
+            } else if rest.is_empty() || rest.starts_with('<') {
+                self.recover(start, format!("Start tag <{}> is not terminated", name))?;
+                break;
+            } else if before_ws == self.pos {
+                self.recover(self.pos, format!("Expected whitespace in <{}>", name))?;
+            }
+
+            let attr_start = self.pos;
+            let attr_name = take_name(rest);
+            if attr_name.is_empty() {
+                self.recover(
+                    attr_start,
+                    format!("Unexpected character in <{}> ignored", name),
+                )?;
+                self.pos += rest.chars().next().map(char::len_utf8).unwrap_or(1);
+                continue;
+            }
+            self.pos += attr_name.len();
+
+            let value = self.read_attr_value(attr_name)?;
+            if tag.attrs.iter().any(|(name, _)| *name == attr_name) {
+                self.recover(
+                    attr_start,
+                    format!("Duplicate attribute {:?} ignored", attr_name),
+                )?;
+            } else {
+                tag.attrs.push((attr_name, value));
+            }
+        }
+
+        if self.stack.is_empty() {
+            if self.root_seen {
+                self.recover(
+                    start,
+                    format!("Element <{}> after the root element moved into it", name),
+                )?;
+            }
+            self.root_seen = true;
+        }
+
+        let preserve_space = match tag.attr("xml:space") {
+            Some("preserve") => true,
+            Some("default") => false,
+            _ => self.preserves_space(),
+        };
+        self.stack.push(name);
+        self.preserve_space.push(preserve_space);
+        self.namespaces
+            .push_declarations(tag.attrs.iter().map(|(name, value)| (*name, value.as_ref())));
+        if self_closing {
+            self.pending.push_back(Event::End(name));
+        }
+        Ok(Some(Event::Start(tag)))
+    }
+
+    fn read_attr_value(&mut self, attr_name: &str) -> Result<Cow<'a, str>, ParseError> {
+        let start = self.pos;
+        self.skip_whitespace();
+        if !self.rest().starts_with('=') {
+            self.recover(start, format!("Attribute {:?} has no value", attr_name))?;
+            return Ok(Cow::Borrowed(""));
+        }
+        self.pos += 1;
+        self.skip_whitespace();
+
+        let value_start = self.pos;
+        let rest = self.rest();
+        let (raw_start, raw) = match rest.chars().next() {
+            Some(quote @ ('"' | '\'')) => match rest[1..].find([quote, '<']) {
+                Some(len) if rest.as_bytes()[len + 1] == quote as u8 => {
+                    self.pos += len + 2;
+                    (value_start + 1, &rest[1..len + 1])
+                }
+                _ => {
+                    self.recover(
+                        value_start,
+                        format!("Attribute {:?} value is not terminated", attr_name),
+                    )?;
+                    let len = rest[1..].find(['>', '<']).unwrap_or(rest.len() - 1);
+                    self.pos += len + 1;
+                    (value_start + 1, &rest[1..len + 1])
+                }
+            },
+            _ => {
+                self.recover(
+                    value_start,
+                    format!("Attribute {:?} value is not quoted", attr_name),
+                )?;
+                let len = rest
+                    .find(|c: char| c.is_whitespace() || c == '>' || c == '<')
+                    .unwrap_or(rest.len());
+                self.pos += len;
+                (value_start, &rest[..len])
+            }
+        };
+
+        self.decode(raw_start, raw).map_err(|err| {
+            ParseError::new(
+                self.input,
+                raw_start,
+                format!(
+                    "Invalid value of attribute {:?}: {}",
+                    attr_name, err.message
+                ),
+            )
+        })
+    }
+
+    fn read_text(&mut self) -> Result<Option<Event<'a>>, ParseError> {
+        let start = self.pos;
+        let len = self.rest().find('<').unwrap_or(self.rest().len());
+        let raw = &self.rest()[..len];
+        self.pos += len;
+
+        if self.stack.is_empty() {
+            if !raw.trim().is_empty() {
+                self.recover(start, "Text outside of root element ignored")?;
+            }
+            return Ok(None);
+        }
+
+        Ok(Some(Event::Text(self.decode(start, raw)?)))
+    }
+
+    fn decode(&mut self, offset: usize, raw: &'a str) -> Result<Cow<'a, str>, ParseError> {
+        match unescape(raw) {
+            Ok(decoded) => Ok(decoded),
+            Err(err) if self.lenient => {
+                self.recover(offset, format!("{}; kept as is", err))?;
+                Ok(Cow::Borrowed(raw))
+            }
+            Err(err) => Err(ParseError::new(self.input, offset, err)),
+        }
+    }
+
+    fn skip_whitespace(&mut self) {
+        let rest = self.rest();
+        self.pos += rest.len() - rest.trim_start().len();
+    }
+}
+
+impl<'a> Iterator for XmlReader<'a> {
+    type Item = Result<Event<'a>, ParseError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.next_event().transpose()
+    }
+}
+
+impl StartTag<'_> {
+    pub fn attr(&self, name: &str) -> Option<&str> {
+        self.attrs
+            .iter()
+            .find_map(|(attr, value)| (*attr == name).then_some(value.as_ref()))
+    }
+
+    pub fn to_element(&self) -> Element {
+        let mut element = Element::new(self.name);
+        for (name, value) in &self.attrs {
+            element.append_attr(name, value);
+        }
+        element
+    }
+}
+
+fn is_declaration(s: &str) -> bool {
+    s.starts_with("<?xml") && s[5..].starts_with(|c: char| c.is_whitespace() || c == '?')
+}
+
+fn take_name(s: &str) -> &str {
+    let len = s
+        .char_indices()
+        .find(|&(idx, c)| {
+            let name_start = c.is_alphabetic() || c == '_';
+            let name_char = c.is_ascii_digit() || matches!(c, '-' | '.' | ':');
+            !(name_start || (idx > 0 && name_char))
+        })
+        .map(|(idx, _)| idx)
+        .unwrap_or(s.len());
+    &s[..len]
+}
@@ -0,0 +1,182 @@
+use std::str::FromStr;
+
+use anyhow::{anyhow, bail};
+
+use crate::Element;
+
+/// Small CSS-like selector over element names and attributes.
+///
+/// `w:p > w:r[w:rsidR]` selects `w:r` children of `w:p` that have a
+/// `w:rsidR` attribute, `w:body w:t` selects every `w:t` below `w:body`,
+/// `*` matches any name and `[name=value]` (or `[name="value"]`) also
+/// compares the attribute value.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Selector {
+    steps: Vec<(Combinator, Compound)>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Combinator {
+    Descendant,
+    Child,
+}
+
+#[derive(Debug, Clone, PartialEq, Default)]
+struct Compound {
+    name: Option<String>,
+    attrs: Vec<(String, Option<String>)>,
+}
+
+impl Compound {
+    fn matches(&self, element: &Element) -> bool {
+        self.name.as_ref().is_none_or(|name| *name == element.name)
+            && self
+                .attrs
+                .iter()
+                .all(|(name, value)| match (element.get_attr(name), value) {
+                    (Some(actual), Some(value)) => actual == value,
+                    (Some(_), None) => true,
+                    (None, _) => false,
+                })
+    }
+}
+
+impl Selector {
+    /// Whether `element`, with `ancestors` listed from the outermost one,
+    /// is matched by this selector.
+    pub fn matches(&self, element: &Element, ancestors: &[&Element]) -> bool {
+        self.matches_step(self.steps.len() - 1, element, ancestors)
+    }
+
+    fn matches_step(&self, step: usize, element: &Element, ancestors: &[&Element]) -> bool {
+        let (combinator, compound) = &self.steps[step];
+        if !compound.matches(element) {
+            return false;
+        }
+        if step == 0 {
+            return true;
+        }
+
+        match combinator {
+            Combinator::Child => ancestors
+                .split_last()
+                .is_some_and(|(parent, rest)| self.matches_step(step - 1, parent, rest)),
+            Combinator::Descendant => (0..ancestors.len())
+                .rev()
+                .any(|idx| self.matches_step(step - 1, ancestors[idx], &ancestors[..idx])),
+        }
+    }
+}
+
+impl FromStr for Selector {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut steps = Vec::new();
+        let mut rest = s.trim();
+        let mut combinator = Combinator::Descendant;
+
+        while !rest.is_empty() {
+            if let Some(after) = rest.strip_prefix('>') {
+                if steps.is_empty() || combinator == Combinator::Child {
+                    bail!("Unexpected `>` in selector {:?}", s);
+                }
+                combinator = Combinator::Child;
+                rest = after.trim_start();
+                continue;
+            }
+
+            let (compound, after) =
+                parse_compound(rest).map_err(|err| anyhow!("Invalid selector {:?}: {}", s, err))?;
+            steps.push((combinator, compound));
+            combinator = Combinator::Descendant;
+
+            let trimmed = after.trim_start();
+            if trimmed.len() == after.len() && !trimmed.is_empty() && !trimmed.starts_with('>') {
+                bail!("Invalid selector {:?}: unexpected {:?}", s, trimmed);
+            }
+            rest = trimmed;
+        }
+
+        if steps.is_empty() || combinator == Combinator::Child {
+            bail!("Selector {:?} is incomplete", s);
+        }
+        Ok(Self { steps })
+    }
+}
+
+fn parse_compound(s: &str) -> anyhow::Result<(Compound, &str)> {
+    let name_len = s
+        .find(|c: char| c.is_whitespace() || c == '>' || c == '[')
+        .unwrap_or(s.len());
+    let mut compound = Compound {
+        name: match &s[..name_len] {
+            "*" => None,
+            "" if s.starts_with('[') => None,
+            "" => bail!("expected element name at {:?}", s),
+            name => Some(name.to_string()),
+        },
+        attrs: Vec::new(),
+    };
+
+    let mut rest = &s[name_len..];
+    while let Some(after) = rest.strip_prefix('[') {
+        let end = after
+            .find(']')
+            .ok_or_else(|| anyhow!("missing `]` after {:?}", after))?;
+        let (name, value) = match after[..end].split_once('=') {
+            Some((name, value)) => {
+                let value = value.trim();
+                let value = value
+                    .strip_prefix('"')
+                    .and_then(|value| value.strip_suffix('"'))
+                    .or_else(|| {
+                        value
+                            .strip_prefix('\'')
+                            .and_then(|value| value.strip_suffix('\''))
+                    })
+                    .unwrap_or(value);
+                (name.trim(), Some(value.to_string()))
+            }
+            None => (after[..end].trim(), None),
+        };
+        if name.is_empty() {
+            bail!("empty attribute name in [{}]", &after[..end]);
+        }
+
+        compound.attrs.push((name.to_string(), value));
+        rest = &after[end + 1..];
+    }
+
+    Ok((compound, rest))
+}
+
+impl Element {
+    /// Descendants of this element matched by `selector`, in document order.
+    pub fn select(&self, selector: &str) -> anyhow::Result<Vec<&Element>> {
+        Ok(self.select_with(&selector.parse()?))
+    }
+
+    pub fn select_with<'a>(&'a self, selector: &Selector) -> Vec<&'a Element> {
+        let mut found = Vec::new();
+        let mut ancestors = vec![self];
+        select_in(self, selector, &mut ancestors, &mut found);
+        found
+    }
+}
+
+fn select_in<'a>(
+    element: &'a Element,
+    selector: &Selector,
+    ancestors: &mut Vec<&'a Element>,
+    found: &mut Vec<&'a Element>,
+) {
+    for child in element.inners.iter().filter_map(crate::Node::get_element) {
+        if selector.matches(child, ancestors) {
+            found.push(child);
+        }
+        ancestors.push(child);
+        select_in(child, selector, ancestors, found);
+        ancestors.pop();
+    }
+}
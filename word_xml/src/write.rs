@@ -1,29 +1,246 @@
 use std::io::Write;
 
+use crate::{
+    escape::{escape_attr, escape_text},
+    namespaces::split_qname,
+    Namespaces,
+};
+
+/// How a document is serialized. The default writes everything on one line
+/// exactly as stored.
+///
+/// Indentation is only added between children of element-only content, so
+/// text of `w:t` and any other mixed content stays byte-exact.
+/// `canonical` produces C14N-style output: no declaration or DOCTYPE, no
+/// comments, CDATA written as text, explicit end tags and attributes ordered
+/// by namespace URI and local name after the namespace declarations.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct WriteOptions {
+    pub indent: Option<String>,
+    pub self_closing: bool,
+    pub sort_attrs: bool,
+    pub canonical: bool,
+}
+
+impl WriteOptions {
+    pub fn pretty() -> Self {
+        Self {
+            indent: Some("  ".to_string()),
+            self_closing: true,
+            sort_attrs: true,
+            canonical: false,
+        }
+    }
+
+    pub fn canonical() -> Self {
+        Self {
+            canonical: true,
+            ..Default::default()
+        }
+    }
+
+    pub fn with_indent(mut self, indent: &str) -> Self {
+        self.indent = Some(indent.to_string());
+        self
+    }
+}
+
+struct Context<'a> {
+    options: &'a WriteOptions,
+    /// Declarations in scope for the element being written.
+    namespaces: Namespaces,
+}
+
 impl super::WordXMLDocument {
     pub fn write_to(&self, writer: &mut impl Write) -> anyhow::Result<()> {
-        writeln!(writer, "{}", self.header)?;
-        self.root.write_to(writer)
+        self.write_with(writer, &WriteOptions::default())
+    }
+
+    pub fn write_with(
+        &self,
+        writer: &mut impl Write,
+        options: &WriteOptions,
+    ) -> anyhow::Result<()> {
+        // Every element opens its own scope when it is written.
+        let mut ctx = Context {
+            options,
+            namespaces: Namespaces::default(),
+        };
+
+        if !options.canonical {
+            if !self.header.is_empty() {
+                writeln!(writer, "{}", self.header)?;
+            }
+            if let Some(doctype) = &self.doctype {
+                writeln!(writer, "{}", doctype)?;
+            }
+        }
+        for node in &self.prolog {
+            if !ctx.keeps(node) {
+                continue;
+            }
+            write_node(node, writer, &mut ctx, 0, true)?;
+            writeln!(writer)?;
+        }
+
+        write_element(&self.root, writer, &mut ctx, 0, false)?;
+
+        for node in &self.epilog {
+            if !ctx.keeps(node) {
+                continue;
+            }
+            writeln!(writer)?;
+            write_node(node, writer, &mut ctx, 0, true)?;
+        }
+        Ok(())
     }
 }
 
 impl super::Element {
     pub fn write_to(&self, writer: &mut impl Write) -> anyhow::Result<()> {
-        write!(writer, "<{}", self.name)?;
-        for super::Attr { name, value } in &self.attrs {
-            write!(writer, " {}={:?}", name, value)?;
+        self.write_with(writer, &WriteOptions::default())
+    }
+
+    pub fn write_with(
+        &self,
+        writer: &mut impl Write,
+        options: &WriteOptions,
+    ) -> anyhow::Result<()> {
+        let mut ctx = Context {
+            options,
+            namespaces: Namespaces::default(),
+        };
+        write_element(self, writer, &mut ctx, 0, false)
+    }
+}
+
+impl super::Node {
+    pub fn write_to(&self, writer: &mut impl Write) -> anyhow::Result<()> {
+        let mut ctx = Context {
+            options: &WriteOptions::default(),
+            namespaces: Namespaces::default(),
+        };
+        write_node(self, writer, &mut ctx, 0, true)
+    }
+}
+
+impl Context<'_> {
+    fn keeps(&self, node: &super::Node) -> bool {
+        !(self.options.canonical && node.is_comment())
+    }
+
+    fn ordered_attrs<'b>(&self, element: &'b super::Element) -> Vec<&'b super::Attr> {
+        let mut attrs = element.attrs.iter().collect::<Vec<_>>();
+        if self.options.canonical {
+            attrs.sort_by_cached_key(|attr| match ns_declaration(attr) {
+                Some(prefix) => (false, String::new(), prefix.to_string()),
+                None => {
+                    let (prefix, local) = split_qname(&attr.name);
+                    let uri = prefix.and_then(|prefix| self.namespaces.resolve(prefix));
+                    (true, uri.unwrap_or_default().to_string(), local.to_string())
+                }
+            });
+        } else if self.options.sort_attrs {
+            attrs.sort_by_key(|attr| (ns_declaration(attr).is_none(), attr.name.as_str()));
+        }
+        attrs
+    }
+
+    fn write_indent(&self, writer: &mut impl Write, depth: usize) -> anyhow::Result<()> {
+        if let Some(indent) = &self.options.indent {
+            write!(writer, "\n{}", indent.repeat(depth))?;
+        }
+        Ok(())
+    }
+}
+
+fn ns_declaration(attr: &super::Attr) -> Option<&str> {
+    match attr.name.as_str() {
+        "xmlns" => Some(""),
+        name => name.strip_prefix("xmlns:"),
+    }
+}
+
+fn write_element(
+    element: &super::Element,
+    writer: &mut impl Write,
+    ctx: &mut Context,
+    depth: usize,
+    inline: bool,
+) -> anyhow::Result<()> {
+    ctx.namespaces.push_scope(element);
+    write!(writer, "<{}", element.name)?;
+    for super::Attr { name, value } in ctx.ordered_attrs(element) {
+        write!(writer, " {}=\"{}\"", name, escape_attr(value))?;
+    }
+
+    let inners = element
+        .inners
+        .iter()
+        .filter(|node| ctx.keeps(node))
+        .collect::<Vec<_>>();
+
+    if inners.is_empty() && ctx.options.self_closing && !ctx.options.canonical {
+        write!(writer, "/>")?;
+        ctx.namespaces.pop_scope();
+        return Ok(());
+    }
+    write!(writer, ">")?;
+
+    let inline = inline
+        || ctx.options.indent.is_none()
+        || ctx.options.canonical
+        || element.get_attr("xml:space") == Some("preserve")
+        || inners.iter().any(|node| node.is_text() || node.is_cdata());
+
+    for node in &inners {
+        if !inline {
+            ctx.write_indent(writer, depth + 1)?;
         }
-        write!(writer, ">")?;
+        write_node(node, writer, ctx, depth + 1, inline)?;
+    }
+    if !inline && !inners.is_empty() {
+        ctx.write_indent(writer, depth)?;
+    }
+
+    write!(writer, "</{}>", element.name)?;
+    ctx.namespaces.pop_scope();
+
+    Ok(())
+}
 
-        for node in &self.inners {
-            match node {
-                crate::Node::Element(elem) => elem.write_to(writer)?,
-                crate::Node::Text(super::Text(txt)) => write!(writer, "{}", txt)?,
+fn write_node(
+    node: &super::Node,
+    writer: &mut impl Write,
+    ctx: &mut Context,
+    depth: usize,
+    inline: bool,
+) -> anyhow::Result<()> {
+    match node {
+        super::Node::Element(elem) => write_element(elem, writer, ctx, depth, inline)?,
+        super::Node::Text(super::Text(txt)) => write_text(txt, writer, ctx)?,
+        super::Node::Comment(super::Comment(comment)) => write!(writer, "<!--{}-->", comment)?,
+        super::Node::CData(super::CData(data)) if ctx.options.canonical => {
+            write_text(data, writer, ctx)?
+        }
+        super::Node::CData(super::CData(data)) => write!(writer, "<![CDATA[{}]]>", data)?,
+        super::Node::ProcessingInstruction(super::ProcessingInstruction { target, content }) => {
+            if content.is_empty() {
+                write!(writer, "<?{}?>", target)?
+            } else {
+                write!(writer, "<?{} {}?>", target, content)?
             }
         }
+    }
 
-        write!(writer, "</{}>", self.name)?;
+    Ok(())
+}
 
-        Ok(())
+fn write_text(txt: &str, writer: &mut impl Write, ctx: &Context) -> anyhow::Result<()> {
+    if ctx.options.canonical {
+        write!(writer, "{}", escape_text(txt).replace('\r', "&#xD;"))?;
+    } else {
+        write!(writer, "{}", escape_text(txt))?;
     }
+    Ok(())
 }